@@ -0,0 +1,225 @@
+//! Iterator adapters that group items into multimaps and multisets.
+//!
+//! [`GroupByExt`] is implemented for every [`Iterator`]. Each method is
+//! available when the feature of the collection it builds is enabled.
+//!
+//! Multimaps with `Vec` values store the items themselves, multimaps with set
+//! values store the result of a value function as the items are typically not
+//! hashable or ordered themselves.
+//!
+//! ```
+//! # #[cfg(feature = "hashvecmultimap")]
+//! # {
+//! use more_collections::GroupByExt;
+//!
+//! let words = ["apple", "avocado", "banana"];
+//! let by_initial = words
+//!     .into_iter()
+//!     .into_hash_vec_multimap_by(|word| word.chars().next().unwrap());
+//! assert_eq!(Some(&vec!["apple", "avocado"]), by_initial.get(&'a'));
+//! # }
+//! ```
+//!
+//! The collections are not sized from [`Iterator::size_hint()`], which counts
+//! items rather than distinct keys. They start empty and only grow when a new
+//! key is inserted.
+
+#[cfg(any(
+    feature = "hashmultiset",
+    feature = "hashsetmultimap",
+    feature = "hashvecmultimap",
+    feature = "indexmultiset",
+    feature = "indexsetmultimap",
+    feature = "indexvecmultimap",
+    feature = "vecsetmultimap"
+))]
+use std::hash::Hash;
+
+/// Extension trait on [`Iterator`] that groups items by key into a multimap or
+/// counts them by key into a multiset.
+///
+/// See the [module documentation](crate::group_by) for more details.
+pub trait GroupByExt: Iterator {
+    /// Groups the items into a [`HashVecMultimap`](crate::HashVecMultimap)
+    /// keyed by `key_fn`, preserving the iteration order of the items per key.
+    #[cfg(feature = "hashvecmultimap")]
+    fn into_hash_vec_multimap_by<K, F>(self, mut key_fn: F) -> crate::HashVecMultimap<K, Self::Item>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        Self::Item: Eq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.map(|item| (key_fn(&item), item)).collect()
+    }
+
+    /// Groups the values produced by `value_fn` into a
+    /// [`HashSetMultimap`](crate::HashSetMultimap) keyed by `key_fn`.
+    #[cfg(feature = "hashsetmultimap")]
+    fn into_hash_set_multimap_by<K, V, F, G>(
+        self,
+        mut key_fn: F,
+        mut value_fn: G,
+    ) -> crate::HashSetMultimap<K, V>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        V: Hash + Eq,
+        F: FnMut(&Self::Item) -> K,
+        G: FnMut(Self::Item) -> V,
+    {
+        self.map(|item| (key_fn(&item), value_fn(item))).collect()
+    }
+
+    /// Groups the items into an [`IndexVecMultimap`](crate::IndexVecMultimap)
+    /// keyed by `key_fn`, keys are ordered by their first occurrence.
+    #[cfg(feature = "indexvecmultimap")]
+    fn into_index_vec_multimap_by<K, F>(
+        self,
+        mut key_fn: F,
+    ) -> crate::IndexVecMultimap<K, Self::Item>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        Self::Item: Eq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.map(|item| (key_fn(&item), item)).collect()
+    }
+
+    /// Groups the values produced by `value_fn` into an
+    /// [`IndexSetMultimap`](crate::IndexSetMultimap) keyed by `key_fn`, keys
+    /// are ordered by their first occurrence.
+    #[cfg(feature = "indexsetmultimap")]
+    fn into_index_set_multimap_by<K, V, F, G>(
+        self,
+        mut key_fn: F,
+        mut value_fn: G,
+    ) -> crate::IndexSetMultimap<K, V>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        V: Hash + Eq,
+        F: FnMut(&Self::Item) -> K,
+        G: FnMut(Self::Item) -> V,
+    {
+        self.map(|item| (key_fn(&item), value_fn(item))).collect()
+    }
+
+    /// Groups the items into a [`BTreeVecMultimap`](crate::BTreeVecMultimap)
+    /// keyed by `key_fn`.
+    #[cfg(feature = "btreevecmultimap")]
+    fn into_btree_vec_multimap_by<K, F>(
+        self,
+        mut key_fn: F,
+    ) -> crate::BTreeVecMultimap<K, Self::Item>
+    where
+        Self: Sized,
+        K: Ord,
+        Self::Item: Eq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.map(|item| (key_fn(&item), item)).collect()
+    }
+
+    /// Groups the values produced by `value_fn` into a
+    /// [`BTreeSetMultimap`](crate::BTreeSetMultimap) keyed by `key_fn`.
+    #[cfg(feature = "btreesetmultimap")]
+    fn into_btree_set_multimap_by<K, V, F, G>(
+        self,
+        mut key_fn: F,
+        mut value_fn: G,
+    ) -> crate::BTreeSetMultimap<K, V>
+    where
+        Self: Sized,
+        K: Ord,
+        V: Ord,
+        F: FnMut(&Self::Item) -> K,
+        G: FnMut(Self::Item) -> V,
+    {
+        self.map(|item| (key_fn(&item), value_fn(item))).collect()
+    }
+
+    /// Groups the items into a [`VecVecMultimap`](crate::VecVecMultimap) keyed
+    /// by the [`IndexKey`](crate::IndexKey) produced by `key_fn`.
+    #[cfg(feature = "vecvecmultimap")]
+    fn into_vec_vec_multimap_by<K, F>(self, mut key_fn: F) -> crate::VecVecMultimap<K, Self::Item>
+    where
+        Self: Sized,
+        K: crate::IndexKey,
+        Self::Item: Eq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.map(|item| (key_fn(&item), item)).collect()
+    }
+
+    /// Groups the values produced by `value_fn` into a
+    /// [`VecSetMultimap`](crate::VecSetMultimap) keyed by the
+    /// [`IndexKey`](crate::IndexKey) produced by `key_fn`.
+    #[cfg(feature = "vecsetmultimap")]
+    fn into_vec_set_multimap_by<K, V, F, G>(
+        self,
+        mut key_fn: F,
+        mut value_fn: G,
+    ) -> crate::VecSetMultimap<K, V>
+    where
+        Self: Sized,
+        K: crate::IndexKey,
+        V: Hash + Eq,
+        F: FnMut(&Self::Item) -> K,
+        G: FnMut(Self::Item) -> V,
+    {
+        self.map(|item| (key_fn(&item), value_fn(item))).collect()
+    }
+
+    /// Counts the items by the key produced by `key_fn` in a
+    /// [`HashMultiset`](crate::HashMultiset).
+    #[cfg(feature = "hashmultiset")]
+    fn into_hash_multiset_by<K, F>(self, key_fn: F) -> crate::HashMultiset<K>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        F: FnMut(Self::Item) -> K,
+    {
+        self.map(key_fn).collect()
+    }
+
+    /// Counts the items by the key produced by `key_fn` in an
+    /// [`IndexMultiset`](crate::IndexMultiset), keys are ordered by their
+    /// first occurrence.
+    #[cfg(feature = "indexmultiset")]
+    fn into_index_multiset_by<K, F>(self, key_fn: F) -> crate::IndexMultiset<K>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        F: FnMut(Self::Item) -> K,
+    {
+        self.map(key_fn).collect()
+    }
+
+    /// Counts the items by the key produced by `key_fn` in a
+    /// [`BTreeMultiset`](crate::BTreeMultiset).
+    #[cfg(feature = "btreemultiset")]
+    fn into_btree_multiset_by<K, F>(self, key_fn: F) -> crate::BTreeMultiset<K>
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(Self::Item) -> K,
+    {
+        self.map(key_fn).collect()
+    }
+
+    /// Counts the items by the [`IndexKey`](crate::IndexKey) produced by
+    /// `key_fn` in a [`VecMultiset`](crate::VecMultiset).
+    #[cfg(feature = "vecmultiset")]
+    fn into_vec_multiset_by<K, F>(self, key_fn: F) -> crate::VecMultiset<K>
+    where
+        Self: Sized,
+        K: crate::IndexKey,
+        F: FnMut(Self::Item) -> K,
+    {
+        self.map(key_fn).collect()
+    }
+}
+
+impl<I> GroupByExt for I where I: Iterator {}
@@ -6,7 +6,9 @@
 //! crate, [`SmallMap`] and [`SmallSet`] are a `Map` and `Set` respectively that
 //! are inlined if they contain fewer values than a (statically chosen)
 //! capacity `C`, otherwise they are heap allocated and backed by an
-//! `IndexMap`.
+//! `IndexMap`. [`SmallSortedMap`] and [`SmallSortedSet`] are the sorted
+//! counterparts, they keep their data sorted inline and are backed by a
+//! `BTreeMap` when they exceed their capacity.
 //!
 //! # `VecMap`
 //!
@@ -34,24 +36,100 @@
 //! | [`HashVecMultimap`]  | [`HashMap`]`<K,`[`Vec`]`<V>>`       | Arbitrary order     | Insertion order[^1] | Yes                    |
 //! | [`IndexSetMultimap`] | [`IndexMap`]`<K,`[`IndexSet`]`<V>>` | Insertion order[^1] | Insertion order[^1] | No                     |
 //! | [`IndexVecMultimap`] | [`IndexMap`]`<K, `[`Vec`]`<V>>`     | Insertion order[^1] | Insertion order[^1] | Yes                    |
+//! | [`HashSmallSetMultimap`] | [`HashMap`]`<K,`[`SmallSet`]`<V, C>>` | Arbitrary order | Insertion order[^1] | No                  |
+//! | [`IndexSmallVecMultimap`] | [`IndexMap`]`<K,`[`SmallVec`]`<[V; C]>>` | Insertion order[^1] | Insertion order[^1] | Yes        |
+//! | [`BTreeSetMultimap`] | [`BTreeMap`]`<K,`[`BTreeSet`]`<V>>` | Sorted              | Sorted              | No                     |
+//! | [`BTreeVecMultimap`] | [`BTreeMap`]`<K,`[`Vec`]`<V>>`      | Sorted              | Insertion order     | Yes                    |
+//! | [`EnumHashSetMultimap`] | `[`[`HashSet`]`<V>; N]` | Index order[^2]         | Arbitrary order     | No                     |
+//! | [`EnumIndexSetMultimap`] | `[`[`IndexSet`]`<V>; N]` | Index order[^2]       | Insertion order[^1] | No                  |
+//! | [`EnumVecMultimap`]  | `[`[`Vec`]`<V>; N]`                 | Index order[^2]     | Insertion order     | Yes                    |
+//! | [`VecSetMultimap`]   | [`VecMap`]`<K,`[`HashSet`]`<V>>`    | Index order[^2]     | Arbitrary order     | No                     |
+//! | [`VecVecMultimap`]   | [`VecMap`]`<K,`[`Vec`]`<V>>`        | Index order[^2]     | Insertion order     | Yes                    |
 //!
 //! [^1]: Insertion order is preserved, unless `remove()` or `swap_remove()`
 //! is called. See more in the [IndexMap](https://docs.rs/indexmap/1.7.0/indexmap/map/struct.IndexMap.html#order) documentation.
 //!
+//! [^2]: Keys are iterated in the order of [`IndexKey::as_index()`]. For the
+//! `Enum*` multimaps keys must implement [`EnumKey`] and a value collection
+//! for each of the `N` variants is allocated upfront, in a boxed slice indexed
+//! by [`IndexKey::as_index()`].
+//!
+//! The `*Small*` multimaps store up to `C` values per key inline, which avoids
+//! an allocation per key when most keys have only a few values.
+//!
+//! The [`HashSetMultimap`], [`HashVecMultimap`], [`IndexSetMultimap`] and
+//! [`IndexVecMultimap`] can be converted into each other with `From`, and
+//! compared to each other with `==`. Converting into a set multimap removes
+//! duplicate values. Two list multimaps are equal when they hold the same
+//! pairs with the same multiplicities, a list and a set multimap are equal
+//! when they hold the same set of pairs, ignoring duplicates.
+//!
+//! All multimaps implement the [`Multimap`] trait, and either [`SetMultimap`]
+//! or [`ListMultimap`] depending on whether they may contain duplicates. Use
+//! these traits to write code that is generic over the multimap
+//! implementation.
+//!
+//! [`BiMultimap`] is an [`IndexSetMultimap`] that maintains a reverse index,
+//! both [`BiMultimap::get_by_key()`] and [`BiMultimap::get_by_value()`] are
+//! O(1).
+//!
+//! The [`GroupByExt`] iterator extension groups items by key into a multimap
+//! or counts them into a multiset, see [`group_by`].
+//!
+//! # Multiset
+//!
+//! A collection that counts the number of occurrences of each value, also
+//! known as a bag. A multiset containing `a, a, b` has `len() == 3` and
+//! `distinct_len() == 2`.
+//!
+//! | Name               | Behaves as                      | Values order        |
+//! | ------------------ | ------------------------------- | ------------------- |
+//! | [`HashMultiset`]   | [`HashMap`]`<T, usize>`         | Arbitrary order     |
+//! | [`IndexMultiset`]  | [`IndexMap`]`<T, usize>`        | Insertion order     |
+//! | [`BTreeMultiset`]  | [`BTreeMap`]`<T, usize>`        | Sorted              |
+//! | [`VecMultiset`]    | [`VecMap`]`<K, usize>`          | Index order[^2]     |
+//!
 //! # Crate features
 //! All features are _disabled_ by default. The options are:
+//! - `bimultimap`
+//! - `btreemultiset`
+//! - `btreesetmultimap`
+//! - `btreevecmultimap`
+//! - `enumhashsetmultimap`
+//! - `enumindexsetmultimap`
+//! - `enumvecmultimap`
+//! - `hashmultiset`
 //! - `hashsetmultimap`
+//! - `hashsmallsetmultimap`
 //! - `hashvecmultimap`
+//! - `indexmultiset`
 //! - `indexsetmultimap`
+//! - `indexsmallvecmultimap`
 //! - `indexvecmultimap`
+//! - `vecmultiset`
+//! - `vecsetmultimap`
+//! - `vecvecmultimap`
+//!
+//! The `serde` feature implements `Serialize` and `Deserialize` for
+//! `SmallMap`, `SmallSet`, `SmallSortedMap`, `SmallSortedSet`, `VecMap` and all
+//! multimaps except `BiMultimap`.
 //!
+//! [`BTreeMap`]: std::collections::BTreeMap
+//! [`BTreeSet`]: std::collections::BTreeSet
 //! [`HashMap`]: std::collections::HashMap
 //! [`HashSet`]: std::collections::HashSet
 //! [`IndexMap`]: indexmap::IndexMap
 //! [`IndexSet`]: indexmap::IndexSet
+//! [`SmallVec`]: smallvec::SmallVec
 //! [`Vec`]: std::vec::Vec
 
+pub mod group_by;
 mod multimap;
+mod multiset;
+pub use group_by::GroupByExt;
+pub use multimap::ListMultimap;
+pub use multimap::Multimap;
+pub use multimap::SetMultimap;
 #[cfg(all(feature = "indexmap", feature = "smallvec", feature = "smallmap"))]
 pub mod small_map;
 #[cfg(all(
@@ -61,17 +139,42 @@ pub mod small_map;
     feature = "smallset"
 ))]
 pub mod small_set;
+#[cfg(all(feature = "smallvec", feature = "smallsortedmap"))]
+pub mod small_sorted_map;
+#[cfg(all(
+    feature = "smallvec",
+    feature = "smallsortedmap",
+    feature = "smallsortedset"
+))]
+pub mod small_sorted_set;
 
 #[cfg(feature = "vecmap")]
 pub mod vec_map;
 
 #[cfg(any(
+    feature = "bimultimap",
+    feature = "btreesetmultimap",
+    feature = "btreevecmultimap",
+    feature = "enumhashsetmultimap",
+    feature = "enumindexsetmultimap",
+    feature = "enumvecmultimap",
     feature = "hashsetmultimap",
+    feature = "hashsmallsetmultimap",
     feature = "hashvecmultimap",
     feature = "indexvecmultimap",
-    feature = "indexsetmultimap"
+    feature = "indexsetmultimap",
+    feature = "indexsmallvecmultimap",
+    feature = "vecsetmultimap",
+    feature = "vecvecmultimap"
 ))]
 pub use multimap::*;
+#[cfg(any(
+    feature = "btreemultiset",
+    feature = "hashmultiset",
+    feature = "indexmultiset",
+    feature = "vecmultiset"
+))]
+pub use multiset::*;
 #[cfg(all(feature = "indexmap", feature = "smallvec", feature = "smallmap"))]
 pub use small_map::SmallMap;
 #[cfg(all(
@@ -81,6 +184,16 @@ pub use small_map::SmallMap;
     feature = "smallset"
 ))]
 pub use small_set::SmallSet;
+#[cfg(all(feature = "smallvec", feature = "smallsortedmap"))]
+pub use small_sorted_map::SmallSortedMap;
+#[cfg(all(
+    feature = "smallvec",
+    feature = "smallsortedmap",
+    feature = "smallsortedset"
+))]
+pub use small_sorted_set::SmallSortedSet;
+#[cfg(feature = "vecmap")]
+pub use vec_map::EnumKey;
 #[cfg(feature = "vecmap")]
 pub use vec_map::IndexKey;
 #[cfg(feature = "vecmap")]
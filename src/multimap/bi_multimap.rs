@@ -0,0 +1,494 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+use indexmap::Equivalent;
+use indexmap::IndexSet;
+
+use crate::IndexSetMultimap;
+
+/// Multimap implementation that maintains a reverse index, it behaves like a
+/// pair of `IndexMap<K, IndexSet<V>>` and `IndexMap<V, IndexSet<K>>` that are
+/// always in sync.
+///
+/// Both [`Self::get_by_key()`] and [`Self::get_by_value()`] are O(1). Every
+/// key and value is stored twice, `K` and `V` must therefore be `Clone`.
+///
+/// Removals preserve the insertion order of the remaining keys and values,
+/// like [`IndexSetMultimap::shift_remove()`].
+#[derive(Debug, Clone)]
+pub struct BiMultimap<K, V, S = RandomState> {
+    forward: IndexSetMultimap<K, V, S>,
+    backward: IndexSetMultimap<V, K, S>,
+}
+
+impl<K, V> BiMultimap<K, V> {
+    /// Creates an empty multimap.
+    ///
+    /// The multimap is initially created with a capacity of 0, so it will not
+    /// allocate until it is first inserted into.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            forward: IndexSetMultimap::new(),
+            backward: IndexSetMultimap::new(),
+        }
+    }
+}
+
+impl<K, V, S> BiMultimap<K, V, S> {
+    /// Returns the number of key-value pairs in the multimap.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    /// Returns `true` if the multimap contains no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    /// Returns the number of distinct keys in the multimap.
+    #[must_use]
+    pub fn keys_len(&self) -> usize {
+        self.forward.keys_len()
+    }
+
+    /// Returns the number of distinct values in the multimap.
+    #[must_use]
+    pub fn values_len(&self) -> usize {
+        self.backward.keys_len()
+    }
+
+    /// Return a borrow of the multimap from keys to values.
+    #[must_use]
+    pub const fn as_multimap(&self) -> &IndexSetMultimap<K, V, S> {
+        &self.forward
+    }
+
+    /// Return a borrow of the multimap from values to keys.
+    #[must_use]
+    pub const fn as_inverse(&self) -> &IndexSetMultimap<V, K, S> {
+        &self.backward
+    }
+
+    /// Return the multimap from keys to values, dropping the reverse index.
+    #[must_use]
+    pub fn into_multimap(self) -> IndexSetMultimap<K, V, S> {
+        self.forward
+    }
+
+    /// Return an iterator over all key-value pairs, in insertion order of the
+    /// keys.
+    #[must_use]
+    pub fn iter(&self) -> crate::index_set_multimap::Iter<'_, K, V, S> {
+        self.forward.iter()
+    }
+
+    /// Return an iterator over the distinct keys, in insertion order.
+    #[must_use]
+    pub fn keys(&self) -> crate::index_set_multimap::Keys<'_, K, V, S> {
+        self.forward.keys()
+    }
+
+    /// Return an iterator over the values of all key-value pairs, in
+    /// insertion order of the keys.
+    #[must_use]
+    pub fn values(&self) -> crate::index_set_multimap::Values<'_, K, V, S> {
+        self.forward.values()
+    }
+
+    /// Return an iterator over the distinct values, in insertion order.
+    #[must_use]
+    pub fn distinct_values(&self) -> crate::index_set_multimap::Keys<'_, V, K, S> {
+        self.backward.keys()
+    }
+}
+
+impl<K, V, S> BiMultimap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    /// Insert the key-value pair into the multimap.
+    ///
+    /// If an equivalent pair already exists in the multimap, it returns
+    /// `false` without altering the multimap. Otherwise, it inserts the new
+    /// pair and returns `true`.
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        if self.forward.insert(key.clone(), value.clone()) {
+            self.backward.insert(value, key);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Return a reference to the values stored for `key`, if it is present,
+    /// else `None`.
+    pub fn get_by_key<Q>(&self, key: &Q) -> Option<&IndexSet<V, S>>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        self.forward.get(key)
+    }
+
+    /// Return a reference to the keys stored for `value`, if it is present,
+    /// else `None`.
+    pub fn get_by_value<R>(&self, value: &R) -> Option<&IndexSet<K, S>>
+    where
+        R: ?Sized + Hash + Equivalent<V>,
+    {
+        self.backward.get(value)
+    }
+
+    /// Returns `true` if the multimap contains a value for the specified key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        self.forward.contains_key(key)
+    }
+
+    /// Returns `true` if the multimap contains a key for the specified value.
+    pub fn contains_value<R>(&self, value: &R) -> bool
+    where
+        R: ?Sized + Hash + Equivalent<V>,
+    {
+        self.backward.contains_key(value)
+    }
+
+    /// Return `true` if an equivalent `key` and `value` combination exists in
+    /// the multimap.
+    pub fn contains<Q, R>(&self, key: &Q, value: &R) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+        R: ?Sized + Hash + Equivalent<V>,
+    {
+        self.forward.contains(key, value)
+    }
+
+    /// Remove the key-value pair from the multimap, and return it if it was
+    /// present.
+    pub fn remove<Q, R>(&mut self, key: &Q, value: &R) -> Option<(K, V)>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+        R: ?Sized + Hash + Equivalent<V>,
+    {
+        let value = self.forward.shift_remove(key, value)?;
+        let key = self
+            .backward
+            .shift_remove(&value, key)
+            .expect("reverse index is out of sync");
+        Some((key, value))
+    }
+
+    /// Remove the key and all associated values from the multimap.
+    ///
+    /// Returns values if at least one value is associated to `key`, returns
+    /// `None` otherwise.
+    pub fn remove_key<Q>(&mut self, key: &Q) -> Option<IndexSet<V, S>>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let values = self.forward.shift_remove_key(key)?;
+        values.iter().for_each(|value| {
+            self.backward.shift_remove(value, key);
+        });
+        Some(values)
+    }
+
+    /// Remove the value and all associated keys from the multimap.
+    ///
+    /// Returns keys if at least one key is associated to `value`, returns
+    /// `None` otherwise.
+    pub fn remove_value<R>(&mut self, value: &R) -> Option<IndexSet<K, S>>
+    where
+        R: ?Sized + Hash + Equivalent<V>,
+    {
+        let keys = self.backward.shift_remove_key(value)?;
+        keys.iter().for_each(|key| {
+            self.forward.shift_remove(key, value);
+        });
+        Some(keys)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k, &v)`
+    /// returns `false`. The predicate is called exactly once for every pair.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&K, &V) -> bool,
+    {
+        self.forward.retain(f);
+        let forward = &self.forward;
+        self.backward
+            .retain(|value, key| forward.contains(key, value));
+    }
+
+    /// Removes all key-value pairs from the multimap.
+    pub fn clear(&mut self) {
+        self.forward.retain(|_, _| false);
+        self.backward.retain(|_, _| false);
+    }
+
+    /// Clears the multimap, returning all key-value pairs as an iterator, in
+    /// insertion order of the keys.
+    ///
+    /// The multimap is empty once this method returns, even if the iterator
+    /// is not consumed.
+    pub fn drain(&mut self) -> crate::index_set_multimap::Drain<'_, K, V, S> {
+        self.backward.drain(..);
+        self.forward.drain(..)
+    }
+
+    /// Removes the key and all associated values from the multimap, returning
+    /// them as an iterator of key-value pairs.
+    ///
+    /// The iterator is empty if `key` is not present.
+    pub fn drain_key<Q>(&mut self, key: &Q) -> crate::index_set_multimap::IntoIter<K, V, S>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        if let Some(values) = self.forward.get(key) {
+            values.iter().for_each(|value| {
+                self.backward.shift_remove(value, key);
+            });
+        }
+        self.forward.drain_key(key)
+    }
+
+    /// Removes all pairs `(k, v)` such that `f(&k, &v)` returns `true`,
+    /// returning them as an iterator.
+    ///
+    /// See [`IndexSetMultimap::extract_if()`], the extraction is eager and
+    /// the reverse index is updated before this method returns.
+    pub fn extract_if<F>(&mut self, f: F) -> crate::index_set_multimap::ExtractIf<K, V>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let extracted = self.forward.extract_if(f);
+        let forward = &self.forward;
+        self.backward
+            .retain(|value, key| forward.contains(key, value));
+        extracted
+    }
+
+    /// Apply `f` to every value of the multimap in place, stopping at the
+    /// first error.
+    ///
+    /// See [`IndexSetMultimap::try_map_values_in_place()`]. Only the pairs
+    /// whose value was changed by `f` are updated in the reverse index, a
+    /// changed value is moved to the end of [`Self::distinct_values()`] unless
+    /// it was already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by `f`.
+    pub fn try_map_values_in_place<E, F>(&mut self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(&K, &mut V) -> Result<(), E>,
+    {
+        let backward = &self.backward;
+        let mut changed = Vec::new();
+        let result = self.forward.try_map_values_in_place(|key, value| {
+            let index = backward
+                .get_key_index(value)
+                .expect("every value is in the reverse index");
+            let result = f(key, value);
+            if backward
+                .get_index(index)
+                .is_some_and(|(old, _)| old != value)
+            {
+                changed.push((index, key.clone(), value.clone()));
+            }
+            result
+        });
+        // Removing in descending index order keeps the remaining indices valid.
+        let mut removals = changed
+            .iter()
+            .map(|(index, key, _)| (*index, key))
+            .collect::<Vec<_>>();
+        removals.sort_by(|(a, _), (b, _)| b.cmp(a));
+        for (index, key) in removals {
+            let key_index = self
+                .backward
+                .get_index(index)
+                .and_then(|(_, keys)| keys.get_index_of(key));
+            if let Some(key_index) = key_index {
+                self.backward.remove_at(index, key_index);
+            }
+        }
+        for (_, key, value) in changed {
+            self.backward.insert(value, key);
+        }
+        result
+    }
+}
+
+impl<K, V, S> Default for BiMultimap<K, V, S>
+where
+    S: Default,
+{
+    /// Creates an empty multimap, with the `Default` value for the hasher.
+    fn default() -> Self {
+        Self {
+            forward: IndexSetMultimap::default(),
+            backward: IndexSetMultimap::default(),
+        }
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for BiMultimap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        iterable.into_iter().for_each(|(key, value)| {
+            self.insert(key, value);
+        });
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for BiMultimap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iterable);
+        map
+    }
+}
+
+impl<K, V, S> From<IndexSetMultimap<K, V, S>> for BiMultimap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn from(forward: IndexSetMultimap<K, V, S>) -> Self {
+        let backward = forward.inverted();
+        Self { forward, backward }
+    }
+}
+
+impl<K, V, S> PartialEq for BiMultimap<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.forward == other.forward
+    }
+}
+
+impl<K, V, S> Eq for BiMultimap<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher + Default,
+{
+}
+
+impl<'a, K, V, S> IntoIterator for &'a BiMultimap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = crate::index_set_multimap::Iter<'a, K, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V, S> crate::Multimap for BiMultimap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    type Key = K;
+    type Value = V;
+    type Values = IndexSet<V, S>;
+    type KeyRef<'a>
+        = &'a K
+    where
+        Self: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn keys_len(&self) -> usize {
+        self.keys_len()
+    }
+
+    fn get(&self, key: &K) -> Option<&IndexSet<V, S>> {
+        self.get_by_key(key)
+    }
+
+    fn contains(&self, key: &K, value: &V) -> bool {
+        self.contains(key, value)
+    }
+
+    fn remove(&mut self, key: &K, value: &V) -> Option<V> {
+        self.remove(key, value).map(|(_key, value)| value)
+    }
+
+    fn remove_key(&mut self, key: &K) -> Option<IndexSet<V, S>> {
+        self.remove_key(key)
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&K, &V) -> bool,
+    {
+        self.retain(f);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys()
+    }
+
+    fn values(&self) -> impl Iterator<Item = &V> {
+        self.values()
+    }
+}
+
+impl<K, V, S> crate::SetMultimap for BiMultimap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn insert(&mut self, key: K, value: V) -> bool {
+        self.insert(key, value)
+    }
+}
+
+#[macro_export]
+macro_rules! bimultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { bimultimap!($($key => {$($value),*}),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = BiMultimap::new();
+            $(
+                $(
+                    let _ = _map.insert($key, $value);
+                )*
+            )*
+            _map
+        }
+    };
+}
@@ -0,0 +1,151 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+/// Multimap implementation that behaves like `BTreeMap<K, BTreeSet<V>>`.
+#[derive(Debug, Clone)]
+pub struct BTreeSetMultimap<K, V> {
+    inner: BTreeMap<K, BTreeSet<V>>,
+    len: usize,
+}
+
+impl<K, V> BTreeSetMultimap<K, V> {
+    multimap_base_impl! {btree, BTreeMap<K, BTreeSet<V>>}
+    multimap_len_impl! {}
+}
+
+impl<K, V> BTreeSetMultimap<K, V>
+where
+    K: Ord,
+    V: Ord,
+{
+    multimap_mutators_impl! {
+        BTreeMap<K, BTreeSet<V>>,
+        BTreeSet<V>,
+        BTreeSet::new(),
+        set,
+        (K: Borrow<Q>, Q: Ord),
+        (V: Borrow<R>, R: Ord)
+    }
+    multimap_remove_impl! {
+        unordered,
+        BTreeSet<V>,
+        set,
+        (K: Borrow<Q>, Q: Ord),
+        (V: Borrow<R>, R: Ord)
+    }
+
+    btree_multimap_impl! {
+        BTreeSet<V>,
+        (K: Borrow<Q>, Q: Ord)
+    }
+}
+
+multimap_extend! {
+    btree,
+    BTreeSetMultimap,
+    (K, V),
+    BTreeSet<V>,
+    (K: Ord),
+    (V: Ord),
+    (K: Ord + Borrow<Q>, Q: Ord)
+}
+multimap_eq! { btree, BTreeSetMultimap, (Ord)}
+
+impl_entry! {
+    BTreeSetMultimap<K, V>,
+    (K, V),
+    (std::collections::btree_map),
+    BTreeSet<V>,
+    BTreeSet::new(),
+    set,
+    unordered,
+    (K: Ord, V: Ord),
+    (K: Ord, V: Ord)
+}
+
+impl_get_mut! {
+    BTreeSetMultimap<K, V>,
+    (K, V),
+    (std::collections::btree_map),
+    unordered,
+    BTreeSet<V>,
+    (K: Ord, V: Ord),
+    (K: Ord)
+}
+
+impl_iter! {
+    BTreeSetMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::Iter<'a, K, BTreeSet<V>>,
+    std::collections::btree_set::Iter<'a, V>
+}
+impl_range! {BTreeSet<V>, std::collections::btree_set::Iter<'a, V>}
+impl_keys! {BTreeSetMultimap<K, V>, (K, V), std::collections::btree_map::Keys<'a, K, BTreeSet<V>>}
+impl_into_iterator! {
+    BTreeSetMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::IntoIter<K, BTreeSet<V>>,
+    std::collections::btree_set::IntoIter<V>
+}
+impl_into_keys! {BTreeSetMultimap<K, V>, (K, V), std::collections::btree_map::IntoKeys<K, BTreeSet<V>>}
+impl_double_ended! {BTreeSetMultimap<K, V>, (K, V)}
+
+impl_drain! {
+    btree,
+    BTreeSetMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::IntoIter<K, BTreeSet<V>>,
+    std::collections::btree_set::IntoIter<V>,
+    BTreeSet<V>,
+    (K: Ord, V: Ord),
+    (K: Borrow<Q>, Q: Ord)
+}
+
+impl_values_mut! {
+    set,
+    BTreeSetMultimap<K, V>,
+    (K, V),
+    BTreeSet<V>,
+    (K: Ord, V: Ord)
+}
+
+multimap_invert_impl! {
+    BTreeSetMultimap,
+    (K, V),
+    (V, K),
+    (K: Ord, V: Ord)
+}
+
+impl_multimap_trait! {
+    keyed,
+    set,
+    BTreeSetMultimap,
+    (K, V),
+    BTreeSet<V>,
+    (K: Ord, V: Ord),
+    remove,
+    remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    BTreeSetMultimap,
+    (K, V),
+    (K: Ord, V: Ord)
+}
+
+#[macro_export]
+macro_rules! btreesetmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { btreesetmultimap!($($key => $($value,)* ),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = std::collections::BTreeMap::new();
+            $(
+                let _ = _map.insert($key, std::collections::BTreeSet::from([$( $value, )*]));
+            )*
+            BTreeSetMultimap::from(_map)
+        }
+    };
+}
@@ -0,0 +1,149 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+/// Multimap implementation that behaves like `BTreeMap<K, Vec<V>>`.
+#[derive(Debug, Clone)]
+pub struct BTreeVecMultimap<K, V> {
+    inner: BTreeMap<K, Vec<V>>,
+    len: usize,
+}
+
+impl<K, V> BTreeVecMultimap<K, V> {
+    multimap_base_impl! {btree, BTreeMap<K, Vec<V>>}
+    multimap_len_impl! {}
+}
+
+impl<K, V> BTreeVecMultimap<K, V>
+where
+    K: Ord,
+    V: Eq,
+{
+    multimap_mutators_impl! {
+        BTreeMap<K, Vec<V>>,
+        Vec<V>,
+        Vec::new(),
+        vec_equal,
+        (K: Borrow<Q>, Q: Ord),
+        (V: Borrow<R>, R: Eq)
+    }
+    multimap_remove_impl! {
+        unordered,
+        Vec<V>,
+        vec_equal,
+        (K: Borrow<Q>, Q: Ord),
+        (V: Borrow<R>, R: Eq)
+    }
+
+    btree_multimap_impl! {
+        Vec<V>,
+        (K: Borrow<Q>, Q: Ord)
+    }
+}
+
+multimap_extend! {
+    btree,
+    BTreeVecMultimap,
+    (K, V),
+    Vec<V>,
+    (K: Ord),
+    (V: Eq),
+    (K: Ord + Borrow<Q>, Q: Ord)
+}
+multimap_eq! { btree, list, BTreeVecMultimap, (Eq)}
+
+impl_entry! {
+    BTreeVecMultimap<K, V>,
+    (K, V),
+    (std::collections::btree_map),
+    Vec<V>,
+    Vec::new(),
+    vec,
+    unordered,
+    (K: Ord, V: Eq),
+    (K: Ord, V: Eq)
+}
+
+impl_get_mut! {
+    BTreeVecMultimap<K, V>,
+    (K, V),
+    (std::collections::btree_map),
+    unordered,
+    Vec<V>,
+    (K: Ord, V: Eq),
+    (K: Ord)
+}
+
+impl_iter! {
+    BTreeVecMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::Iter<'a, K, Vec<V>>,
+    std::slice::Iter<'a, V>
+}
+impl_range! {Vec<V>, std::slice::Iter<'a, V>}
+impl_keys! {BTreeVecMultimap<K, V>, (K, V), std::collections::btree_map::Keys<'a, K, Vec<V>>}
+impl_into_iterator! {
+    BTreeVecMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::IntoIter<K, Vec<V>>,
+    std::vec::IntoIter<V>
+}
+impl_into_keys! {BTreeVecMultimap<K, V>, (K, V), std::collections::btree_map::IntoKeys<K, Vec<V>>}
+impl_double_ended! {BTreeVecMultimap<K, V>, (K, V)}
+
+impl_drain! {
+    btree,
+    BTreeVecMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::IntoIter<K, Vec<V>>,
+    std::vec::IntoIter<V>,
+    Vec<V>,
+    (K: Ord, V: Eq),
+    (K: Borrow<Q>, Q: Ord)
+}
+
+impl_values_mut! {
+    list,
+    BTreeVecMultimap<K, V>,
+    (K, V),
+    std::collections::btree_map::IterMut<'a, K, Vec<V>>
+}
+
+multimap_invert_impl! {
+    BTreeVecMultimap,
+    (K, V),
+    (V, K),
+    (K: Ord, V: Ord)
+}
+
+impl_multimap_trait! {
+    keyed,
+    list,
+    BTreeVecMultimap,
+    (K, V),
+    Vec<V>,
+    (K: Ord, V: Eq),
+    remove,
+    remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    BTreeVecMultimap,
+    (K, V),
+    (K: Ord, V: Eq)
+}
+
+#[macro_export]
+macro_rules! btreevecmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { btreevecmultimap!($($key => $($value,)* ),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = std::collections::BTreeMap::new();
+            $(
+                let _ = _map.insert($key, vec!{$( $value, )*});
+            )*
+            BTreeVecMultimap::from(_map)
+        }
+    };
+}
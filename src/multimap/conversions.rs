@@ -0,0 +1,96 @@
+//! Conversions and comparisons between the hash and index multimap families.
+//!
+//! Converting to a set multimap removes duplicate values, converting to a
+//! hash multimap loses the insertion order. Multimaps of different families
+//! are equal if they contain the same key-value pairs, the same number of
+//! times.
+
+#[cfg(all(feature = "hashsetmultimap", feature = "hashvecmultimap"))]
+mod hash_set_hash_vec {
+    multimap_from! {
+        crate::HashSetMultimap => crate::HashVecMultimap,
+        std::collections::HashMap<K, Vec<V>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_from! {
+        crate::HashVecMultimap => crate::HashSetMultimap,
+        std::collections::HashMap<K, std::collections::HashSet<V, S>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_cross_eq! { list, set, crate::HashVecMultimap, crate::HashSetMultimap }
+}
+
+#[cfg(all(feature = "hashsetmultimap", feature = "indexsetmultimap"))]
+mod hash_set_index_set {
+    multimap_from! {
+        crate::HashSetMultimap => crate::IndexSetMultimap,
+        indexmap::IndexMap<K, indexmap::IndexSet<V, S>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_from! {
+        crate::IndexSetMultimap => crate::HashSetMultimap,
+        std::collections::HashMap<K, std::collections::HashSet<V, S>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_cross_eq! { set, set, crate::HashSetMultimap, crate::IndexSetMultimap }
+}
+
+#[cfg(all(feature = "hashsetmultimap", feature = "indexvecmultimap"))]
+mod hash_set_index_vec {
+    multimap_from! {
+        crate::HashSetMultimap => crate::IndexVecMultimap,
+        indexmap::IndexMap<K, Vec<V>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_from! {
+        crate::IndexVecMultimap => crate::HashSetMultimap,
+        std::collections::HashMap<K, std::collections::HashSet<V, S>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_cross_eq! { list, set, crate::IndexVecMultimap, crate::HashSetMultimap }
+}
+
+#[cfg(all(feature = "hashvecmultimap", feature = "indexsetmultimap"))]
+mod hash_vec_index_set {
+    multimap_from! {
+        crate::HashVecMultimap => crate::IndexSetMultimap,
+        indexmap::IndexMap<K, indexmap::IndexSet<V, S>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_from! {
+        crate::IndexSetMultimap => crate::HashVecMultimap,
+        std::collections::HashMap<K, Vec<V>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_cross_eq! { list, set, crate::HashVecMultimap, crate::IndexSetMultimap }
+}
+
+#[cfg(all(feature = "hashvecmultimap", feature = "indexvecmultimap"))]
+mod hash_vec_index_vec {
+    multimap_from! {
+        crate::HashVecMultimap => crate::IndexVecMultimap,
+        indexmap::IndexMap<K, Vec<V>, S>,
+        (Eq)
+    }
+    multimap_from! {
+        crate::IndexVecMultimap => crate::HashVecMultimap,
+        std::collections::HashMap<K, Vec<V>, S>,
+        (Eq)
+    }
+    multimap_cross_eq! { list, list, crate::HashVecMultimap, crate::IndexVecMultimap }
+}
+
+#[cfg(all(feature = "indexsetmultimap", feature = "indexvecmultimap"))]
+mod index_set_index_vec {
+    multimap_from! {
+        crate::IndexSetMultimap => crate::IndexVecMultimap,
+        indexmap::IndexMap<K, Vec<V>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_from! {
+        crate::IndexVecMultimap => crate::IndexSetMultimap,
+        indexmap::IndexMap<K, indexmap::IndexSet<V, S>, S>,
+        (std::hash::Hash + Eq)
+    }
+    multimap_cross_eq! { list, set, crate::IndexVecMultimap, crate::IndexSetMultimap }
+}
@@ -0,0 +1,84 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::EnumKey;
+
+/// Multimap implementation that behaves like `HashMap<K, HashSet<V>>` but
+/// where `K` is an [`EnumKey`].
+///
+/// Storage for the values of every key is allocated upfront and indexed by
+/// [`IndexKey::as_index()`](crate::IndexKey::as_index), key lookups are
+/// therefore O(1) without hashing. Keys are iterated in index order.
+#[derive(Clone)]
+pub struct EnumHashSetMultimap<K, V, S = RandomState> {
+    inner: Box<[HashSet<V, S>]>,
+    len: usize,
+    keys_len: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<K, V> EnumHashSetMultimap<K, V, RandomState>
+where
+    K: EnumKey,
+    V: Hash + Eq,
+{
+    enum_multimap_base_impl! {}
+}
+
+vec_multimap_impl! {
+    enum_key,
+    EnumHashSetMultimap,
+    (K, V, S),
+    HashSet<V, S>,
+    HashSet::with_hasher(S::default()),
+    set,
+    (unordered, set),
+    (K: EnumKey, V: Hash + Eq, S: BuildHasher + Default),
+    (V: Borrow<R>, R: Hash + Eq),
+    std::collections::hash_set::Iter<'a, V>,
+    std::collections::hash_set::IntoIter<V>
+}
+
+multimap_invert_impl! {
+    EnumHashSetMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: EnumKey + Hash + Eq, V: EnumKey + Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    index_key,
+    set,
+    EnumHashSetMultimap,
+    (K, V, S),
+    HashSet<V, S>,
+    (K: EnumKey, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    enum_key,
+    EnumHashSetMultimap,
+    (K, V, S),
+    (K: EnumKey, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[macro_export]
+macro_rules! enumhashsetmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { enumhashsetmultimap!($($key => {$($value),*}),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = EnumHashSetMultimap::new();
+            $(
+                $(
+                    let _ = _map.insert($key, $value);
+                )*
+            )*
+            _map
+        }
+    };
+}
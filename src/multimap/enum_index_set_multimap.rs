@@ -0,0 +1,86 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use indexmap::Equivalent;
+use indexmap::IndexSet;
+
+use crate::EnumKey;
+
+/// Multimap implementation that behaves like `IndexMap<K, IndexSet<V>>` but
+/// where `K` is an [`EnumKey`].
+///
+/// Storage for the values of every key is allocated upfront and indexed by
+/// [`IndexKey::as_index()`](crate::IndexKey::as_index), key lookups are
+/// therefore O(1) without hashing. Keys are iterated in index order, values in
+/// insertion order.
+#[derive(Clone)]
+pub struct EnumIndexSetMultimap<K, V, S = RandomState> {
+    inner: Box<[IndexSet<V, S>]>,
+    len: usize,
+    keys_len: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<K, V> EnumIndexSetMultimap<K, V, RandomState>
+where
+    K: EnumKey,
+    V: Hash + Eq,
+{
+    enum_multimap_base_impl! {}
+}
+
+vec_multimap_impl! {
+    enum_key,
+    EnumIndexSetMultimap,
+    (K, V, S),
+    IndexSet<V, S>,
+    IndexSet::with_hasher(S::default()),
+    set,
+    (ordered, shift, set),
+    (K: EnumKey, V: Hash + Eq, S: BuildHasher + Default),
+    (R: Hash + Equivalent<V>),
+    indexmap::set::Iter<'a, V>,
+    indexmap::set::IntoIter<V>
+}
+
+multimap_invert_impl! {
+    EnumIndexSetMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: EnumKey + Hash + Eq, V: EnumKey + Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    index_key,
+    set,
+    EnumIndexSetMultimap,
+    (K, V, S),
+    IndexSet<V, S>,
+    (K: EnumKey, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    enum_key,
+    EnumIndexSetMultimap,
+    (K, V, S),
+    (K: EnumKey, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[macro_export]
+macro_rules! enumindexsetmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { enumindexsetmultimap!($($key => {$($value),*}),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = EnumIndexSetMultimap::new();
+            $(
+                $(
+                    let _ = _map.insert($key, $value);
+                )*
+            )*
+            _map
+        }
+    };
+}
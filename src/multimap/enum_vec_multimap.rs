@@ -0,0 +1,79 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use crate::EnumKey;
+
+/// Multimap implementation that behaves like `HashMap<K, Vec<V>>` but where
+/// `K` is an [`EnumKey`].
+///
+/// Storage for the values of every key is allocated upfront and indexed by
+/// [`IndexKey::as_index()`](crate::IndexKey::as_index), key lookups are
+/// therefore O(1) without hashing. Keys are iterated in index order.
+#[derive(Clone)]
+pub struct EnumVecMultimap<K, V> {
+    inner: Box<[Vec<V>]>,
+    len: usize,
+    keys_len: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<K, V> EnumVecMultimap<K, V>
+where
+    K: EnumKey,
+{
+    enum_multimap_base_impl! {}
+}
+
+vec_multimap_impl! {
+    enum_key,
+    EnumVecMultimap,
+    (K, V),
+    Vec<V>,
+    Vec::new(),
+    vec_equal,
+    (unordered, vec_equal),
+    (K: EnumKey, V: Eq),
+    (V: Borrow<R>, R: Eq),
+    std::slice::Iter<'a, V>,
+    std::vec::IntoIter<V>
+}
+
+multimap_invert_impl! {
+    EnumVecMultimap,
+    (K, V),
+    (V, K),
+    (K: EnumKey + Eq, V: EnumKey + Eq)
+}
+
+impl_multimap_trait! {
+    index_key,
+    list,
+    EnumVecMultimap,
+    (K, V),
+    Vec<V>,
+    (K: EnumKey, V: Eq)
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    enum_key,
+    EnumVecMultimap,
+    (K, V),
+    (K: EnumKey, V: Eq)
+}
+
+#[macro_export]
+macro_rules! enumvecmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { enumvecmultimap!($($key => {$($value),*}),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = EnumVecMultimap::new();
+            $(
+                $(
+                    _map.insert($key, $value);
+                )*
+            )*
+            _map
+        }
+    };
+}
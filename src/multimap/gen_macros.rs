@@ -1,6 +1,18 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! multimap_base_impl {
+    (btree, $keys:ty) => {
+        /// Creates an empty multimap.
+        ///
+        /// Does not allocate anything on its own.
+        #[must_use]
+        pub const fn new() -> Self {
+            Self {
+                inner: <$keys>::new(),
+                len: 0,
+            }
+        }
+    };
     ($keys:ty) => {
         /// Creates an empty multimap.
         ///
@@ -55,20 +67,26 @@ macro_rules! multimap_base2_impl {
             self.inner.capacity()
         }
 
-        // TODO values_mut()
-
-        // TODO iter_mut()
+        $crate::multimap_len_impl!();
+    };
+}
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_len_impl {
+    () => {
         /// Returns the number of elements in the multimap.
         ///
         /// Note that the number of elements in the multimap may not be the
         /// same as the number of keys in the multimap. See
         /// [`Self::keys_len()`].
+        #[must_use]
         pub const fn len(&self) -> usize {
             self.len
         }
 
         /// Returns `true` if the multimap contains no elements.
+        #[must_use]
         pub const fn is_empty(&self) -> bool {
             self.len == 0
         }
@@ -78,6 +96,7 @@ macro_rules! multimap_base2_impl {
         /// Note that the number of keys in the multimap may not be the
         /// same as the number of elements in the multimap. See
         /// [`Self::len()`].
+        #[must_use]
         pub fn keys_len(&self) -> usize {
             self.inner.len()
         }
@@ -86,10 +105,8 @@ macro_rules! multimap_base2_impl {
 
 #[doc(hidden)]
 #[macro_export]
-macro_rules! multimap_mutators_impl {
-    ($keys:ty, $values:ty, $values_ctx:expr, $values_class:tt, ($($keys_ref:tt)*), ($($values_ref:tt)*)) => {
-
-        // TODO this method will have to be split out as it won't be needed in all implementations
+macro_rules! multimap_capacity_impl {
+    () => {
         /// Reserve capacity for `additional` more keys.
         #[inline]
         pub fn reserve(&mut self, additional: usize) {
@@ -110,13 +127,20 @@ macro_rules! multimap_mutators_impl {
         /// internal rules and possibly leaving some space in accordance with
         /// the resize policy.
         pub fn shrink_values_to_fit(&mut self) {
-            self.inner.iter_mut().for_each(|(_,values)| values.shrink_to_fit());
+            self.inner
+                .iter_mut()
+                .for_each(|(_, values)| values.shrink_to_fit());
         }
 
         // TODO add shrink_keys_to for Hash*Multimaps
         // TODO add shrink_values_to for Vec, HashSet
+    };
+}
 
-        // TODO add entry()
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_mutators_impl {
+    ($keys:ty, $values:ty, $values_ctx:expr, $values_class:tt, ($($keys_ref:tt)*), ($($values_ref:tt)*)) => {
 
         #[doc = concat!("Return a reference to the ", stringify!($values_class), " stored for `key`, if it is present, else `None`.")]
         #[inline]
@@ -148,8 +172,6 @@ macro_rules! multimap_mutators_impl {
             self.get(key).is_some()
         }
 
-        // TODO add get_mut() --> only if it is possible to keep internal `len` consistent
-
         $crate::insert!($values_class $values_ctx);
 
         /// Retains only the elements specified by the predicate.
@@ -194,12 +216,14 @@ macro_rules! multimap_mutators_impl {
         }
 
         /// Return a borrow of the underlying map.
+        #[must_use]
         pub const fn as_map(&self) -> &$keys {
             &self.inner
         }
 
         /// Return the underlying map, the multimap cannot be used after
         /// calling this.
+        #[must_use]
         pub fn into_map(self) -> $keys {
             self.inner
         }
@@ -400,6 +424,515 @@ macro_rules! multimap_remove_impl {
     }
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_entry {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_entry!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), ($($inner_mod:ident)::+), $values:ty, $values_ctx:expr, $values_class:tt, $remove:tt, ($($type_bounds:tt)*), ($($entry_bounds:tt)*)) => {
+        /// A view into a single entry in a multimap, which may either be vacant
+        /// or occupied.
+        ///
+        /// This `enum` is constructed from the `entry` method on multimap.
+        pub enum Entry<'a, $($generic_params)*> {
+            /// An occupied entry, the key has at least one value.
+            Occupied(OccupiedEntry<'a, $($generic_ids)*>),
+            /// A vacant entry, the key has no values.
+            Vacant(VacantEntry<'a, $($generic_ids)*>),
+        }
+
+        /// A view into an occupied entry in a multimap. It is part of the
+        /// [`Entry`] enum.
+        pub struct OccupiedEntry<'a, $($generic_params)*> {
+            inner: $($inner_mod)::+::OccupiedEntry<'a, K, $values>,
+            len: &'a mut usize,
+        }
+
+        /// A view into a vacant entry in a multimap. It is part of the
+        /// [`Entry`] enum.
+        pub struct VacantEntry<'a, $($generic_params)*> {
+            inner: $($inner_mod)::+::VacantEntry<'a, K, $values>,
+            len: &'a mut usize,
+        }
+
+        impl<$($type_params)*> $type<$($type_generics),*>
+        where
+            $($type_bounds)*,
+        {
+            /// Get the given key's corresponding entry in the multimap for
+            /// in-place manipulation.
+            ///
+            /// The key is hashed only once, regardless of the operations that
+            /// are performed on the entry.
+            pub fn entry(&mut self, key: K) -> Entry<'_, $($generic_ids)*> {
+                match self.inner.entry(key) {
+                    $($inner_mod)::+::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry {
+                        inner,
+                        len: &mut self.len,
+                    }),
+                    $($inner_mod)::+::Entry::Vacant(inner) => Entry::Vacant(VacantEntry {
+                        inner,
+                        len: &mut self.len,
+                    }),
+                }
+            }
+        }
+
+        impl<$($generic_params)*> Entry<'_, $($generic_ids)*>
+        where
+            $($entry_bounds)*,
+        {
+            /// Return a reference to this entry's key.
+            #[must_use]
+            pub fn key(&self) -> &K {
+                match self {
+                    Entry::Occupied(entry) => entry.key(),
+                    Entry::Vacant(entry) => entry.key(),
+                }
+            }
+        }
+
+        impl<'a, $($generic_params)*> Entry<'a, $($generic_ids)*>
+        where
+            $($entry_bounds)*,
+        {
+            /// Insert `value` for this entry's key.
+            ///
+            /// Returns a reference to all values that are associated with the
+            /// key after the insertion.
+            pub fn insert(self, value: V) -> &'a $values {
+                match self {
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(value);
+                        entry.into_ref()
+                    }
+                    Entry::Vacant(entry) => entry.insert(value),
+                }
+            }
+
+            /// Insert all `values` for this entry's key.
+            ///
+            /// Returns a reference to all values that are associated with the
+            /// key after the insertion, or `None` if the entry was vacant and
+            /// `values` was empty (keys without values are never stored).
+            pub fn extend<I>(self, values: I) -> Option<&'a $values>
+            where
+                I: IntoIterator<Item = V>,
+            {
+                match self {
+                    Entry::Occupied(mut entry) => {
+                        entry.extend(values);
+                        Some(entry.into_ref())
+                    }
+                    Entry::Vacant(entry) => entry.extend(values),
+                }
+            }
+
+            /// Insert the values returned by `values` if the entry is vacant,
+            /// otherwise leave the entry untouched.
+            ///
+            /// Returns a reference to all values that are associated with the
+            /// key, or `None` if the entry was vacant and `values` returned no
+            /// values (keys without values are never stored).
+            pub fn or_insert_with_values<F, I>(self, values: F) -> Option<&'a $values>
+            where
+                F: FnOnce() -> I,
+                I: IntoIterator<Item = V>,
+            {
+                match self {
+                    Entry::Occupied(entry) => Some(entry.into_ref()),
+                    Entry::Vacant(entry) => entry.extend(values()),
+                }
+            }
+        }
+
+        impl<'a, $($generic_params)*> OccupiedEntry<'a, $($generic_ids)*>
+        where
+            $($entry_bounds)*,
+        {
+            /// Return a reference to this entry's key.
+            #[must_use]
+            pub fn key(&self) -> &K {
+                self.inner.key()
+            }
+
+            /// Return a reference to the values associated with this entry's
+            /// key.
+            #[must_use]
+            pub fn get(&self) -> &$values {
+                self.inner.get()
+            }
+
+            /// Convert the entry into a reference to the values associated
+            /// with this entry's key, with a lifetime bound to the multimap.
+            #[must_use]
+            pub fn into_ref(self) -> &'a $values {
+                self.inner.into_mut()
+            }
+        }
+
+        impl<$($generic_params)*> OccupiedEntry<'_, $($generic_ids)*>
+        where
+            $($entry_bounds)*,
+        {
+            $crate::impl_entry!(@occupied_insert $values_class);
+
+            /// Insert all `values` for this entry's key.
+            pub fn extend<I>(&mut self, values: I)
+            where
+                I: IntoIterator<Item = V>,
+            {
+                let current = self.inner.get_mut();
+                let before = current.len();
+                current.extend(values);
+                *self.len += current.len() - before;
+            }
+
+            $crate::impl_entry!(@remove $remove, $values);
+        }
+
+        impl<$($generic_params)*> VacantEntry<'_, $($generic_ids)*>
+        where
+            $($entry_bounds)*,
+        {
+            /// Return a reference to this entry's key.
+            #[must_use]
+            pub fn key(&self) -> &K {
+                self.inner.key()
+            }
+
+            /// Take ownership of the key.
+            #[must_use]
+            pub fn into_key(self) -> K {
+                self.inner.into_key()
+            }
+        }
+
+        impl<'a, $($generic_params)*> VacantEntry<'a, $($generic_ids)*>
+        where
+            $($entry_bounds)*,
+        {
+            /// Insert `value` for this entry's key.
+            ///
+            /// Returns a reference to the newly created values of the key.
+            pub fn insert(self, value: V) -> &'a $values {
+                let mut values = $values_ctx;
+                values.extend(std::iter::once(value));
+                *self.len += 1;
+                self.inner.insert(values)
+            }
+
+            /// Insert all `values` for this entry's key.
+            ///
+            /// Returns a reference to the newly created values of the key, or
+            /// `None` if `values` was empty (keys without values are never
+            /// stored).
+            pub fn extend<I>(self, values: I) -> Option<&'a $values>
+            where
+                I: IntoIterator<Item = V>,
+            {
+                let mut new_values = $values_ctx;
+                new_values.extend(values);
+                if new_values.is_empty() {
+                    None
+                } else {
+                    *self.len += new_values.len();
+                    Some(self.inner.insert(new_values))
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Entry<'_, $($generic_ids)*>
+        where
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+            $($entry_bounds)*,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+                    Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for OccupiedEntry<'_, $($generic_ids)*>
+        where
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+            $($entry_bounds)*,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("OccupiedEntry")
+                    .field("key", self.key())
+                    .field("values", self.get())
+                    .finish()
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for VacantEntry<'_, $($generic_ids)*>
+        where
+            K: std::fmt::Debug,
+            $($entry_bounds)*,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple("VacantEntry").field(self.key()).finish()
+            }
+        }
+    };
+
+    (@occupied_insert set) => {
+        /// Insert `value` for this entry's key.
+        ///
+        /// If an equivalent value already exists for this key, it returns
+        /// `false` leaving the original value in place. Otherwise, it inserts
+        /// the new value and returns `true`.
+        pub fn insert(&mut self, value: V) -> bool {
+            if self.inner.get_mut().insert(value) {
+                *self.len += 1;
+                true
+            } else {
+                false
+            }
+        }
+    };
+    (@occupied_insert small_set) => {
+        /// Insert `value` for this entry's key.
+        ///
+        /// If an equivalent value already exists for this key, it returns
+        /// `false` leaving the original value in place. Otherwise, it inserts
+        /// the new value and returns `true`.
+        pub fn insert(&mut self, value: V) -> bool {
+            let values = self.inner.get_mut();
+            let before = values.len();
+            values.insert(value);
+            if values.len() > before {
+                *self.len += 1;
+                true
+            } else {
+                false
+            }
+        }
+    };
+    (@occupied_insert $values_class:tt) => {
+        /// Insert `value` for this entry's key.
+        ///
+        /// Allows duplicates.
+        pub fn insert(&mut self, value: V) {
+            self.inner.get_mut().push(value);
+            *self.len += 1;
+        }
+    };
+
+    (@remove unordered, $values:ty) => {
+        /// Remove the key and all associated values from the multimap.
+        ///
+        /// Returns the key and all values that were associated with it.
+        #[must_use]
+        pub fn remove_entry(self) -> (K, $values) {
+            let (key, values) = self.inner.remove_entry();
+            *self.len -= values.len();
+            (key, values)
+        }
+    };
+    (@remove ordered, $values:ty) => {
+        /// Remove the key and all associated values from the multimap.
+        ///
+        /// Like [`IndexMap::swap_remove`], the key is removed by swapping it
+        /// with the last element of the map and popping it off. **This
+        /// perturbs the position of what used to be the last element!**
+        ///
+        /// Returns the key and all values that were associated with it.
+        #[must_use]
+        pub fn swap_remove_entry(self) -> (K, $values) {
+            let (key, values) = self.inner.swap_remove_entry();
+            *self.len -= values.len();
+            (key, values)
+        }
+
+        /// Remove the key and all associated values from the multimap.
+        ///
+        /// Like [`IndexMap::shift_remove`], the key is removed by shifting all
+        /// of the elements that follow it, preserving their relative order.
+        /// **This perturbs the index of all of those elements!**
+        ///
+        /// Returns the key and all values that were associated with it.
+        #[must_use]
+        pub fn shift_remove_entry(self) -> (K, $values) {
+            let (key, values) = self.inner.shift_remove_entry();
+            *self.len -= values.len();
+            (key, values)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_get_mut {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_get_mut!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), ($($inner_mod:ident)::+), $remove:tt, $values:ty, ($($type_bounds:tt)*), ($($guard_bounds:tt)*)) => {
+        /// A guard that gives mutable access to the values of a single key.
+        ///
+        /// The guard dereferences to the underlying values collection. When it
+        /// is dropped, the length of the multimap is updated to reflect any
+        /// inserted or removed values, and the key is removed from the multimap
+        /// if it no longer has any values.
+        ///
+        /// This `struct` is created by the `get_mut` method on multimap.
+        pub struct ValuesGuard<'a, $($generic_params)*>
+        where
+            $($guard_bounds)*,
+        {
+            inner: Option<$crate::impl_get_mut!(@inner $remove, ($($inner_mod)::+), $values)>,
+            initial_len: usize,
+            len: &'a mut usize,
+        }
+
+        impl<$($type_params)*> $type<$($type_generics),*>
+        where
+            $($type_bounds)*,
+        {
+            #[doc = concat!("Return a guard that gives mutable access to the `", stringify!($values), "` stored for `key`, if it is present, else `None`.")]
+            ///
+            /// The key is taken by value, like [`Self::entry()`]. See
+            /// [`ValuesGuard`] for how the multimap is updated when the guard
+            /// is dropped.
+            pub fn get_mut(&mut self, key: K) -> Option<ValuesGuard<'_, $($generic_ids)*>> {
+                $crate::impl_get_mut!(@get_mut $remove, ($($inner_mod)::+), self, key)
+            }
+
+            $crate::impl_get_mut!(@get_index_mut $remove, ($($generic_ids)*));
+        }
+
+        impl<$($generic_params)*> ValuesGuard<'_, $($generic_ids)*>
+        where
+            $($guard_bounds)*,
+        {
+            /// Return a reference to the key of the guarded values.
+            #[must_use]
+            pub fn key(&self) -> &K {
+                $crate::impl_get_mut!(@key $remove, self.inner.as_ref().expect("values are present until drop"))
+            }
+        }
+
+        impl<$($generic_params)*> std::ops::Deref for ValuesGuard<'_, $($generic_ids)*>
+        where
+            $($guard_bounds)*,
+        {
+            type Target = $values;
+
+            fn deref(&self) -> &Self::Target {
+                $crate::impl_get_mut!(@deref $remove, self.inner.as_ref().expect("values are present until drop"))
+            }
+        }
+
+        impl<$($generic_params)*> std::ops::DerefMut for ValuesGuard<'_, $($generic_ids)*>
+        where
+            $($guard_bounds)*,
+        {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                $crate::impl_get_mut!(@deref_mut $remove, self.inner.as_mut().expect("values are present until drop"))
+            }
+        }
+
+        impl<$($generic_params)*> Drop for ValuesGuard<'_, $($generic_ids)*>
+        where
+            $($guard_bounds)*,
+        {
+            fn drop(&mut self) {
+                if let Some(inner) = self.inner.take() {
+                    $crate::impl_get_mut!(@drop $remove, self, inner);
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for ValuesGuard<'_, $($generic_ids)*>
+        where
+            V: std::fmt::Debug,
+            $($guard_bounds)*,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple("ValuesGuard").field(&**self).finish()
+            }
+        }
+    };
+
+    (@inner unordered, ($($inner_mod:ident)::+), $values:ty) => {
+        $($inner_mod)::+::OccupiedEntry<'a, K, $values>
+    };
+    (@inner ordered, ($($inner_mod:ident)::+), $values:ty) => {
+        $($inner_mod)::+::IndexedEntry<'a, K, $values>
+    };
+
+    (@get_mut unordered, ($($inner_mod:ident)::+), $self:ident, $key:ident) => {
+        match $self.inner.entry($key) {
+            $($inner_mod)::+::Entry::Occupied(entry) => Some(ValuesGuard {
+                initial_len: entry.get().len(),
+                inner: Some(entry),
+                len: &mut $self.len,
+            }),
+            $($inner_mod)::+::Entry::Vacant(_) => None,
+        }
+    };
+    (@get_mut ordered, ($($inner_mod:ident)::+), $self:ident, $key:ident) => {{
+        let index = $self.inner.get_index_of(&$key)?;
+        $self.get_index_mut(index)
+    }};
+
+    (@get_index_mut unordered, ($($generic_ids:tt)*)) => {};
+    (@get_index_mut ordered, ($($generic_ids:tt)*)) => {
+        /// Return a guard that gives mutable access to the values of the key
+        /// at `index`, if it is in bounds, else `None`.
+        ///
+        /// See [`ValuesGuard`] for how the multimap is updated when the
+        /// guard is dropped.
+        pub fn get_index_mut(&mut self, index: usize) -> Option<ValuesGuard<'_, $($generic_ids)*>> {
+            let entry = self.inner.get_index_entry(index)?;
+            Some(ValuesGuard {
+                initial_len: entry.get().len(),
+                inner: Some(entry),
+                len: &mut self.len,
+            })
+        }
+    };
+
+    (@key $remove:tt, $inner:expr) => {
+        $inner.key()
+    };
+
+    (@deref $remove:tt, $inner:expr) => {
+        $inner.get()
+    };
+    (@deref_mut $remove:tt, $inner:expr) => {
+        $inner.get_mut()
+    };
+
+    (@drop unordered, $self:ident, $inner:ident) => {{
+        *$self.len = *$self.len - $self.initial_len + $inner.get().len();
+        if $inner.get().is_empty() {
+            $inner.remove();
+        }
+    }};
+    (@drop ordered, $self:ident, $inner:ident) => {{
+        *$self.len = *$self.len - $self.initial_len + $inner.get().len();
+        if $inner.get().is_empty() {
+            // preserve the order of the remaining keys
+            $inner.shift_remove();
+        }
+    }};
+}
+
 //////////////////////////////////////
 // IndexMap keys specific methods
 //////////////////////////////////////
@@ -442,708 +975,4260 @@ macro_rules! index_multimap_impl {
             }
         }
 
+        /// Get the first key and its values, if the multimap is not empty.
+        #[must_use]
+        pub fn first(&self) -> Option<(&K, &$values)> {
+            self.inner.first()
+        }
 
-    };
-}
+        /// Get the last key and its values, if the multimap is not empty.
+        #[must_use]
+        pub fn last(&self) -> Option<(&K, &$values)> {
+            self.inner.last()
+        }
 
-// TODO add get_full_mut()
-// TODO add swap_remove()
-// TODO add swap_remove_entry()
-// TODO add swap_remove_full()
-// TODO add shift_remove()
-// TODO add shift_remove_entry()
-// TODO add shift_remove_full()
-// TODO add pop()
-// TODO add sort_keys()
-// TODO add sort_by()
-// TODO add sorted_by()
-// TODO add reverse()
-
-// TODO add get_index()
-// TODO add get_index_mut()
-// TODO add first()
-// TODO add first_mut()
-// TODO add last()
-// TODO add last_mut()
-// TODO add swap_remove_index()
-// TODO add shift_remove_index()
-// TODO add swap_indices()
+        /// Remove the last key and return it with all its values, if the
+        /// multimap is not empty.
+        pub fn pop(&mut self) -> Option<(K, $values)> {
+            self.inner.pop().map(|(key, values)| {
+                self.len -= values.len();
+                (key, values)
+            })
+        }
 
-// ////////////////////////////////////
-// / *Set values specific methods
-// ////////////////////////////////////
-
-// TODO add difference()
-// TODO add symmetric_difference()
-// TODO add intersection()
-// TODO add union()
-// TODO add is_disjoint()
-// TODO add is_subset()
-// TODO add is_superset()
+        /// Remove the key at `index` and return it with all its values, if
+        /// `index` is in bounds.
+        ///
+        /// Like [`IndexMap::swap_remove_index`], the key is removed by swapping
+        /// it with the last key of the map and popping it off. **This perturbs
+        /// the position of what used to be the last key!**
+        pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, $values)> {
+            self.inner.swap_remove_index(index).map(|(key, values)| {
+                self.len -= values.len();
+                (key, values)
+            })
+        }
 
-// ////////////////////////////////////
-// / Vec / IndexSet values specific methods
-// ////////////////////////////////////
+        /// Remove the key at `index` and return it with all its values, if
+        /// `index` is in bounds.
+        ///
+        /// Like [`IndexMap::shift_remove_index`], the key is removed by
+        /// shifting all of the keys that follow it, preserving their relative
+        /// order. **This perturbs the index of all of those keys!**
+        pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, $values)> {
+            self.inner.shift_remove_index(index).map(|(key, values)| {
+                self.len -= values.len();
+                (key, values)
+            })
+        }
 
-// TODO add sort_values()
-// TODO add sort_values_by()
-// TODO consider adding more mutators
+        /// Get the value at `value_index` of the key at `key_index`, if both
+        /// indices are in bounds.
+        ///
+        /// The indices are the same as the ones returned by `insert_full()`.
+        #[must_use]
+        pub fn get_value_at(&self, key_index: usize, value_index: usize) -> Option<&V> {
+            let (_, values) = self.inner.get_index(key_index)?;
+            $crate::index_multimap_impl!(@get_value_at $values_class, values, value_index)
+        }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! insert {
-    (set $values_ctx:expr) => {
-        /// Insert the value into the multimap.
+        /// Remove the value at `value_index` of the key at `key_index` and
+        /// return it, if both indices are in bounds.
         ///
-        /// If an equivalent entry already exists in the multimap, it returns
-        /// `false` leaving the original value in the set and without altering its
-        /// insertion order. Otherwise, it inserts the new entry and returns `true`.
-        #[inline]
-        pub fn insert(&mut self, key: K, value: V) -> bool {
-            if self
-                .inner
-                .entry(key)
-                .or_insert_with(|| $values_ctx)
-                .insert(value)
-            {
-                self.len += 1;
-                true
-            } else {
-                false
+        /// The indices are the same as the ones returned by `insert_full()`.
+        /// The values that follow the removed value are shifted, preserving
+        /// their relative order. If the key no longer has any values, it is
+        /// removed by shifting all of the keys that follow it.
+        pub fn remove_at(&mut self, key_index: usize, value_index: usize) -> Option<V> {
+            let (_, values) = self.inner.get_index_mut(key_index)?;
+            let value = $crate::index_multimap_impl!(@remove_at $values_class, values, value_index)?;
+            if values.is_empty() {
+                self.inner.shift_remove_index(key_index);
             }
+            self.len -= 1;
+            Some(value)
         }
     };
 
-    (vec_equal $values_ctx:expr) => {
-        $crate::vec_insert!($values_ctx);
+    (@get_value_at set, $values:ident, $value_index:ident) => {
+        $values.get_index($value_index)
     };
-    (vec_equivalent $values_ctx:expr) => {
-        $crate::vec_insert!($values_ctx);
+    (@get_value_at vec, $values:ident, $value_index:ident) => {
+        $values.get($value_index)
+    };
+
+    (@remove_at set, $values:ident, $value_index:ident) => {
+        $values.shift_remove_index($value_index)
+    };
+    (@remove_at vec, $values:ident, $value_index:ident) => {
+        if $value_index < $values.len() {
+            Some($values.remove($value_index))
+        } else {
+            None
+        }
     };
 }
 
+// TODO add get_full_mut()
+// TODO add first_mut()
+// TODO add last_mut()
+
 #[doc(hidden)]
 #[macro_export]
-macro_rules! vec_insert {
-    ($values_ctx:expr) => {
-        /// Insert the value into the multimap.
+macro_rules! index_multimap_sort_impl {
+    (($($generic_ids:tt)*), $values:ty, ($($keys_ref:tt)*)) => {
+        /// Sort the keys of the multimap.
         ///
-        /// Allows duplicates.
-        #[inline]
-        pub fn insert(&mut self, key: K, value: V) {
+        /// Values are not sorted, see [`Self::sort_values()`].
+        pub fn sort_keys(&mut self)
+        where
+            K: Ord,
+        {
+            self.inner.sort_keys();
+        }
+
+        /// Sort the keys of the multimap with a comparison function that
+        /// receives two keys and their associated values.
+        ///
+        /// Values are not sorted, see [`Self::sort_values_by()`].
+        pub fn sort_by<F>(&mut self, cmp: F)
+        where
+            F: FnMut(&K, &$values, &K, &$values) -> std::cmp::Ordering,
+        {
+            self.inner.sort_by(cmp);
+        }
+
+        /// Sort the keys of the multimap with a comparison function that
+        /// receives two keys and their associated values, and return an
+        /// owning iterator over all key-value pairs in the sorted order.
+        pub fn sorted_by<F>(self, cmp: F) -> IntoIter<$($generic_ids)*>
+        where
+            F: FnMut(&K, &$values, &K, &$values) -> std::cmp::Ordering,
+        {
+            let mut iter = self.inner.sorted_by(cmp);
+            let inner = iter.next().map(|(k, v)| (k, v.into_iter()));
+            IntoIter {
+                outer: iter,
+                inner,
+                back: None,
+                len: self.len,
+            }
+        }
+
+        /// Reverse the order of the keys of the multimap.
+        ///
+        /// The order of the values of each key is not changed.
+        pub fn reverse(&mut self) {
+            self.inner.reverse();
+        }
+
+        /// Sort the values of every key.
+        ///
+        /// Keys are not sorted, see [`Self::sort_keys()`].
+        pub fn sort_values(&mut self)
+        where
+            V: Ord,
+        {
+            self.inner.values_mut().for_each(|values| values.sort());
+        }
+
+        /// Sort the values of every key with a comparison function.
+        ///
+        /// Keys are not sorted, see [`Self::sort_by()`].
+        pub fn sort_values_by<F>(&mut self, mut cmp: F)
+        where
+            F: FnMut(&V, &V) -> std::cmp::Ordering,
+        {
             self.inner
-                .entry(key)
-                .or_insert_with(|| $values_ctx)
-                .push(value);
-            self.len += 1;
+                .values_mut()
+                .for_each(|values| values.sort_by(&mut cmp));
+        }
+
+        /// Sort the values of `key`.
+        ///
+        /// Returns `true` if `key` is present in the multimap, `false`
+        /// otherwise.
+        pub fn sort_key_values<Q>(&mut self, key: &Q) -> bool
+        where
+            V: Ord,
+            Q: ?Sized,
+            $($keys_ref)*,
+        {
+            self.inner.get_mut(key).map(|values| values.sort()).is_some()
+        }
+
+        /// Sort the values of `key` with a comparison function.
+        ///
+        /// Returns `true` if `key` is present in the multimap, `false`
+        /// otherwise.
+        pub fn sort_key_values_by<Q, F>(&mut self, key: &Q, cmp: F) -> bool
+        where
+            Q: ?Sized,
+            $($keys_ref)*,
+            F: FnMut(&V, &V) -> std::cmp::Ordering,
+        {
+            self.inner.get_mut(key).map(|values| values.sort_by(cmp)).is_some()
+        }
+
+        /// Swap the position of two keys in the multimap.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `a` or `b` are out of bounds.
+        pub fn swap_indices(&mut self, a: usize, b: usize) {
+            self.inner.swap_indices(a, b);
+        }
+
+        /// Move the position of a key from one index to another by shifting
+        /// all other keys in-between.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `from` or `to` are out of bounds.
+        pub fn move_index(&mut self, from: usize, to: usize) {
+            self.inner.move_index(from, to);
         }
     };
 }
 
+//////////////////////////////////////
+// BTreeMap keys specific methods
+//////////////////////////////////////
+
 #[doc(hidden)]
 #[macro_export]
-macro_rules! insert_full {
-    (set $values_ctx:expr) => {
-        /// Insert a key-value pair in the multimap, and get its indices.
+macro_rules! btree_multimap_impl {
+    ($values:ty, ($($keys_ref:tt)*)) => {
+        /// Return an iterator over the key-value pairs of the multimap whose
+        /// keys are within `range`, in ascending key order.
         ///
-        /// If an equivalent key already exists in the multimap, the key
-        /// remains and retains its place in the order. Additionally, if an
-        /// equivalent value already exists for that particular key the value
-        /// remains and retains its place in the order, and `false` is
-        /// returned.
+        /// # Panics
         ///
-        /// If no equivalent key existed in the multimap the new key is
-        /// inserted last in order. If no equivalent value existed in the
-        /// multimap for this key, the value is inserted last in order.
+        /// Panics if range `start > end`. Panics if range `start == end` and
+        /// both bounds are `Excluded`.
+        pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+        where
+            Q: ?Sized,
+            R: std::ops::RangeBounds<Q>,
+            $($keys_ref)*,
+        {
+            Range {
+                outer: self.inner.range(range),
+                inner: None,
+            }
+        }
+
+        /// Return the first key and its values, if any. The key in this pair
+        /// is the minimum key in the multimap.
+        #[must_use]
+        pub fn first_key_value(&self) -> Option<(&K, &$values)> {
+            self.inner.first_key_value()
+        }
+
+        /// Return the last key and its values, if any. The key in this pair
+        /// is the maximum key in the multimap.
+        #[must_use]
+        pub fn last_key_value(&self) -> Option<(&K, &$values)> {
+            self.inner.last_key_value()
+        }
+
+        /// Removes and returns the first key and its values, if any. The key
+        /// in this pair is the minimum key that was in the multimap.
+        pub fn pop_first(&mut self) -> Option<(K, $values)> {
+            self.inner.pop_first().map(|(key, values)| {
+                self.len -= values.len();
+                (key, values)
+            })
+        }
+
+        /// Removes and returns the last key and its values, if any. The key
+        /// in this pair is the maximum key that was in the multimap.
+        pub fn pop_last(&mut self) -> Option<(K, $values)> {
+            self.inner.pop_last().map(|(key, values)| {
+                self.len -= values.len();
+                (key, values)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_range {
+    ($values:ty, $inner_iter:ty) => {
+        /// An iterator over a sub-range of the entries of a multimap.
         ///
-        /// Returns `(key index, values index, success)` where success is
-        /// `true` if the multimap changes as a result of calling this method.
-        pub fn insert_full(&mut self, key: K, value: V) -> (usize, usize, bool) {
-            match self.inner.get_full_mut(&key) {
-                Some((keys_index, _, values)) => {
-                    let (values_index, success) = values.insert_full(value);
-                    if success {
-                        self.len += 1;
+        /// This `struct` is created by the `range` method on multimap.
+        pub struct Range<'a, K, V> {
+            outer: std::collections::btree_map::Range<'a, K, $values>,
+            inner: Option<(&'a K, $inner_iter)>,
+        }
+
+        impl<'a, K, V> Iterator for Range<'a, K, V> {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            return Some((key, value));
+                        }
                     }
-                    (keys_index, values_index, success)
+                    let (key, values) = self.outer.next()?;
+                    self.inner = Some((key, values.iter()));
                 }
-                None => {
-                    let mut values = $values_ctx;
-                    values.insert(value);
-                    let (keys_index, _) = self.inner.insert_full(key, values);
-                    self.len += 1;
-                    (keys_index, 0, true)
+            }
+        }
+
+        impl<K, V> std::iter::FusedIterator for Range<'_, K, V> {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<K, V> Clone for Range<'_, K, V> {
+            fn clone(&self) -> Self {
+                Range {
+                    outer: self.outer.clone(),
+                    inner: self.inner.clone(),
                 }
             }
         }
+
+        impl<K, V> std::fmt::Debug for Range<'_, K, V>
+        where
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
     };
-    (vec $values_ctx:expr) => {
-        /// Insert a key-value pair in the multimap, and get its indices.
-        ///
-        /// If an equivalent key already exists in the multimap, the key
-        /// remains and retains its place in the order. If no equivalent key
-        /// existed in the multimap the new key is inserted last in order.
+}
+
+//////////////////////////////////////
+// Enum keys multimaps
+//////////////////////////////////////
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_multimap_base_impl {
+    () => {
+        /// Creates an empty multimap.
         ///
-        /// The value is inserted last in order in the values for this
-        /// particular key (duplicates are allowed).
+        /// An empty value collection is created upfront for every key, a key
+        /// is present in the multimap while its collection is non-empty.
+        #[must_use]
+        pub fn new() -> Self {
+            Self::default()
+        }
+    };
+}
+
+//////////////////////////////////////
+// VecMap keys multimaps
+//////////////////////////////////////
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! vec_multimap_base_impl {
+    () => {
+        /// Creates an empty multimap.
         ///
-        /// Returns `(key index, values index)`
-        pub fn insert_full(&mut self, key: K, value: V) -> (usize, usize) {
-            match self.inner.get_full_mut(&key) {
-                Some((keys_index, _, values)) => {
-                    let values_index = values.len();
-                    values.push(value);
-                    self.len += 1;
-                    (keys_index, values_index)
-                }
-                None => {
-                    let values = vec![value];
-                    let (keys_index, _) = self.inner.insert_full(key, values);
-                    self.len += 1;
-                    (keys_index, 0)
-                }
+        /// For performance reasons it's almost always better to avoid dynamic
+        /// resizing by using [`Self::with_key_capacity()`] instead.
+        #[must_use]
+        pub const fn new() -> Self {
+            Self {
+                inner: $crate::VecMap::new(),
+                len: 0,
+            }
+        }
+
+        /// Creates an empty multimap with capacity to hold keys in the index
+        /// range of `0..capacity` without reallocating.
+        #[must_use]
+        pub fn with_key_capacity(capacity: usize) -> Self {
+            Self {
+                inner: $crate::VecMap::with_capacity(capacity),
+                len: 0,
             }
         }
     };
 }
 
+/// Implements a multimap that stores its values in a `VecMap`.
+///
+/// Used for both the `Enum*` (`enum_key`) and the `Vec*` (`index_key`)
+/// multimaps, the latter additionally expose the capacity and the underlying
+/// `VecMap`.
 #[doc(hidden)]
 #[macro_export]
-macro_rules! values_contains {
-    (set, $values:ident, $value:ident) => {
-        $values.contains($value)
-    };
+macro_rules! vec_multimap_impl {
+    ($key_kind:tt, $type:ident, ($($generic_ids:tt)*), $values:ty, $values_ctx:expr, $values_class:tt, ($($values_remove:tt)*), ($($type_bounds:tt)*), ($($values_ref:tt)*), $values_iter:ty, $values_into_iter:ty) => {
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            /// Returns the number of elements in the multimap.
+            ///
+            /// Note that the number of elements in the multimap may not be the
+            /// same as the number of keys in the multimap. See
+            /// [`Self::keys_len()`].
+            #[must_use]
+            pub const fn len(&self) -> usize {
+                self.len
+            }
 
-    (vec_equivalent, $values:ident, $value:ident) => {
-        $values.iter().find(|x| $value.equivalent(x)).is_some()
-    };
+            /// Returns `true` if the multimap contains no elements.
+            #[must_use]
+            pub const fn is_empty(&self) -> bool {
+                self.len == 0
+            }
 
-    (vec_equal, $values:ident, $value:ident) => {
-        $values.iter().find(|&x| $value == x.borrow()).is_some()
+            /// Returns the number of keys in the multimap.
+            ///
+            /// Note that the number of keys in the multimap may not be the
+            /// same as the number of elements in the multimap. See
+            /// [`Self::len()`].
+            #[must_use]
+            pub const fn keys_len(&self) -> usize {
+                $crate::vec_multimap_impl!(@keys_len $key_kind, self)
+            }
+
+            /// Return an iterator over the key-value pairs of the multimap,
+            /// following the natural order of the keys.
+            #[must_use]
+            pub fn iter(&self) -> Iter<'_, $($generic_ids)*> {
+                Iter {
+                    outer: $crate::vec_multimap_impl!(@outer $key_kind, self.inner.iter()),
+                    inner: None,
+                    len: self.len,
+                }
+            }
+
+            /// Return an iterator over the values of the multimap, following
+            /// the natural order of the keys.
+            #[must_use]
+            pub fn values(&self) -> Values<'_, $($generic_ids)*> {
+                Values { inner: self.iter() }
+            }
+
+            /// Return an owning iterator over the values of the multimap,
+            /// following the natural order of the keys.
+            #[must_use]
+            pub fn into_values(self) -> IntoValues<$($generic_ids)*> {
+                IntoValues {
+                    inner: self.into_iter(),
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Shrinks the capacity of the multimap's values as much as possible.
+            /// It will drop down as much as possible while maintaining the
+            /// internal rules and possibly leaving some space in accordance with
+            /// the resize policy.
+            pub fn shrink_values_to_fit(&mut self) {
+                $crate::vec_multimap_impl!(@shrink_values $key_kind, self);
+            }
+
+            #[doc = concat!("Return a reference to the `", stringify!($values), "` stored for `key`, if it is present, else `None`.")]
+            #[inline]
+            pub fn get(&self, key: K) -> Option<&$values> {
+                self.values_of(key)
+            }
+
+            /// Return the key and a reference to the values stored for `key`,
+            /// if it is present, else `None`.
+            pub fn get_key_values(&self, key: K) -> Option<(K, &$values)> {
+                self.values_of(key).map(|values| (key, values))
+            }
+
+            /// Returns `true` if the map contains a value for the specified key.
+            #[inline]
+            pub fn contains_key(&self, key: K) -> bool {
+                self.values_of(key).is_some()
+            }
+
+            $crate::vec_multimap_impl!(@insert $values_class);
+
+            /// Removes the key and all associated values from the multimap.
+            ///
+            /// Returns values if at least one value is associated to `key`,
+            /// returns `None` otherwise.
+            pub fn remove_key(&mut self, key: K) -> Option<$values> {
+                self.take_values(key).map(|values| {
+                    self.len -= values.len();
+                    values
+                })
+            }
+
+            /// Removes the key and all associated values from the multimap.
+            ///
+            /// Returns the entry (key and all associated values) if at least one
+            /// value is associated to `key`, returns `None` otherwise.
+            pub fn remove_key_entry(&mut self, key: K) -> Option<(K, $values)> {
+                self.remove_key(key).map(|values| (key, values))
+            }
+
+            /// Remove the entry from the multimap, and return it if it was present.
+            pub fn remove<R>(&mut self, key: K, value: &R) -> Option<V>
+            where
+                R: ?Sized,
+                $($values_ref)*,
+            {
+                let values = self.values_of_mut(key)?;
+                let value = $crate::values_remove!($($values_remove)*, values, value);
+                if value.is_some() {
+                    if values.is_empty() {
+                        self.remove_emptied(key);
+                    }
+                    self.len -= 1;
+                }
+                value
+            }
+
+            /// Retains only the elements specified by the predicate.
+            ///
+            /// In other words, remove all pairs `(k, v)` such that `f(&k, &v)`
+            /// returns `false`.
+            pub fn retain<F>(&mut self, f: F)
+            where
+                F: Fn(&K, &V) -> bool,
+            {
+                let mut removed = 0;
+                self.retain_values(|key, values| {
+                    values.retain(|value| {
+                        let retain = f(&key, value);
+                        if !retain {
+                            removed += 1;
+                        }
+                        retain
+                    });
+                });
+                self.len -= removed;
+            }
+
+            /// Clears the multimap, returning all key-value pairs as an
+            /// iterator, in key index order.
+            ///
+            /// The multimap is empty once this method returns, even if the
+            /// iterator is not consumed.
+            pub fn drain(&mut self) -> IntoIter<$($generic_ids)*> {
+                std::mem::take(self).into_iter()
+            }
+
+            /// Removes the key and all associated values from the multimap,
+            /// returning them as an iterator of key-value pairs.
+            ///
+            /// The iterator is empty if `key` is not present.
+            pub fn drain_key(&mut self, key: K) -> IntoIter<$($generic_ids)*> {
+                let inner = self.remove_key_entry(key);
+                IntoIter {
+                    outer: $crate::vec_multimap_impl!(@outer_into $key_kind, Self::default().inner),
+                    len: inner.as_ref().map_or(0, |(_key, values)| values.len()),
+                    inner: inner.map(|(key, values)| (key, values.into_iter())),
+                }
+            }
+
+            /// Removes all pairs `(k, v)` such that `f(&k, &v)` returns
+            /// `true`, returning them as an iterator in key index order.
+            ///
+            /// Unlike `Vec::extract_if()`, this method is eager: `f` is called
+            /// exactly once for every pair and all matching pairs are removed
+            /// before this method returns, dropping the iterator early does not
+            /// put them back. Only the values of keys with at least one match
+            /// are rebuilt.
+            pub fn extract_if<F>(&mut self, mut f: F) -> ExtractIf<K, V>
+            where
+                F: FnMut(&K, &V) -> bool,
+            {
+                let mut extracted = Vec::new();
+                self.retain_values(|key, values| {
+                    let Some(first) = values.iter().position(|value| f(&key, value)) else {
+                        return;
+                    };
+                    for (index, value) in std::mem::take(values).into_iter().enumerate() {
+                        if index == first || (index > first && f(&key, &value)) {
+                            extracted.push((key, value));
+                        } else {
+                            values.extend(std::iter::once(value));
+                        }
+                    }
+                });
+                self.len -= extracted.len();
+                ExtractIf {
+                    inner: extracted.into_iter(),
+                }
+            }
+
+            /// Return `true` if the `key` and an equivalent `value` combination
+            /// exists in the multimap.
+            pub fn contains<R>(&self, key: K, value: &R) -> bool
+            where
+                R: ?Sized,
+                $($values_ref)*,
+            {
+                self.values_of(key)
+                    .is_some_and(|values| $crate::values_contains!($values_class, values, value))
+            }
+        }
+
+        impl<$($generic_ids)*> Extend<(K, V)> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+                // extend does not attempt to reserve additional space because the space needed
+                // is dependent on the keys that are added
+                iterable.into_iter().for_each(move |(k, v)| {
+                    self.insert(k, v);
+                });
+            }
+        }
+
+        impl<'a, $($generic_ids)*> Extend<(&'a K, &'a V)> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            V: Copy,
+        {
+            fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iterable: I) {
+                self.extend(iterable.into_iter().map(|(&key, &value)| (key, value)));
+            }
+        }
+
+        impl<$($generic_ids)*> FromIterator<(K, V)> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+                let mut map = Self::default();
+                map.extend(iterable);
+                map
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::Index<K> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            type Output = $values;
+
+            /// Returns a reference to the values container corresponding to the supplied key.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the key is not present in the multimap.
+            #[inline]
+            fn index(&self, key: K) -> &$values {
+                self.get(key).expect("no entry found for key")
+            }
+        }
+
+        $crate::vec_multimap_impl!(@eq $values_class, $type, ($($generic_ids)*), ($($type_bounds)*));
+
+        impl<$($generic_ids)*> Eq for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey + std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_map()
+                    .entries(self.keys().filter_map(|key| self.values_of(key).map(|values| (key, values))))
+                    .finish()
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for ValuesGuard<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple("ValuesGuard").field(&**self).finish()
+            }
+        }
+
+        /// A view into a single entry in a multimap, which may either be vacant
+        /// or occupied.
+        ///
+        /// This `enum` is constructed from the `entry` method on multimap.
+        pub enum Entry<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            /// An occupied entry, the key has at least one value.
+            Occupied(OccupiedEntry<'a, $($generic_ids)*>),
+            /// A vacant entry, the key has no values.
+            Vacant(VacantEntry<'a, $($generic_ids)*>),
+        }
+
+        impl<'a, $($generic_ids)*> Entry<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Return this entry's key.
+            #[must_use]
+            pub const fn key(&self) -> K {
+                match self {
+                    Entry::Occupied(entry) => entry.key,
+                    Entry::Vacant(entry) => entry.key,
+                }
+            }
+
+            /// Insert `value` for this entry's key.
+            ///
+            /// Returns a reference to all values that are associated with the
+            /// key after the insertion.
+            pub fn insert(self, value: V) -> &'a $values {
+                match self {
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(value);
+                        entry.into_ref()
+                    }
+                    Entry::Vacant(entry) => entry.insert(value),
+                }
+            }
+
+            /// Insert all `values` for this entry's key.
+            ///
+            /// Returns a reference to all values that are associated with the
+            /// key after the insertion, or `None` if the entry was vacant and
+            /// `values` was empty (keys without values are never stored).
+            pub fn extend<I>(self, values: I) -> Option<&'a $values>
+            where
+                I: IntoIterator<Item = V>,
+            {
+                match self {
+                    Entry::Occupied(mut entry) => {
+                        entry.extend(values);
+                        Some(entry.into_ref())
+                    }
+                    Entry::Vacant(entry) => entry.extend(values),
+                }
+            }
+
+            /// Insert the values returned by `values` if the entry is vacant,
+            /// otherwise leave the entry untouched.
+            ///
+            /// Returns a reference to all values that are associated with the
+            /// key, or `None` if the entry was vacant and `values` returned no
+            /// values (keys without values are never stored).
+            pub fn or_insert_with_values<F, I>(self, values: F) -> Option<&'a $values>
+            where
+                F: FnOnce() -> I,
+                I: IntoIterator<Item = V>,
+            {
+                match self {
+                    Entry::Occupied(entry) => Some(entry.into_ref()),
+                    Entry::Vacant(entry) => entry.extend(values()),
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> OccupiedEntry<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Return this entry's key.
+            #[must_use]
+            pub const fn key(&self) -> K {
+                self.key
+            }
+
+            $crate::vec_multimap_impl!(@occupied_insert $values_class);
+
+            /// Insert all `values` for this entry's key.
+            pub fn extend<I>(&mut self, values: I)
+            where
+                I: IntoIterator<Item = V>,
+            {
+                let current = self.values_mut();
+                let before = current.len();
+                current.extend(values);
+                let added = current.len() - before;
+                *self.len += added;
+            }
+        }
+
+        impl<'a, $($generic_ids)*> VacantEntry<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Return this entry's key.
+            #[must_use]
+            pub const fn key(&self) -> K {
+                self.key
+            }
+
+            /// Take ownership of the key.
+            #[must_use]
+            pub const fn into_key(self) -> K {
+                self.key
+            }
+
+            /// Insert `value` for this entry's key.
+            ///
+            /// Returns a reference to the newly created values of the key.
+            pub fn insert(self, value: V) -> &'a $values {
+                let mut values = $values_ctx;
+                values.extend(std::iter::once(value));
+                self.insert_values(values)
+            }
+
+            /// Insert all `values` for this entry's key.
+            ///
+            /// Returns a reference to the newly created values of the key, or
+            /// `None` if `values` was empty (keys without values are never
+            /// stored).
+            pub fn extend<I>(self, values: I) -> Option<&'a $values>
+            where
+                I: IntoIterator<Item = V>,
+            {
+                let mut new_values = $values_ctx;
+                new_values.extend(values);
+                if new_values.is_empty() {
+                    None
+                } else {
+                    Some(self.insert_values(new_values))
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for Entry<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+                    Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for OccupiedEntry<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("OccupiedEntry")
+                    .field("key", &self.key)
+                    .field("values", self.get())
+                    .finish()
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for VacantEntry<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey + std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple("VacantEntry").field(&self.key).finish()
+            }
+        }
+
+        /// An iterator over the entries of a multimap.
+        ///
+        /// This struct is created by the `iter` method on multimap.
+        pub struct Iter<'a, $($generic_ids)*> {
+            outer: $crate::vec_multimap_impl!(@outer_type $key_kind, std::slice::Iter<'a, $values>, $crate::vec_map::Iter<'a, K, $values>),
+            inner: Option<(K, $values_iter)>,
+            len: usize,
+        }
+
+        impl<'a, $($generic_ids)*> Iterator for Iter<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = (K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((*key, value));
+                        }
+                    }
+                    let (key, values) = self.outer.next()?;
+                    self.inner = Some(($crate::vec_multimap_impl!(@key $key_kind, key), values.iter()));
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for Iter<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for Iter<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_ids)*> Clone for Iter<'_, $($generic_ids)*>
+        where
+            K: Clone,
+        {
+            fn clone(&self) -> Self {
+                Iter {
+                    outer: self.outer.clone(),
+                    inner: self.inner.clone(),
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for Iter<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey + std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        impl<'a, $($generic_ids)*> IntoIterator for &'a $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type IntoIter = Iter<'a, $($generic_ids)*>;
+            type Item = (K, &'a V);
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        /// An iterator over the values of a multimap.
+        ///
+        /// This `struct` is created by the `values` method on multimap.
+        pub struct Values<'a, $($generic_ids)*> {
+            inner: Iter<'a, $($generic_ids)*>,
+        }
+
+        impl<'a, $($generic_ids)*> Iterator for Values<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = &'a V;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map(|(_, v)| v)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for Values<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for Values<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_ids)*> Clone for Values<'_, $($generic_ids)*>
+        where
+            K: Clone,
+        {
+            fn clone(&self) -> Self {
+                Values {
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for Values<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        $crate::multimap_extract_if_iter!();
+
+        /// An owning iterator over the entries of a multimap.
+        pub struct IntoIter<$($generic_ids)*> {
+            outer: $crate::vec_multimap_impl!(@outer_type $key_kind, std::vec::IntoIter<$values>, $crate::vec_map::IntoIter<K, $values>),
+            inner: Option<(K, $values_into_iter)>,
+            len: usize,
+        }
+
+        impl<$($generic_ids)*> Iterator for IntoIter<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = (K, V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((*key, value));
+                        }
+                    }
+                    let (key, values) = self.outer.next()?;
+                    self.inner = Some(($crate::vec_multimap_impl!(@key $key_kind, key), values.into_iter()));
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for IntoIter<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for IntoIter<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::fmt::Debug for IntoIter<$($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("IntoIter").field("len", &self.len).finish_non_exhaustive()
+            }
+        }
+
+        impl<$($generic_ids)*> IntoIterator for $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = (K, V);
+            type IntoIter = IntoIter<$($generic_ids)*>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                IntoIter {
+                    outer: $crate::vec_multimap_impl!(@outer_into $key_kind, self.inner),
+                    inner: None,
+                    len: self.len,
+                }
+            }
+        }
+
+        /// An owning iterator over the values of a multimap.
+        ///
+        /// This `struct` is created by the `into_values` method on multimap.
+        pub struct IntoValues<$($generic_ids)*> {
+            inner: IntoIter<$($generic_ids)*>,
+        }
+
+        impl<$($generic_ids)*> Iterator for IntoValues<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = V;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map(|(_, v)| v)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for IntoValues<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for IntoValues<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::fmt::Debug for IntoValues<$($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple("IntoValues").field(&self.inner).finish()
+            }
+        }
+
+        $crate::vec_multimap_impl!(@storage $key_kind, $type, ($($generic_ids)*), $values, $values_ctx, ($($type_bounds)*));
+        $crate::vec_multimap_impl!(@values_mut $values_class, $key_kind, $type, ($($generic_ids)*), $values, ($($type_bounds)*));
+    };
+
+    (@eq set, $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> PartialEq for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                if self.len() != other.len() {
+                    return false;
+                }
+                self.iter().all(|(key, value)| other.contains(key, value))
+            }
+        }
+    };
+    (@eq vec_equal, $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Returns `true` if both multimaps contain the same keys, and each
+            /// key has the same values in the same order.
+            #[must_use]
+            pub fn eq_ordered(&self, other: &Self) -> bool {
+                self.len() == other.len()
+                    && self.keys_len() == other.keys_len()
+                    && self.keys().all(|key| self.get(key) == other.get(key))
+            }
+
+            /// Returns `true` if both multimaps contain the same keys, and each
+            /// key has the same values with the same multiplicities, in any
+            /// order.
+            ///
+            /// This is the equality used by `==`. The values of a key are
+            /// compared pairwise, which is quadratic in the number of values
+            /// per key.
+            #[must_use]
+            pub fn eq_unordered(&self, other: &Self) -> bool {
+                self.len() == other.len()
+                    && self.keys_len() == other.keys_len()
+                    && self.keys().all(|key| {
+                        other.get(key).is_some_and(|other_values| {
+                            $crate::multimap::slice_eq_unordered(&self[key], other_values)
+                        })
+                    })
+            }
+        }
+
+        impl<$($generic_ids)*> PartialEq for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.eq_unordered(other)
+            }
+        }
+    };
+
+    (@keys_len enum_key, $self:ident) => {
+        $self.keys_len
+    };
+    (@keys_len index_key, $self:ident) => {
+        $self.inner.len()
+    };
+
+    // The `Enum*` multimaps store a value collection for every variant in a
+    // boxed slice, an empty collection stands for a missing key.
+    (@outer enum_key, $iter:expr) => {
+        $iter.enumerate()
+    };
+    (@outer index_key, $iter:expr) => {
+        $iter
+    };
+    (@outer_into enum_key, $inner:expr) => {
+        $inner.into_vec().into_iter().enumerate()
+    };
+    (@outer_into index_key, $inner:expr) => {
+        $inner.into_iter()
+    };
+    (@outer_type enum_key, $slice_iter:ty, $vec_map_iter:ty) => {
+        std::iter::Enumerate<$slice_iter>
+    };
+    (@outer_type index_key, $slice_iter:ty, $vec_map_iter:ty) => {
+        $vec_map_iter
+    };
+    (@key enum_key, $index:ident) => {
+        K::from_index($index)
+    };
+    (@key index_key, $key:ident) => {
+        $key
+    };
+    (@shrink_values enum_key, $self:ident) => {
+        $self.inner.iter_mut().for_each(|values| values.shrink_to_fit())
+    };
+    (@shrink_values index_key, $self:ident) => {
+        $self.inner.iter_mut().for_each(|(_, values)| values.shrink_to_fit())
+    };
+
+    (@storage enum_key, $type:ident, ($($generic_ids:tt)*), $values:ty, $values_ctx:expr, ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            /// Return an iterator over the keys of the multimap, following the
+            /// natural order of the keys.
+            #[must_use]
+            pub fn keys(&self) -> Keys<'_, $($generic_ids)*> {
+                Keys {
+                    outer: self.inner.iter().enumerate(),
+                    len: self.keys_len,
+                    _marker: std::marker::PhantomData,
+                }
+            }
+
+            /// Return an owning iterator over the keys of the multimap,
+            /// following the natural order of the keys.
+            #[must_use]
+            pub fn into_keys(self) -> IntoKeys<$($generic_ids)*> {
+                IntoKeys {
+                    outer: self.inner.into_vec().into_iter().enumerate(),
+                    len: self.keys_len,
+                    _marker: std::marker::PhantomData,
+                }
+            }
+
+            fn values_of(&self, key: K) -> Option<&$values> {
+                let values = &self.inner[key.as_index()];
+                if values.is_empty() {
+                    None
+                } else {
+                    Some(values)
+                }
+            }
+
+            fn values_of_mut(&mut self, key: K) -> Option<&mut $values> {
+                let values = &mut self.inner[key.as_index()];
+                if values.is_empty() {
+                    None
+                } else {
+                    Some(values)
+                }
+            }
+
+            /// Return the values of `key` to insert into, the key is counted
+            /// as present from here on.
+            fn values_for_insert(&mut self, key: K) -> &mut $values {
+                let values = &mut self.inner[key.as_index()];
+                if values.is_empty() {
+                    self.keys_len += 1;
+                }
+                values
+            }
+
+            /// Account for the values of `key` that became empty.
+            fn remove_emptied(&mut self, _key: K) {
+                self.keys_len -= 1;
+            }
+
+            /// Call `f` with the values of every key, keys whose values became
+            /// empty are removed.
+            fn retain_values<F>(&mut self, mut f: F)
+            where
+                F: FnMut(K, &mut $values),
+            {
+                for (index, values) in self.inner.iter_mut().enumerate() {
+                    if !values.is_empty() {
+                        f(K::from_index(index), values);
+                        if values.is_empty() {
+                            self.keys_len -= 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            #[doc = concat!("Return a guard that gives mutable access to the `", stringify!($values), "` stored for `key`, if it is present, else `None`.")]
+            ///
+            /// See [`ValuesGuard`] for how the multimap is updated when the
+            /// guard is dropped.
+            pub fn get_mut(&mut self, key: K) -> Option<ValuesGuard<'_, $($generic_ids)*>> {
+                let values = &mut self.inner[key.as_index()];
+                if values.is_empty() {
+                    None
+                } else {
+                    Some(ValuesGuard {
+                        initial_len: values.len(),
+                        values,
+                        len: &mut self.len,
+                        keys_len: &mut self.keys_len,
+                        _marker: std::marker::PhantomData,
+                    })
+                }
+            }
+
+            /// Get the given key's corresponding entry in the multimap for
+            /// in-place manipulation.
+            pub fn entry(&mut self, key: K) -> Entry<'_, $($generic_ids)*> {
+                let values = &mut self.inner[key.as_index()];
+                let len = &mut self.len;
+                let keys_len = &mut self.keys_len;
+                if values.is_empty() {
+                    Entry::Vacant(VacantEntry { key, values, len, keys_len })
+                } else {
+                    Entry::Occupied(OccupiedEntry { key, values, len, keys_len })
+                }
+            }
+
+            fn take_values(&mut self, key: K) -> Option<$values> {
+                let values = &mut self.inner[key.as_index()];
+                if values.is_empty() {
+                    None
+                } else {
+                    self.keys_len -= 1;
+                    Some(std::mem::take(values))
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> Default for $type<$($generic_ids)*>
+        where
+            K: $crate::EnumKey,
+            $values: Default,
+        {
+            /// Creates an empty multimap.
+            fn default() -> Self {
+                Self {
+                    inner: (0..K::VARIANT_COUNT).map(|_| <$values>::default()).collect(),
+                    len: 0,
+                    keys_len: 0,
+                    _marker: std::marker::PhantomData,
+                }
+            }
+        }
+
+        /// A guard that gives mutable access to the values of a single key.
+        ///
+        /// The guard dereferences to the underlying values collection. When it
+        /// is dropped, the length of the multimap is updated to reflect any
+        /// inserted or removed values, and the key is removed if no values
+        /// remain.
+        ///
+        /// This `struct` is created by the `get_mut` method on multimap.
+        pub struct ValuesGuard<'a, $($generic_ids)*> {
+            values: &'a mut $values,
+            initial_len: usize,
+            len: &'a mut usize,
+            keys_len: &'a mut usize,
+            _marker: std::marker::PhantomData<K>,
+        }
+
+        impl<$($generic_ids)*> std::ops::Deref for ValuesGuard<'_, $($generic_ids)*> {
+            type Target = $values;
+
+            fn deref(&self) -> &Self::Target {
+                self.values
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::DerefMut for ValuesGuard<'_, $($generic_ids)*> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                self.values
+            }
+        }
+
+        impl<$($generic_ids)*> Drop for ValuesGuard<'_, $($generic_ids)*> {
+            fn drop(&mut self) {
+                *self.len = *self.len - self.initial_len + self.values.len();
+                if self.values.is_empty() {
+                    *self.keys_len -= 1;
+                }
+            }
+        }
+
+        /// A view into an occupied entry in a multimap. It is part of the
+        /// [`Entry`] enum.
+        pub struct OccupiedEntry<'a, $($generic_ids)*> {
+            key: K,
+            values: &'a mut $values,
+            len: &'a mut usize,
+            keys_len: &'a mut usize,
+        }
+
+        /// A view into a vacant entry in a multimap. It is part of the
+        /// [`Entry`] enum.
+        pub struct VacantEntry<'a, $($generic_ids)*> {
+            key: K,
+            values: &'a mut $values,
+            len: &'a mut usize,
+            keys_len: &'a mut usize,
+        }
+
+        impl<'a, $($generic_ids)*> OccupiedEntry<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Return a reference to the values associated with this entry's
+            /// key.
+            #[must_use]
+            pub fn get(&self) -> &$values {
+                self.values
+            }
+
+            /// Convert the entry into a reference to the values associated
+            /// with this entry's key, with a lifetime bound to the multimap.
+            #[must_use]
+            pub fn into_ref(self) -> &'a $values {
+                self.values
+            }
+
+            /// Remove the key and all associated values from the multimap.
+            ///
+            /// Returns the key and all values that were associated with it.
+            #[must_use]
+            pub fn remove_entry(self) -> (K, $values) {
+                let values = std::mem::take(self.values);
+                *self.len -= values.len();
+                *self.keys_len -= 1;
+                (self.key, values)
+            }
+
+            fn values_mut(&mut self) -> &mut $values {
+                self.values
+            }
+        }
+
+        impl<'a, $($generic_ids)*> VacantEntry<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn insert_values(self, values: $values) -> &'a $values {
+                *self.len += values.len();
+                *self.keys_len += 1;
+                *self.values = values;
+                self.values
+            }
+        }
+
+        /// An iterator over the keys of a multimap.
+        ///
+        /// This `struct` is created by the `keys` method on multimap.
+        pub struct Keys<'a, $($generic_ids)*> {
+            outer: std::iter::Enumerate<std::slice::Iter<'a, $values>>,
+            len: usize,
+            _marker: std::marker::PhantomData<K>,
+        }
+
+        impl<$($generic_ids)*> Iterator for Keys<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = K;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.len == 0 {
+                    return None;
+                }
+                let (index, _) = self.outer.find(|(_, values)| !values.is_empty())?;
+                self.len -= 1;
+                Some(K::from_index(index))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for Keys<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for Keys<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_ids)*> Clone for Keys<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Keys {
+                    outer: self.outer.clone(),
+                    len: self.len,
+                    _marker: std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> std::fmt::Debug for Keys<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey + std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// An owning iterator over the keys of a multimap.
+        ///
+        /// This `struct` is created by the `into_keys` method on multimap.
+        pub struct IntoKeys<$($generic_ids)*> {
+            outer: std::iter::Enumerate<std::vec::IntoIter<$values>>,
+            len: usize,
+            _marker: std::marker::PhantomData<K>,
+        }
+
+        impl<$($generic_ids)*> Iterator for IntoKeys<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = K;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.len == 0 {
+                    return None;
+                }
+                let (index, _) = self.outer.find(|(_, values)| !values.is_empty())?;
+                self.len -= 1;
+                Some(K::from_index(index))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for IntoKeys<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for IntoKeys<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::fmt::Debug for IntoKeys<$($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("IntoKeys").field("len", &self.len).finish_non_exhaustive()
+            }
+        }
+    };
+    (@storage index_key, $type:ident, ($($generic_ids:tt)*), $values:ty, $values_ctx:expr, ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            /// Returns the number of keys the multimap can hold without
+            /// reallocating.
+            ///
+            /// The index range of keys that the multimap can hold without
+            /// reallocating is `0..key_capacity`.
+            #[must_use]
+            pub fn key_capacity(&self) -> usize {
+                self.inner.capacity()
+            }
+
+            /// Reserve capacity for `additional` more keys.
+            pub fn reserve(&mut self, additional: usize) {
+                self.inner.reserve(additional);
+            }
+
+            /// Return an iterator over the keys of the multimap, following the
+            /// natural order of the keys.
+            #[must_use]
+            pub fn keys(&self) -> $crate::vec_map::Keys<'_, K, $values> {
+                self.inner.keys()
+            }
+
+            /// Return an owning iterator over the keys of the multimap,
+            /// following the natural order of the keys.
+            #[must_use]
+            pub fn into_keys(self) -> IntoKeys<$($generic_ids)*> {
+                IntoKeys {
+                    inner: self.inner.into_iter(),
+                }
+            }
+
+            /// Return a reference to the underlying [`VecMap`](crate::VecMap).
+            #[must_use]
+            pub const fn as_map(&self) -> &$crate::VecMap<K, $values> {
+                &self.inner
+            }
+
+            /// Return the underlying [`VecMap`](crate::VecMap).
+            #[must_use]
+            pub fn into_map(self) -> $crate::VecMap<K, $values> {
+                self.inner
+            }
+
+            fn values_of(&self, key: K) -> Option<&$values> {
+                self.inner.get(key)
+            }
+
+            fn values_of_mut(&mut self, key: K) -> Option<&mut $values> {
+                self.inner.get_mut(key)
+            }
+
+            /// Remove `key`, whose values became empty.
+            fn remove_emptied(&mut self, key: K) {
+                self.inner.remove(key);
+            }
+
+            /// Call `f` with the values of every key, keys whose values became
+            /// empty are removed.
+            fn retain_values<F>(&mut self, mut f: F)
+            where
+                F: FnMut(K, &mut $values),
+            {
+                self.inner.retain(|key, values| {
+                    f(key, values);
+                    !values.is_empty()
+                });
+            }
+
+            fn take_values(&mut self, key: K) -> Option<$values> {
+                self.inner.remove(key)
+            }
+        }
+
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            #[doc = concat!("Return a guard that gives mutable access to the `", stringify!($values), "` stored for `key`, if it is present, else `None`.")]
+            ///
+            /// See [`ValuesGuard`] for how the multimap is updated when the
+            /// guard is dropped.
+            pub fn get_mut(&mut self, key: K) -> Option<ValuesGuard<'_, $($generic_ids)*>> {
+                let initial_len = self.inner.get(key)?.len();
+                Some(ValuesGuard {
+                    key,
+                    map: &mut self.inner,
+                    initial_len,
+                    len: &mut self.len,
+                })
+            }
+
+            /// Get the given key's corresponding entry in the multimap for
+            /// in-place manipulation.
+            pub fn entry(&mut self, key: K) -> Entry<'_, $($generic_ids)*> {
+                let map = &mut self.inner;
+                let len = &mut self.len;
+                if map.contains_key(key) {
+                    Entry::Occupied(OccupiedEntry { key, map, len })
+                } else {
+                    Entry::Vacant(VacantEntry { key, map, len })
+                }
+            }
+
+            /// Return the values of `key` to insert into, creating them if the
+            /// key is not present.
+            fn values_for_insert(&mut self, key: K) -> &mut $values {
+                self.inner.entry(key).or_insert_with(|| $values_ctx)
+            }
+        }
+
+        impl<$($generic_ids)*> Default for $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            /// Creates an empty multimap.
+            fn default() -> Self {
+                Self {
+                    inner: $crate::VecMap::new(),
+                    len: 0,
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> From<$crate::VecMap<K, $values>> for $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            fn from(mut map: $crate::VecMap<K, $values>) -> Self {
+                map.retain(|_, values| !values.is_empty());
+                let len = map.values().map(|values| values.len()).sum();
+                Self { inner: map, len }
+            }
+        }
+
+        /// A guard that gives mutable access to the values of a single key.
+        ///
+        /// The guard dereferences to the underlying values collection. When it
+        /// is dropped, the length of the multimap is updated to reflect any
+        /// inserted or removed values, and the key is removed if no values
+        /// remain.
+        ///
+        /// This `struct` is created by the `get_mut` method on multimap.
+        pub struct ValuesGuard<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            key: K,
+            map: &'a mut $crate::VecMap<K, $values>,
+            initial_len: usize,
+            len: &'a mut usize,
+        }
+
+        impl<$($generic_ids)*> std::ops::Deref for ValuesGuard<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Target = $values;
+
+            fn deref(&self) -> &Self::Target {
+                self.map.get(self.key).expect("values are present until drop")
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::DerefMut for ValuesGuard<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                self.map.get_mut(self.key).expect("values are present until drop")
+            }
+        }
+
+        impl<$($generic_ids)*> Drop for ValuesGuard<'_, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            fn drop(&mut self) {
+                let current_len = self.len();
+                *self.len = *self.len - self.initial_len + current_len;
+                if current_len == 0 {
+                    self.map.remove(self.key);
+                }
+            }
+        }
+
+        /// A view into an occupied entry in a multimap. It is part of the
+        /// [`Entry`] enum.
+        pub struct OccupiedEntry<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            key: K,
+            map: &'a mut $crate::VecMap<K, $values>,
+            len: &'a mut usize,
+        }
+
+        /// A view into a vacant entry in a multimap. It is part of the
+        /// [`Entry`] enum.
+        pub struct VacantEntry<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            key: K,
+            map: &'a mut $crate::VecMap<K, $values>,
+            len: &'a mut usize,
+        }
+
+        impl<'a, $($generic_ids)*> OccupiedEntry<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Return a reference to the values associated with this entry's
+            /// key.
+            #[must_use]
+            pub fn get(&self) -> &$values {
+                self.map.get(self.key).expect("values are present in an occupied entry")
+            }
+
+            /// Convert the entry into a reference to the values associated
+            /// with this entry's key, with a lifetime bound to the multimap.
+            #[must_use]
+            pub fn into_ref(self) -> &'a $values {
+                self.map.get(self.key).expect("values are present in an occupied entry")
+            }
+
+            /// Remove the key and all associated values from the multimap.
+            ///
+            /// Returns the key and all values that were associated with it.
+            #[must_use]
+            pub fn remove_entry(self) -> (K, $values) {
+                let values = self.map.remove(self.key).expect("values are present in an occupied entry");
+                *self.len -= values.len();
+                (self.key, values)
+            }
+
+            fn values_mut(&mut self) -> &mut $values {
+                self.map.get_mut(self.key).expect("values are present in an occupied entry")
+            }
+        }
+
+        impl<'a, $($generic_ids)*> VacantEntry<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn insert_values(self, values: $values) -> &'a $values {
+                *self.len += values.len();
+                self.map.entry(self.key).or_insert(values)
+            }
+        }
+
+        /// An owning iterator over the keys of a multimap.
+        ///
+        /// This `struct` is created by the `into_keys` method on multimap.
+        pub struct IntoKeys<$($generic_ids)*> {
+            inner: $crate::vec_map::IntoIter<K, $values>,
+        }
+
+        impl<$($generic_ids)*> Iterator for IntoKeys<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = K;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map(|(key, _)| key)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for IntoKeys<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for IntoKeys<$($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::fmt::Debug for IntoKeys<$($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("IntoKeys").finish_non_exhaustive()
+            }
+        }
+    };
+
+    (@values_mut set, $key_kind:tt, $type:ident, ($($generic_ids:tt)*), $values:ty, ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*
+        {
+            /// Apply `f` to every value of the multimap in place, stopping at
+            /// the first error.
+            ///
+            /// The values of every visited key are reinserted after `f` has
+            /// been applied, so changed values end up in the right place of the
+            /// set. Values of the same key that become equal are merged into
+            /// one, [`Self::len()`] is updated accordingly. When `f` returns an
+            /// error, the value it was applied to keeps its changes and the
+            /// remaining values are left untouched.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error returned by `f`.
+            pub fn try_map_values_in_place<E, F>(&mut self, mut f: F) -> Result<(), E>
+            where
+                F: FnMut(&K, &mut V) -> Result<(), E>,
+            {
+                let mut result = Ok(());
+                let mut merged = 0;
+                self.retain_values(|key, values| {
+                    if result.is_err() {
+                        return;
+                    }
+                    let len = values.len();
+                    let taken: $values = std::mem::take(values);
+                    for mut value in taken {
+                        if result.is_ok() {
+                            result = f(&key, &mut value);
+                        }
+                        values.extend(std::iter::once(value));
+                    }
+                    merged += len - values.len();
+                });
+                self.len -= merged;
+                result
+            }
+        }
+    };
+    (@values_mut vec_equal, $key_kind:tt, $type:ident, ($($generic_ids:tt)*), $values:ty, ($($type_bounds:tt)*)) => {
+        /// A mutable iterator over the entries of a multimap.
+        ///
+        /// This `struct` is created by the `iter_mut` method on multimap.
+        pub struct IterMut<'a, $($generic_ids)*> {
+            outer: $crate::vec_multimap_impl!(@outer_type $key_kind, std::slice::IterMut<'a, $values>, $crate::vec_map::IterMut<'a, K, $values>),
+            inner: Option<(K, std::slice::IterMut<'a, V>)>,
+            len: usize,
+        }
+
+        impl<'a, $($generic_ids)*> Iterator for IterMut<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = (K, &'a mut V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((*key, value));
+                        }
+                    }
+                    let (key, values) = self.outer.next()?;
+                    self.inner = Some(($crate::vec_multimap_impl!(@key $key_kind, key), values.iter_mut()));
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for IterMut<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for IterMut<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::fmt::Debug for IterMut<'_, $($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("IterMut").field("len", &self.len).finish_non_exhaustive()
+            }
+        }
+
+        /// A mutable iterator over the values of a multimap.
+        ///
+        /// This `struct` is created by the `values_mut` method on multimap.
+        pub struct ValuesMut<'a, $($generic_ids)*> {
+            inner: IterMut<'a, $($generic_ids)*>,
+        }
+
+        impl<'a, $($generic_ids)*> Iterator for ValuesMut<'a, $($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type Item = &'a mut V;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map(|(_, v)| v)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($generic_ids)*> ExactSizeIterator for ValuesMut<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::iter::FusedIterator for ValuesMut<'_, $($generic_ids)*> where K: $crate::IndexKey {}
+
+        impl<$($generic_ids)*> std::fmt::Debug for ValuesMut<'_, $($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("ValuesMut").field("len", &self.inner.len).finish_non_exhaustive()
+            }
+        }
+
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            /// Return an iterator over the key-value pairs of the multimap,
+            /// with mutable references to the values, following the
+            /// natural order of the keys.
+            pub fn iter_mut(&mut self) -> IterMut<'_, $($generic_ids)*> {
+                IterMut {
+                    outer: $crate::vec_multimap_impl!(@outer $key_kind, self.inner.iter_mut()),
+                    inner: None,
+                    len: self.len,
+                }
+            }
+
+            /// Return an iterator over mutable references to the values of the
+            /// multimap, following the natural order of the keys.
+            pub fn values_mut(&mut self) -> ValuesMut<'_, $($generic_ids)*> {
+                ValuesMut {
+                    inner: self.iter_mut(),
+                }
+            }
+        }
+
+        impl<'a, $($generic_ids)*> IntoIterator for &'a mut $type<$($generic_ids)*>
+        where
+            K: $crate::IndexKey,
+        {
+            type IntoIter = IterMut<'a, $($generic_ids)*>;
+            type Item = (K, &'a mut V);
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter_mut()
+            }
+        }
+    };
+
+    (@insert set) => {
+        /// Insert the value into the multimap.
+        ///
+        /// If an equivalent entry already exists in the multimap, it returns
+        /// `false` leaving the original value in the set and without altering its
+        /// insertion order. Otherwise, it inserts the new entry and returns `true`.
+        #[inline]
+        pub fn insert(&mut self, key: K, value: V) -> bool {
+            if self.values_for_insert(key).insert(value) {
+                self.len += 1;
+                true
+            } else {
+                false
+            }
+        }
+    };
+    (@insert vec_equal) => {
+        /// Insert the value into the multimap.
+        ///
+        /// Allows duplicates.
+        #[inline]
+        pub fn insert(&mut self, key: K, value: V) {
+            self.values_for_insert(key).push(value);
+            self.len += 1;
+        }
+    };
+
+    (@occupied_insert set) => {
+        /// Insert `value` for this entry's key.
+        ///
+        /// If an equivalent value already exists for this key, it returns
+        /// `false` leaving the original value in place. Otherwise, it inserts
+        /// the new value and returns `true`.
+        pub fn insert(&mut self, value: V) -> bool {
+            if self.values_mut().insert(value) {
+                *self.len += 1;
+                true
+            } else {
+                false
+            }
+        }
+    };
+    (@occupied_insert vec_equal) => {
+        /// Insert `value` for this entry's key.
+        ///
+        /// Allows duplicates.
+        pub fn insert(&mut self, value: V) {
+            self.values_mut().push(value);
+            *self.len += 1;
+        }
+    };
+}
+
+// ////////////////////////////////////
+// / *Set values specific methods
+// ////////////////////////////////////
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! set_multimap_algebra_impl {
+    ($type:ident, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::set_multimap_algebra_impl!([$($generic_ids)*] $type, ($($generic_ids)*), $($rest)*);
+    };
+    ([$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_params)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            /// Visits the key-value pairs representing the difference, i.e.,
+            /// the pairs that are in `self` but not in `other`.
+            pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, $($generic_ids)*> {
+                Difference {
+                    iter: self.iter(),
+                    other,
+                }
+            }
+
+            /// Visits the key-value pairs representing the symmetric
+            /// difference, i.e., the pairs that are in `self` or in `other`
+            /// but not in both.
+            pub fn symmetric_difference<'a>(
+                &'a self,
+                other: &'a Self,
+            ) -> SymmetricDifference<'a, $($generic_ids)*> {
+                SymmetricDifference {
+                    iter: self.difference(other).chain(other.difference(self)),
+                }
+            }
+
+            /// Visits the key-value pairs representing the intersection, i.e.,
+            /// the pairs that are both in `self` and `other`.
+            ///
+            /// Pairs are visited in the iteration order of `self`.
+            pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, $($generic_ids)*> {
+                Intersection {
+                    iter: self.iter(),
+                    other,
+                }
+            }
+
+            /// Visits the key-value pairs representing the union, i.e., all the
+            /// pairs in `self` or `other`, without duplicates.
+            ///
+            /// All pairs of `self` are visited first, followed by the pairs
+            /// that are only in `other`.
+            pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, $($generic_ids)*> {
+                Union {
+                    iter: self.iter().chain(other.difference(self)),
+                }
+            }
+
+            /// Returns `true` if `self` has no key-value pairs in common with
+            /// `other`. This is equivalent to checking for an empty
+            /// intersection.
+            pub fn is_disjoint(&self, other: &Self) -> bool {
+                if self.len() <= other.len() {
+                    self.iter().all(|(key, value)| !other.contains(key, value))
+                } else {
+                    other.iter().all(|(key, value)| !self.contains(key, value))
+                }
+            }
+
+            /// Returns `true` if all key-value pairs of `self` are also in
+            /// `other`.
+            pub fn is_subset(&self, other: &Self) -> bool {
+                self.len() <= other.len() && self.iter().all(|(key, value)| other.contains(key, value))
+            }
+
+            /// Returns `true` if all key-value pairs of `other` are also in
+            /// `self`.
+            pub fn is_superset(&self, other: &Self) -> bool {
+                other.is_subset(self)
+            }
+
+            /// Retains only the key-value pairs that are also in `other`.
+            ///
+            /// Keys without remaining values are removed.
+            pub fn intersect_with(&mut self, other: &Self) {
+                self.retain(|key, value| other.contains(key, value));
+            }
+
+            /// Removes all key-value pairs that are in `other`.
+            ///
+            /// Keys without remaining values are removed.
+            pub fn subtract(&mut self, other: &Self) {
+                self.retain(|key, value| !other.contains(key, value));
+            }
+        }
+
+        impl<$($generic_params)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: Clone,
+            V: Clone,
+        {
+            /// Inserts all key-value pairs of `other` that are not yet in
+            /// `self`.
+            pub fn union_with(&mut self, other: &Self) {
+                for (key, value) in other {
+                    if !self.contains(key, value) {
+                        self.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::ops::BitOr<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: Clone,
+            V: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the union of `self` and `rhs` as a new multimap.
+            fn bitor(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.union(rhs).map(|(key, value)| (key.clone(), value.clone())).collect()
+            }
+        }
+
+        impl<$($generic_params)*> std::ops::BitAnd<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: Clone,
+            V: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the intersection of `self` and `rhs` as a new multimap.
+            fn bitand(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.intersection(rhs).map(|(key, value)| (key.clone(), value.clone())).collect()
+            }
+        }
+
+        impl<$($generic_params)*> std::ops::BitXor<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: Clone,
+            V: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the symmetric difference of `self` and `rhs` as a new
+            /// multimap.
+            fn bitxor(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.symmetric_difference(rhs).map(|(key, value)| (key.clone(), value.clone())).collect()
+            }
+        }
+
+        impl<$($generic_params)*> std::ops::Sub<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: Clone,
+            V: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the difference of `self` and `rhs` as a new multimap.
+            fn sub(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.difference(rhs).map(|(key, value)| (key.clone(), value.clone())).collect()
+            }
+        }
+
+        /// A lazy iterator producing the key-value pairs in the difference of
+        /// multimaps.
+        ///
+        /// This `struct` is created by the `difference` method on multimap.
+        pub struct Difference<'a, $($generic_params)*>
+        where
+            K: 'a,
+            V: 'a,
+        {
+            iter: Iter<'a, $($generic_ids)*>,
+            other: &'a $type<$($generic_ids)*>,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for Difference<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let other = self.other;
+                self.iter.find(|(key, value)| !other.contains(*key, *value))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (0, Some(self.iter.len()))
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Difference<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+        }
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for Difference<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Difference {
+                    iter: self.iter.clone(),
+                    other: self.other,
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Difference<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// A lazy iterator producing the key-value pairs in the intersection of
+        /// multimaps.
+        ///
+        /// This `struct` is created by the `intersection` method on multimap.
+        pub struct Intersection<'a, $($generic_params)*>
+        where
+            K: 'a,
+            V: 'a,
+        {
+            iter: Iter<'a, $($generic_ids)*>,
+            other: &'a $type<$($generic_ids)*>,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for Intersection<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let other = self.other;
+                self.iter.find(|(key, value)| other.contains(*key, *value))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (0, Some(self.iter.len().min(self.other.len())))
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Intersection<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+        }
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for Intersection<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Intersection {
+                    iter: self.iter.clone(),
+                    other: self.other,
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Intersection<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// A lazy iterator producing the key-value pairs in the symmetric
+        /// difference of multimaps.
+        ///
+        /// This `struct` is created by the `symmetric_difference` method on
+        /// multimap.
+        pub struct SymmetricDifference<'a, $($generic_params)*>
+        where
+            K: 'a,
+            V: 'a,
+        {
+            iter: std::iter::Chain<Difference<'a, $($generic_ids)*>, Difference<'a, $($generic_ids)*>>,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for SymmetricDifference<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.iter.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for SymmetricDifference<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+        }
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for SymmetricDifference<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                SymmetricDifference {
+                    iter: self.iter.clone(),
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for SymmetricDifference<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// A lazy iterator producing the key-value pairs in the union of
+        /// multimaps.
+        ///
+        /// This `struct` is created by the `union` method on multimap.
+        pub struct Union<'a, $($generic_params)*>
+        where
+            K: 'a,
+            V: 'a,
+        {
+            iter: std::iter::Chain<Iter<'a, $($generic_ids)*>, Difference<'a, $($generic_ids)*>>,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for Union<'a, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.iter.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Union<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+        }
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for Union<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Union {
+                    iter: self.iter.clone(),
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Union<'_, $($generic_ids)*>
+        where
+            $($type_bounds)*,
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+    };
+}
+
+// ////////////////////////////////////
+// / Vec / IndexSet values specific methods
+// ////////////////////////////////////
+
+// TODO consider adding more mutators
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! insert {
+    (set $values_ctx:expr) => {
+        /// Insert the value into the multimap.
+        ///
+        /// If an equivalent entry already exists in the multimap, it returns
+        /// `false` leaving the original value in the set and without altering its
+        /// insertion order. Otherwise, it inserts the new entry and returns `true`.
+        #[inline]
+        pub fn insert(&mut self, key: K, value: V) -> bool {
+            if self
+                .inner
+                .entry(key)
+                .or_insert_with(|| $values_ctx)
+                .insert(value)
+            {
+                self.len += 1;
+                true
+            } else {
+                false
+            }
+        }
+    };
+
+    (small_set $values_ctx:expr) => {
+        /// Insert the value into the multimap.
+        ///
+        /// If an equivalent entry already exists in the multimap, it returns
+        /// `false` leaving the original value in the set and without altering its
+        /// insertion order. Otherwise, it inserts the new entry and returns `true`.
+        #[inline]
+        pub fn insert(&mut self, key: K, value: V) -> bool {
+            let values = self.inner.entry(key).or_insert_with(|| $values_ctx);
+            let before = values.len();
+            values.insert(value);
+            if values.len() > before {
+                self.len += 1;
+                true
+            } else {
+                false
+            }
+        }
+    };
+
+    (vec_equal $values_ctx:expr) => {
+        $crate::vec_insert!($values_ctx);
+    };
+    (vec_equivalent $values_ctx:expr) => {
+        $crate::vec_insert!($values_ctx);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! vec_insert {
+    ($values_ctx:expr) => {
+        /// Insert the value into the multimap.
+        ///
+        /// Allows duplicates.
+        #[inline]
+        pub fn insert(&mut self, key: K, value: V) {
+            self.inner
+                .entry(key)
+                .or_insert_with(|| $values_ctx)
+                .push(value);
+            self.len += 1;
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! insert_full {
+    (set $values_ctx:expr) => {
+        /// Insert a key-value pair in the multimap, and get its indices.
+        ///
+        /// If an equivalent key already exists in the multimap, the key
+        /// remains and retains its place in the order. Additionally, if an
+        /// equivalent value already exists for that particular key the value
+        /// remains and retains its place in the order, and `false` is
+        /// returned.
+        ///
+        /// If no equivalent key existed in the multimap the new key is
+        /// inserted last in order. If no equivalent value existed in the
+        /// multimap for this key, the value is inserted last in order.
+        ///
+        /// Returns `(key index, values index, success)` where success is
+        /// `true` if the multimap changes as a result of calling this method.
+        pub fn insert_full(&mut self, key: K, value: V) -> (usize, usize, bool) {
+            match self.inner.get_full_mut(&key) {
+                Some((keys_index, _, values)) => {
+                    let (values_index, success) = values.insert_full(value);
+                    if success {
+                        self.len += 1;
+                    }
+                    (keys_index, values_index, success)
+                }
+                None => {
+                    let mut values = $values_ctx;
+                    values.insert(value);
+                    let (keys_index, _) = self.inner.insert_full(key, values);
+                    self.len += 1;
+                    (keys_index, 0, true)
+                }
+            }
+        }
+    };
+    (vec $values_ctx:expr) => {
+        /// Insert a key-value pair in the multimap, and get its indices.
+        ///
+        /// If an equivalent key already exists in the multimap, the key
+        /// remains and retains its place in the order. If no equivalent key
+        /// existed in the multimap the new key is inserted last in order.
+        ///
+        /// The value is inserted last in order in the values for this
+        /// particular key (duplicates are allowed).
+        ///
+        /// Returns `(key index, values index)`
+        pub fn insert_full(&mut self, key: K, value: V) -> (usize, usize) {
+            match self.inner.get_full_mut(&key) {
+                Some((keys_index, _, values)) => {
+                    let values_index = values.len();
+                    values.push(value);
+                    self.len += 1;
+                    (keys_index, values_index)
+                }
+                None => {
+                    let values = std::iter::once(value).collect();
+                    let (keys_index, _) = self.inner.insert_full(key, values);
+                    self.len += 1;
+                    (keys_index, 0)
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! values_contains {
+    (set, $values:ident, $value:ident) => {
+        $values.contains($value)
+    };
+
+    (small_set, $values:ident, $value:ident) => {
+        $values.contains($value)
+    };
+
+    (vec_equivalent, $values:ident, $value:ident) => {
+        $values.iter().find(|x| $value.equivalent(x)).is_some()
+    };
+
+    (vec_equal, $values:ident, $value:ident) => {
+        $values.iter().find(|&x| $value == x.borrow()).is_some()
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! values_remove {
+    // ordered keys, values set
+    (ordered, swap, set, $values:ident, $value:ident) => {
+        $values.swap_take($value)
+    };
+    (ordered, shift, set, $values:ident, $value:ident) => {
+        $values.shift_take($value)
+    };
+    // ordered keys, values vec
+    (ordered, shift, vec_equivalent, $values:ident, $value:ident) => {
+        $values
+            .iter()
+            .position(|x| $value.equivalent(x))
+            .map(|index| $values.remove(index)) // Vec::remove() shifts
+    };
+    (ordered, swap, vec_equivalent, $values:ident, $value:ident) => {
+        $values
+            .iter()
+            .position(|x| $value.equivalent(x))
+            .map(|index| $values.swap_remove(index))
+    };
+    // unordered keys, values set
+    (unordered, set, $values:ident, $value:ident) => {
+        $values.take($value)
+    };
+    (unordered, small_set, $values:ident, $value:ident) => {
+        $values.take($value)
+    };
+    // unordered keys, values vec
+    (unordered, vec_equal, $values:ident, $value:ident) => {
+        $values
+            .iter()
+            .position(|x| $value == x.borrow())
+            .map(|index| $values.remove(index))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_extend {
+    (btree, $type:tt, ($($generic_ids:tt)*), $inner_values_type:ty, ($($keys:tt)*), ($($values:tt)*), ($($keys_get:tt)*) )=> {
+        impl<$($generic_ids)*> Extend<(K, V)> for $type<$($generic_ids)*>
+        where
+            $($keys)*,
+            $($values)*,
+        {
+            fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+                iterable.into_iter().for_each(move |(k, v)| {
+                    self.insert(k, v);
+                });
+            }
+        }
+
+        impl<'a, $($generic_ids)*> Extend<(&'a K, &'a V)> for $type<$($generic_ids)*>
+        where
+            $($keys)* + Copy,
+            $($values)* + Copy,
+        {
+            fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iterable: I) {
+                self.extend(iterable.into_iter().map(|(&key, &value)| (key, value)));
+            }
+        }
+
+        impl<$($generic_ids)*> FromIterator<(K, V)> for $type<$($generic_ids)*>
+        where
+            $($keys)*,
+            $($values)*,
+        {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+                let mut map = Self::new();
+                map.extend(iterable);
+                map
+            }
+        }
+
+        impl<$($generic_ids)*> From<std::collections::BTreeMap<K, $inner_values_type>> for $type<$($generic_ids)*>
+        where
+            $($keys)*,
+            $($values)*,
+        {
+            fn from(mut map: std::collections::BTreeMap<K, $inner_values_type>) -> Self {
+                map.retain(|_k, v| !v.is_empty());
+                let len = map.iter().map(|(_k, v)| v.len()).sum();
+                $type { inner: map, len }
+            }
+        }
+
+        impl<$($generic_ids)*> Default for $type<$($generic_ids)*> {
+            /// Creates an empty multimap.
+            #[inline]
+            fn default() -> $type<$($generic_ids)*> {
+                $type::new()
+            }
+        }
+
+        impl<K, Q: ?Sized, V> std::ops::Index<&Q> for $type<$($generic_ids)*>
+        where
+            $($keys_get)*,
+            $($values)*,
+        {
+            type Output = $inner_values_type;
+
+            /// Returns a reference to the values container corresponding to the supplied key.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the key is not present in the multimap.
+            #[inline]
+            fn index(&self, key: &Q) -> &$inner_values_type {
+                self.get(key).expect("no entry found for key")
+            }
+        }
+    };
+    ($type:tt, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::multimap_extend!([$($generic_ids)*] $type, ($($generic_ids)*), $($rest)*);
+    };
+    ([$($generic_params:tt)*] $type:tt, ($($generic_ids:tt)*), $inner_keys_type:tt, $inner_values_type:ty, ($($keys:tt)*), ($($values:tt)*), ($($keys_get:tt)*) )=> {
+        impl<$($generic_params)*> Extend<(K, V)> for $type<$($generic_ids)*>
+        where
+            $($keys)*,
+            $($values)*,
+            S: BuildHasher + Default,
+        {
+            fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+                // extend does not attempt to reserve additional space because the space needed
+                // is dependent on the keys that are added
+                iterable.into_iter().for_each(move |(k, v)| {
+                    self.insert(k, v);
+                });
+            }
+        }
+
+        impl<'a, $($generic_params)*> Extend<(&'a K, &'a V)> for $type<$($generic_ids)*>
+        where
+            $($keys)* + Copy,
+            $($values)* + Copy,
+            S: BuildHasher + Default,
+        {
+            fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iterable: I) {
+                self.extend(iterable.into_iter().map(|(&key, &value)| (key, value)));
+            }
+        }
+
+        impl<$($generic_params)*> FromIterator<(K, V)> for $type<$($generic_ids)*>
+        where
+            $($keys)*,
+            $($values)*,
+            S: BuildHasher + Default,
+        {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+                // The number of pairs is only an upper bound of the number of
+                // keys, so the map starts empty and only grows when a new key
+                // is inserted.
+                let mut map = Self::default();
+                map.extend(iterable);
+                map
+            }
+        }
+
+        impl<$($generic_params)*> From<$inner_keys_type<K,$inner_values_type,S>> for $type<$($generic_ids)*>
+        where
+            $($keys)*,
+            $($values)*,
+            S: BuildHasher + Default,
+        {
+            fn from(mut map: $inner_keys_type<K,$inner_values_type,S>) -> Self {
+                map.retain(|_k, v| !v.is_empty());
+                let len = map.iter().map(|(_k, v)| v.len()).sum();
+                $type { inner: map, len }
+            }
+        }
+
+        impl<$($generic_params)*> Default for $type<$($generic_ids)*>
+        where
+            S: Default,
+        {
+            /// Creates an empty multimap, with the `Default` value for the hasher.
+            #[inline]
+            fn default() -> $type<$($generic_ids)*> {
+                $type::with_hasher(Default::default())
+            }
+        }
+
+        impl<Q: ?Sized, $($generic_params)*> std::ops::Index<&Q> for $type<$($generic_ids)*>
+        where
+            $($keys_get)*,
+            $($values)*,
+            S: BuildHasher + Default,
+        {
+            type Output = $inner_values_type;
+
+            /// Returns a reference to the values container corresponding to the supplied key.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the key is not present in the multimap.
+            #[inline]
+            fn index(&self, key: &Q) -> &$inner_values_type {
+                self.get(key).expect("no entry found for key")
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_eq {
+    (btree, list, $type:tt, ($($values_generics:tt)*)) => {
+        impl<K, V> $type<K, V>
+        where
+            K: Ord,
+        {
+            $crate::multimap_eq!(@list_eq_impl $type<K, V2>, [V2]);
+        }
+
+        impl<K, V1, V2> PartialEq<$type<K, V2>> for $type<K, V1>
+        where
+            K: Ord,
+            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
+            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
+        {
+            fn eq(&self, other: &$type<K, V2>) -> bool {
+                self.eq_unordered(other)
+            }
+        }
+
+        impl<K, V> Eq for $type<K, V>
+        where
+            K: Ord,
+            V: $($values_generics)*,
+        {
+        }
+    };
+    (small, list, $type:tt, ($($values_generics:tt)*)) => {
+        impl<K, V, const C: usize, S> $type<K, V, C, S>
+        where
+            K: Hash + Eq,
+            S: BuildHasher,
+        {
+            $crate::multimap_eq!(
+                @list_eq_impl $type<K, V2, C2, S2>,
+                [V2, const C2: usize, S2: BuildHasher]
+            );
+        }
+
+        impl<K, V1, const C1: usize, S1, V2, const C2: usize, S2> PartialEq<$type<K, V2, C2, S2>>
+            for $type<K, V1, C1, S1>
+        where
+            K: Hash + Eq,
+            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
+            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
+            S1: BuildHasher + Default,
+            S2: BuildHasher + Default,
+        {
+            fn eq(&self, other: &$type<K, V2, C2, S2>) -> bool {
+                self.eq_unordered(other)
+            }
+        }
+
+        impl<K, V, const C: usize, S> Eq for $type<K, V, C, S>
+        where
+            K: Eq + Hash,
+            V: $($values_generics)*,
+            S: BuildHasher + Default,
+        {
+        }
+    };
+    (list, $type:tt, ($($values_generics:tt)*)) => {
+        impl<K, V, S> $type<K, V, S>
+        where
+            K: Hash + Eq,
+            S: BuildHasher,
+        {
+            $crate::multimap_eq!(@list_eq_impl $type<K, V2, S2>, [V2, S2: BuildHasher]);
+        }
+
+        impl<K, V1, S1, V2, S2> PartialEq<$type<K, V2, S2>> for $type<K, V1, S1>
+        where
+            K: Hash + Eq,
+            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
+            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
+            S1: BuildHasher + Default,
+            S2: BuildHasher + Default,
+        {
+            fn eq(&self, other: &$type<K, V2, S2>) -> bool {
+                self.eq_unordered(other)
+            }
+        }
+
+        impl<K, V, S> Eq for $type<K, V, S>
+        where
+            K: Eq + Hash,
+            V: $($values_generics)*,
+            S: BuildHasher + Default,
+        {
+        }
+    };
+    (@list_eq_impl $other:ty, [$($other_params:tt)*]) => {
+        /// Returns `true` if both multimaps contain the same keys, and each key
+        /// has the same values in the same order.
+        ///
+        /// The order of the keys is not taken into account.
+        #[must_use]
+        pub fn eq_ordered<$($other_params)*>(&self, other: &$other) -> bool
+        where
+            V: PartialEq<V2>,
+        {
+            self.len() == other.len()
+                && self.inner.len() == other.inner.len()
+                && self.inner.iter().all(|(key, values)| {
+                    other
+                        .inner
+                        .get(key)
+                        .is_some_and(|other_values| values[..] == other_values[..])
+                })
+        }
+
+        /// Returns `true` if both multimaps contain the same keys, and each key
+        /// has the same values with the same multiplicities, in any order.
+        ///
+        /// The order of the keys is not taken into account. This is the
+        /// equality used by `==`. The values of a key are compared pairwise,
+        /// which is quadratic in the number of values per key.
+        #[must_use]
+        pub fn eq_unordered<$($other_params)*>(&self, other: &$other) -> bool
+        where
+            V: PartialEq<V2>,
+        {
+            self.len() == other.len()
+                && self.inner.len() == other.inner.len()
+                && self.inner.iter().all(|(key, values)| {
+                    other.inner.get(key).is_some_and(|other_values| {
+                        $crate::multimap::slice_eq_unordered(values, other_values)
+                    })
+                })
+        }
+    };
+    (btree, $type:tt, ($($values_generics:tt)*)) => {
+        impl<K, V1, V2> PartialEq<$type<K, V2>> for $type<K, V1>
+        where
+            K: Ord,
+            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
+            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
+        {
+            fn eq(&self, other: &$type<K, V2>) -> bool {
+                if self.len() != other.len() {
+                    return false;
+                }
+                self.iter().all(|(key, value)| other.contains(key, value))
+            }
+        }
+
+        impl<K, V> Eq for $type<K, V>
+        where
+            K: Ord,
+            V: $($values_generics)*,
+        {
+        }
+    };
+    (small, $type:tt, ($($values_generics:tt)*)) => {
+        impl<K, V1, const C1: usize, S1, V2, const C2: usize, S2> PartialEq<$type<K, V2, C2, S2>>
+            for $type<K, V1, C1, S1>
+        where
+            K: Hash + Eq,
+            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
+            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
+            S1: BuildHasher + Default,
+            S2: BuildHasher + Default,
+        {
+            fn eq(&self, other: &$type<K, V2, C2, S2>) -> bool {
+                if self.len() != other.len() {
+                    return false;
+                }
+                self.iter().all(|(key, value)| other.contains(key, value))
+            }
+        }
+
+        impl<K, V, const C: usize, S> Eq for $type<K, V, C, S>
+        where
+            K: Eq + Hash,
+            V: $($values_generics)*,
+            S: BuildHasher + Default,
+        {
+        }
+    };
+    ($type:tt, ($($values_generics:tt)*)) => {
+        impl<K, V1, S1, V2, S2> PartialEq<$type<K, V2, S2>> for $type<K, V1, S1>
+        where
+            K: Hash + Eq,
+            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
+            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
+            S1: BuildHasher + Default,
+            S2: BuildHasher + Default,
+        {
+            fn eq(&self, other: &$type<K, V2, S2>) -> bool {
+                if self.len() != other.len() {
+                    return false;
+                }
+                self.iter().all(|(key, value)| other.contains(key, value))
+            }
+        }
+
+        impl<K, V, S> Eq for $type<K, V, S>
+        where
+            K: Eq + Hash,
+            V: $($values_generics)*,
+            S: BuildHasher + Default,
+        {
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_cross_eq {
+    (set, set, $($left:ident)::+, $($right:ident)::+) => {
+        $crate::multimap_cross_eq!(@contains $($left)::+, $($right)::+);
+        $crate::multimap_cross_eq!(@contains $($right)::+, $($left)::+);
+    };
+    (list, set, $($list:ident)::+, $($set:ident)::+) => {
+        $crate::multimap_cross_eq!(@pairs $($list)::+, $($set)::+);
+        $crate::multimap_cross_eq!(@pairs $($set)::+, $($list)::+);
+    };
+    (list, list, $($left:ident)::+, $($right:ident)::+) => {
+        $crate::multimap_cross_eq!(@list $($left)::+, $($right)::+);
+        $crate::multimap_cross_eq!(@list $($right)::+, $($left)::+);
+    };
+    // `other` has no duplicate pairs, all its pairs must be in `self`.
+    (@contains $($type:ident)::+, $($other:ident)::+) => {
+        impl<K, V, S1, S2> PartialEq<$($other)::+<K, V, S2>> for $($type)::+<K, V, S1>
+        where
+            K: std::hash::Hash + Eq,
+            V: std::hash::Hash + Eq,
+            S1: std::hash::BuildHasher + Default,
+            S2: std::hash::BuildHasher + Default,
+        {
+            fn eq(&self, other: &$($other)::+<K, V, S2>) -> bool {
+                self.len() == other.len() && other.iter().all(|(key, value)| self.contains(key, value))
+            }
+        }
+    };
+    // Compares the sets of pairs, duplicate pairs of a list multimap are
+    // ignored.
+    (@pairs $($type:ident)::+, $($other:ident)::+) => {
+        impl<K, V, S1, S2> PartialEq<$($other)::+<K, V, S2>> for $($type)::+<K, V, S1>
+        where
+            K: std::hash::Hash + Eq,
+            V: std::hash::Hash + Eq,
+            S1: std::hash::BuildHasher + Default,
+            S2: std::hash::BuildHasher + Default,
+        {
+            fn eq(&self, other: &$($other)::+<K, V, S2>) -> bool {
+                self.keys_len() == other.keys_len()
+                    && self.iter().all(|(key, value)| other.contains(key, value))
+                    && other.iter().all(|(key, value)| self.contains(key, value))
+            }
+        }
+    };
+    (@list $($type:ident)::+, $($other:ident)::+) => {
+        impl<K, V, S1, S2> PartialEq<$($other)::+<K, V, S2>> for $($type)::+<K, V, S1>
+        where
+            K: std::hash::Hash + Eq,
+            V: Eq,
+            S1: std::hash::BuildHasher + Default,
+            S2: std::hash::BuildHasher + Default,
+        {
+            fn eq(&self, other: &$($other)::+<K, V, S2>) -> bool {
+                self.len() == other.len()
+                    && self.keys_len() == other.keys_len()
+                    && self.as_map().iter().all(|(key, values)| {
+                        other.as_map().get(key).is_some_and(|other_values| {
+                            $crate::multimap::slice_eq_unordered(values, other_values)
+                        })
+                    })
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_from {
+    ($($from:ident)::+ => $($to:ident)::+, $to_inner:ty, ($($values:tt)*)) => {
+        impl<K, V, S> From<$($from)::+<K, V, S>> for $($to)::+<K, V, S>
+        where
+            K: std::hash::Hash + Eq,
+            V: $($values)*,
+            S: std::hash::BuildHasher + Default,
+        {
+            fn from(map: $($from)::+<K, V, S>) -> Self {
+                map.into_map()
+                    .into_iter()
+                    .map(|(key, values)| (key, values.into_iter().collect()))
+                    .collect::<$to_inner>()
+                    .into()
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_iter {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_iter!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $outer_iter:ty, $inner_iter:ty) => {
+        /// An iterator over the entries of a multimap.
+        ///
+        /// This struct is created by the `iter` method on multimap.
+        pub struct Iter<'a, $($generic_params)*> where K: 'a, V: 'a{
+            outer: $outer_iter,
+            inner: Option<(&'a K, $inner_iter)>,
+            back: Option<(&'a K, $inner_iter)>,
+            len: usize,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for Iter<'a, $($generic_ids)*> {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((*key, value));
+                        }
+                    }
+                    if let Some((key, values)) = self.outer.next() {
+                        self.inner = Some((key, values.iter()));
+                    } else {
+                        let (key, values) = self.back.as_mut()?;
+                        let value = values.next()?;
+                        self.len -= 1;
+                        return Some((*key, value));
+                    }
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_params)*> ExactSizeIterator for Iter<'_, $($generic_ids)*> {
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Iter<'_, $($generic_ids)*> {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for Iter<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Iter {
+                    outer: self.outer.clone(),
+                    inner: self.inner.clone(),
+                    back: self.back.clone(),
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Iter<'_, $($generic_ids)*>
+        where
+            K: std::fmt::Debug,
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// An iterator over the values of a multimap.
+        ///
+        /// This `struct` is created by the `values` method on multimap.
+        pub struct Values<'a, $($generic_params)*>{
+            inner: Iter<'a, $($generic_ids)*>,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for Values<'a, $($generic_ids)*>
+        {
+            type Item = &'a V;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map( |(_,v)| v)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<$($generic_params)*> ExactSizeIterator for Values<'_, $($generic_ids)*> {
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Values<'_, $($generic_ids)*> {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for Values<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Values {
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Values<'_, $($generic_ids)*>
+        where
+            V: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        impl<$($type_params)*> $type<$($type_generics),*> {
+            /// Return an iterator over the key-value pairs of the multimap.
+            #[must_use]
+            pub fn iter(&self) -> Iter<'_, $($generic_ids)*> {
+                let mut iter = self.inner.iter();
+                let inner = iter.next().map(|(k, v)| (k, v.iter()));
+                Iter {
+                    outer: iter,
+                    inner,
+                    back: None,
+                    len: self.len,
+                }
+            }
+
+            /// Return an iterator over the values of the multimap.
+            #[must_use]
+            pub fn values(&self) -> Values<'_, $($generic_ids)*> {
+                Values {
+                    inner: self.iter(),
+                }
+            }
+        }
+
+        impl<'a, $($type_params)*> IntoIterator for &'a $type<$($type_generics),*> {
+            type IntoIter = Iter<'a, $($generic_ids)*>;
+            type Item = (&'a K, &'a V);
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_keys {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_keys!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $inner_iter:ty) => {
+        /// An iterator over the keys of a multimap.
+        ///
+        /// This `struct` is created by the `keys` method on multimap.
+        pub struct Keys<'a, $($generic_params)*> {
+            inner: $inner_iter,
+        }
+
+        impl<'a, $($generic_params)*> Iterator for Keys<'a, $($generic_ids)*> {
+            type Item = &'a K;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
+            }
+        }
+
+        impl<$($generic_params)*> ExactSizeIterator for Keys<'_, $($generic_ids)*> {
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Keys<'_, $($generic_ids)*> {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<$($generic_params)*> Clone for Keys<'_, $($generic_ids)*> {
+            fn clone(&self) -> Self {
+                Keys {
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+
+        impl<$($generic_params)*> std::fmt::Debug for Keys<'_, $($generic_ids)*>
+        where
+            K: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        impl<$($type_params)*> $type<$($type_generics),*> {
+            /// Return an iterator over the keys of the multimap.
+            #[must_use]
+            pub fn keys(&self) -> Keys<'_, $($generic_ids)*> {
+                Keys {
+                    inner: self.inner.keys(),
+                }
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_into_iterator {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_into_iterator!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $outer_iter:ty, $inner_iter:ty) => {
+        /// An owning iterator over the entries of a multimap.
+        #[derive(Debug)]
+        pub struct IntoIter<$($generic_params)*> {
+            outer: $outer_iter,
+            inner: Option<(K, $inner_iter)>,
+            back: Option<(K, $inner_iter)>,
+            len: usize,
+        }
+
+        impl<$($generic_params)*> Iterator for IntoIter<$($generic_ids)*>
+        where
+            K: Clone,
+        {
+            type Item = (K, V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((key.clone(), value));
+                        }
+                    }
+                    if let Some((key, values)) = self.outer.next() {
+                        self.inner = Some((key, values.into_iter()));
+                    } else {
+                        let (key, values) = self.back.as_mut()?;
+                        let value = values.next()?;
+                        self.len -= 1;
+                        return Some((key.clone(), value));
+                    }
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_params)*> ExactSizeIterator for IntoIter<$($generic_ids)*>
+        where
+            K: Clone,
+        {
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for IntoIter<$($generic_ids)*>
+        where
+           K: Clone,
+        {}
+
+        impl<$($type_params)*> IntoIterator for $type<$($type_generics),*>
+        where
+            K: Clone,
+        {
+            type Item = (K, V);
+
+            type IntoIter = IntoIter<$($generic_ids)*>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                let mut iter = self.inner.into_iter();
+                let inner = iter.next().map(|(k, v)| (k, v.into_iter()));
+                IntoIter {
+                    outer: iter,
+                    inner,
+                    back: None,
+                    len: self.len,
+                }
+            }
+        }
+
+        /// An owning iterator over the values of a multimap.
+        ///
+        /// This `struct` is created by the `into_values` method on multimap.
+        #[derive(Debug)]
+        pub struct IntoValues<$($generic_params)*> {
+            outer: $outer_iter,
+            inner: Option<$inner_iter>,
+            back: Option<$inner_iter>,
+            len: usize,
+        }
+
+        impl<$($generic_params)*> Iterator for IntoValues<$($generic_ids)*> {
+            type Item = V;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some(values) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some(value);
+                        }
+                    }
+                    if let Some((_key, values)) = self.outer.next() {
+                        self.inner = Some(values.into_iter());
+                    } else {
+                        let value = self.back.as_mut()?.next()?;
+                        self.len -= 1;
+                        return Some(value);
+                    }
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_params)*> ExactSizeIterator for IntoValues<$($generic_ids)*> {
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<$($generic_params)*> std::iter::FusedIterator for IntoValues<$($generic_ids)*> {}
+
+        impl<$($type_params)*> $type<$($type_generics),*> {
+            /// Return an iterator over the values of the multimap.
+            #[must_use]
+            pub fn into_values(self) -> IntoValues<$($generic_ids)*> {
+                let mut iter = self.inner.into_iter();
+                let inner = iter.next().map(|(_k, v)| v.into_iter());
+                IntoValues {
+                    outer: iter,
+                    inner,
+                    back: None,
+                    len: self.len,
+                }
+            }
+        }
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
-macro_rules! values_remove {
-    // ordered keys, values set
-    (ordered, swap, set, $values:ident, $value:ident) => {
-        $values.swap_take($value)
-    };
-    (ordered, shift, set, $values:ident, $value:ident) => {
-        $values.shift_take($value)
-    };
-    // ordered keys, values vec
-    (ordered, shift, vec_equivalent, $values:ident, $value:ident) => {
-        $values
-            .iter()
-            .position(|x| $value.equivalent(x))
-            .map(|index| $values.remove(index)) // Vec::remove() shifts
-    };
-    (ordered, swap, vec_equivalent, $values:ident, $value:ident) => {
-        $values
-            .iter()
-            .position(|x| $value.equivalent(x))
-            .map(|index| $values.swap_remove(index))
-    };
-    // unordered keys, values set
-    (unordered, set, $values:ident, $value:ident) => {
-        $values.take($value)
-    };
-    // unordered keys, values vec
-    (unordered, vec_equal, $values:ident, $value:ident) => {
-        $values
-            .iter()
-            .position(|x| $value == x.borrow())
-            .map(|index| $values.remove(index))
+macro_rules! impl_into_keys {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_into_keys!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
     };
-}
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $inner_iter:ty) => {
+        /// An owning iterator over the keys of a multimap.
+        ///
+        /// This `struct` is created by the `into_keys` method on multimap.
+        #[derive(Debug)]
+        pub struct IntoKeys<$($generic_params)*> {
+            inner: $inner_iter,
+        }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! multimap_extend {
-    ($type:tt, ($($generic_ids:tt)*), $inner_keys_type:tt, $inner_values_type:ty, ($($keys:tt)*), ($($values:tt)*), ($($keys_get:tt)*) )=> {
-        impl<$($generic_ids)*> Extend<(K, V)> for $type<$($generic_ids)*>
-        where
-            $($keys)*,
-            $($values)*,
-            S: BuildHasher + Default,
-        {
-            fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
-                let iter = iterable.into_iter();
-                let reserve = iter.size_hint().0.div_ceil(2);
-                self.reserve(reserve);
-                iter.for_each(move |(k, v)| {
-                    self.insert(k, v);
-                });
+        impl<$($generic_params)*> Iterator for IntoKeys<$($generic_ids)*> {
+            type Item = K;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
             }
         }
 
-        impl<'a, $($generic_ids)*> Extend<(&'a K, &'a V)> for $type<$($generic_ids)*>
-        where
-            $($keys)* + Copy,
-            $($values)* + Copy,
-            S: BuildHasher + Default,
-        {
-            fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iterable: I) {
-                self.extend(iterable.into_iter().map(|(&key, &value)| (key, value)));
+        impl<$($generic_params)*> ExactSizeIterator for IntoKeys<$($generic_ids)*> {
+            fn len(&self) -> usize {
+                self.inner.len()
             }
         }
 
-        impl<$($generic_ids)*> FromIterator<(K, V)> for $type<$($generic_ids)*>
-        where
-            $($keys)*,
-            $($values)*,
-            S: BuildHasher + Default,
-        {
-            fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
-                let iter = iterable.into_iter();
-                let (low, _) = iter.size_hint();
-                // TODO this resizing has a high chance of over provisioning
-                let mut map = Self::with_key_capacity_and_hasher(low, <_>::default());
-                map.extend(iter);
-                map
+        impl<$($generic_params)*> std::iter::FusedIterator for IntoKeys<$($generic_ids)*> {}
+
+        impl<$($type_params)*> $type<$($type_generics),*> {
+            /// Return an owning iterator over the keys of the multimap.
+            #[must_use]
+            pub fn into_keys(self) -> IntoKeys<$($generic_ids)*> {
+                IntoKeys {
+                    inner: self.inner.into_keys(),
+                }
             }
         }
+    }
+}
 
-        impl<$($generic_ids)*> From<$inner_keys_type<K,$inner_values_type,S>> for $type<$($generic_ids)*>
-        where
-            $($keys)*,
-            $($values)*,
-            S: BuildHasher + Default,
-        {
-            fn from(mut map: $inner_keys_type<K,$inner_values_type,S>) -> Self {
-                map.retain(|_k, v| !v.is_empty());
-                let len = map.iter().map(|(_k, v)| v.len()).sum();
-                $type { inner: map, len }
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_double_ended {
+    ($type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*)) => {
+        $crate::impl_double_ended!(
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*)
+        );
+    };
+    ([$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*)) => {
+        impl<$($generic_params)*> DoubleEndedIterator for Iter<'_, $($generic_ids)*> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.back {
+                        if let Some(value) = values.next_back() {
+                            self.len -= 1;
+                            return Some((*key, value));
+                        }
+                    }
+                    if let Some((key, values)) = self.outer.next_back() {
+                        self.back = Some((key, values.iter()));
+                    } else {
+                        let (key, values) = self.inner.as_mut()?;
+                        let value = values.next_back()?;
+                        self.len -= 1;
+                        return Some((*key, value));
+                    }
+                }
             }
         }
 
-        impl<$($generic_ids)*> Default for $type<$($generic_ids)*>
-        where
-            S: Default,
-        {
-            /// Creates an empty multimap, with the `Default` value for the hasher.
-            #[inline]
-            fn default() -> $type<$($generic_ids)*> {
-                $type::with_hasher(Default::default())
+        impl<$($generic_params)*> DoubleEndedIterator for Values<'_, $($generic_ids)*> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back().map(|(_, v)| v)
             }
         }
 
-        impl<K, Q: ?Sized, V, S> std::ops::Index<&Q> for $type<$($generic_ids)*>
-        where
-            $($keys_get)*,
-            $($values)*,
-            S: BuildHasher + Default,
-        {
-            type Output = $inner_values_type;
-
-            /// Returns a reference to the values container corresponding to the supplied key.
-            ///
-            /// # Panics
-            ///
-            /// Panics if the key is not present in the multimap.
-            #[inline]
-            fn index(&self, key: &Q) -> &$inner_values_type {
-                self.get(key).expect("no entry found for key")
+        impl<$($generic_params)*> DoubleEndedIterator for Keys<'_, $($generic_ids)*> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back()
             }
         }
-    };
-}
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! multimap_eq {
-    ($type:tt, ($($values_generics:tt)*)) => {
-        impl<K, V1, S1, V2, S2> PartialEq<$type<K, V2, S2>> for $type<K, V1, S1>
+        impl<$($generic_params)*> DoubleEndedIterator for IntoIter<$($generic_ids)*>
         where
-            K: Hash + Eq,
-            V1: $($values_generics)* + PartialEq<V2> + Borrow<V2>,
-            V2: $($values_generics)* + PartialEq<V1> + Borrow<V1>,
-            S1: BuildHasher + Default,
-            S2: BuildHasher + Default,
+            K: Clone,
         {
-            fn eq(&self, other: &$type<K, V2, S2>) -> bool {
-                if self.len() != other.len() {
-                    return false;
+            fn next_back(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.back {
+                        if let Some(value) = values.next_back() {
+                            self.len -= 1;
+                            return Some((key.clone(), value));
+                        }
+                    }
+                    if let Some((key, values)) = self.outer.next_back() {
+                        self.back = Some((key, values.into_iter()));
+                    } else {
+                        let (key, values) = self.inner.as_mut()?;
+                        let value = values.next_back()?;
+                        self.len -= 1;
+                        return Some((key.clone(), value));
+                    }
                 }
-                self.iter().all(|(key, value)| other.contains(key, value))
             }
         }
 
-        impl<K, V, S> Eq for $type<K, V, S>
-        where
-            K: Eq + Hash,
-            V: $($values_generics)*,
-            S: BuildHasher + Default,
-        {
+        impl<$($generic_params)*> DoubleEndedIterator for IntoValues<$($generic_ids)*> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some(values) = &mut self.back {
+                        if let Some(value) = values.next_back() {
+                            self.len -= 1;
+                            return Some(value);
+                        }
+                    }
+                    if let Some((_key, values)) = self.outer.next_back() {
+                        self.back = Some(values.into_iter());
+                    } else {
+                        let value = self.inner.as_mut()?.next_back()?;
+                        self.len -= 1;
+                        return Some(value);
+                    }
+                }
+            }
+        }
+
+        impl<$($generic_params)*> DoubleEndedIterator for IntoKeys<$($generic_ids)*> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back()
+            }
         }
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
-macro_rules! impl_iter {
-    ($type:tt, ($($generic_ids:tt)*), $outer_iter:ty, $inner_iter:ty) => {
-        /// An iterator over the entries of a multimap.
+macro_rules! impl_values_mut {
+    ($kind:tt, $type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_values_mut!(
+            $kind,
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    (list, [$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $outer_iter:ty) => {
+        /// A mutable iterator over the entries of a multimap.
         ///
-        /// This struct is created by the `iter` method on multimap.
-        pub struct Iter<'a, $($generic_ids)*> where K: 'a, V: 'a{
+        /// This `struct` is created by the `iter_mut` method on multimap.
+        pub struct IterMut<'a, $($generic_params)*> {
             outer: $outer_iter,
-            inner: Option<(&'a K, $inner_iter)>,
+            inner: Option<(&'a K, std::slice::IterMut<'a, V>)>,
             len: usize,
         }
 
-        impl<'a, $($generic_ids)*> Iterator for Iter<'a, $($generic_ids)*> {
-            type Item = (&'a K, &'a V);
+        impl<'a, $($generic_params)*> Iterator for IterMut<'a, $($generic_ids)*> {
+            type Item = (&'a K, &'a mut V);
 
             fn next(&mut self) -> Option<Self::Item> {
-                if let Some((current_key, inner_iter)) = &mut self.inner {
-                    let next = inner_iter.next();
-
-                    if let Some(next_value) = next {
-                        Some((current_key, next_value))
-                    } else {
-                        if let Some((key, values)) = self.outer.next() {
-                            let mut new_inner_iter = values.iter();
-                            let v = new_inner_iter.next().unwrap();
-                            self.inner = Some((key, new_inner_iter));
-
-                            Some((key, v))
-                        } else {
-                            None
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((*key, value));
                         }
                     }
-                } else {
-                    None
+                    let (key, values) = self.outer.next()?;
+                    self.inner = Some((key, values.iter_mut()));
                 }
             }
-        }
 
-        impl<$($generic_ids)*> ExactSizeIterator for Iter<'_, $($generic_ids)*> {
-            fn len(&self) -> usize {
-                self.len
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
             }
         }
 
-        impl<$($generic_ids)*> std::iter::FusedIterator for Iter<'_, $($generic_ids)*> {}
+        impl<$($generic_params)*> ExactSizeIterator for IterMut<'_, $($generic_ids)*> {}
 
-        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
-        impl<$($generic_ids)*> Clone for Iter<'_, $($generic_ids)*> {
-            fn clone(&self) -> Self {
-                Iter {
-                    outer: self.outer.clone(),
-                    inner: self.inner.clone(),
-                    len: self.len,
-                }
-            }
-        }
+        impl<$($generic_params)*> std::iter::FusedIterator for IterMut<'_, $($generic_ids)*> {}
 
-        impl<$($generic_ids)*> std::fmt::Debug for Iter<'_, $($generic_ids)*>
-        where
-            K: std::fmt::Debug,
-            V: std::fmt::Debug,
-        {
+        impl<$($generic_params)*> std::fmt::Debug for IterMut<'_, $($generic_ids)*> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.debug_list().entries(self.clone()).finish()
+                f.debug_struct("IterMut").field("len", &self.len).finish_non_exhaustive()
             }
         }
 
-        /// An iterator over the values of a multimap.
+        /// A mutable iterator over the values of a multimap.
         ///
-        /// This `struct` is created by the `values` method on multimap.
-        pub struct Values<'a, $($generic_ids)*>{
-            inner: Iter<'a, $($generic_ids)*>,
+        /// This `struct` is created by the `values_mut` method on multimap.
+        pub struct ValuesMut<'a, $($generic_params)*> {
+            inner: IterMut<'a, $($generic_ids)*>,
         }
 
-        impl<'a, $($generic_ids)*> Iterator for Values<'a, $($generic_ids)*>
-        {
-            type Item = &'a V;
+        impl<'a, $($generic_params)*> Iterator for ValuesMut<'a, $($generic_ids)*> {
+            type Item = &'a mut V;
 
             fn next(&mut self) -> Option<Self::Item> {
-                self.inner.next().map( |(_,v)| v)
+                self.inner.next().map(|(_, v)| v)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
             }
         }
 
-        impl<$($generic_ids)*> ExactSizeIterator for Values<'_, $($generic_ids)*> {
-            fn len(&self) -> usize {
-                self.inner.len()
+        impl<$($generic_params)*> ExactSizeIterator for ValuesMut<'_, $($generic_ids)*> {}
+
+        impl<$($generic_params)*> std::iter::FusedIterator for ValuesMut<'_, $($generic_ids)*> {}
+
+        impl<$($generic_params)*> std::fmt::Debug for ValuesMut<'_, $($generic_ids)*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("ValuesMut").field("len", &self.inner.len).finish_non_exhaustive()
             }
         }
 
-        impl<$($generic_ids)*> std::iter::FusedIterator for Values<'_, $($generic_ids)*> {}
+        impl<$($type_params)*> $type<$($type_generics),*> {
+            /// Return an iterator over the key-value pairs of the multimap,
+            /// with mutable references to the values.
+            pub fn iter_mut(&mut self) -> IterMut<'_, $($generic_ids)*> {
+                IterMut {
+                    outer: self.inner.iter_mut(),
+                    inner: None,
+                    len: self.len,
+                }
+            }
 
-        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
-        impl<$($generic_ids)*> Clone for Values<'_, $($generic_ids)*> {
-            fn clone(&self) -> Self {
-                Values {
-                    inner: self.inner.clone(),
+            /// Return an iterator over mutable references to the values of the
+            /// multimap.
+            pub fn values_mut(&mut self) -> ValuesMut<'_, $($generic_ids)*> {
+                ValuesMut {
+                    inner: self.iter_mut(),
                 }
             }
         }
 
-        impl<$($generic_ids)*> std::fmt::Debug for Values<'_, $($generic_ids)*>
+        impl<'a, $($type_params)*> IntoIterator for &'a mut $type<$($type_generics),*> {
+            type IntoIter = IterMut<'a, $($generic_ids)*>;
+            type Item = (&'a K, &'a mut V);
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter_mut()
+            }
+        }
+    };
+    (set, [$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $values:ty, ($($bounds:tt)*)) => {
+        impl<$($type_params)*> $type<$($type_generics),*>
         where
-            V: std::fmt::Debug,
+            $($bounds)*
+        {
+            /// Apply `f` to every value of the multimap in place, stopping at
+            /// the first error.
+            ///
+            /// The values of every visited key are reinserted after `f` has
+            /// been applied, so changed values end up in the right place of the
+            /// set. Values of the same key that become equal are merged into
+            /// one, [`Self::len()`] is updated accordingly. When `f` returns an
+            /// error, the value it was applied to keeps its changes and the
+            /// remaining values are left untouched.
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error returned by `f`.
+            pub fn try_map_values_in_place<E, F>(&mut self, mut f: F) -> Result<(), E>
+            where
+                F: FnMut(&K, &mut V) -> Result<(), E>,
+            {
+                let mut result = Ok(());
+                for (key, values) in &mut self.inner {
+                    if result.is_err() {
+                        break;
+                    }
+                    let len = values.len();
+                    let taken: $values = std::mem::take(values);
+                    for mut value in taken {
+                        if result.is_ok() {
+                            result = f(key, &mut value);
+                        }
+                        values.extend(std::iter::once(value));
+                    }
+                    self.len -= len - values.len();
+                }
+                result
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_drain {
+    ($kind:tt, $type:ident<$($type_generics:ident),*>, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_drain!(
+            $kind,
+            [$($type_generics),*] $type<$($type_generics),*>,
+            [$($generic_ids)*] ($($generic_ids)*),
+            $($rest)*
+        );
+    };
+    ($kind:tt, [$($type_params:tt)*] $type:ident<$($type_generics:ident),*>, [$($generic_params:tt)*] ($($generic_ids:tt)*), $outer_iter:ty, $inner_iter:ty, $values:ty, ($($bounds:tt)*), ($($keys_ref:tt)*)) => {
+        /// A draining iterator over the entries of a multimap.
+        ///
+        /// This `struct` is created by the `drain` method on multimap. The
+        /// drained pairs are removed from the multimap even if the iterator is
+        /// dropped before it is exhausted.
+        pub struct Drain<'a, $($generic_params)*> {
+            outer: $outer_iter,
+            inner: Option<(K, $inner_iter)>,
+            len: usize,
+            _marker: std::marker::PhantomData<&'a mut K>,
+        }
+
+        impl<$($generic_params)*> Iterator for Drain<'_, $($generic_ids)*>
+        where
+            K: Clone,
         {
+            type Item = (K, V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((key, values)) = &mut self.inner {
+                        if let Some(value) = values.next() {
+                            self.len -= 1;
+                            return Some((key.clone(), value));
+                        }
+                    }
+                    let (key, values) = self.outer.next()?;
+                    self.inner = Some((key, values.into_iter()));
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<$($generic_params)*> ExactSizeIterator for Drain<'_, $($generic_ids)*> where K: Clone {}
+
+        impl<$($generic_params)*> std::iter::FusedIterator for Drain<'_, $($generic_ids)*> where K: Clone {}
+
+        impl<$($generic_params)*> std::fmt::Debug for Drain<'_, $($generic_ids)*> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.debug_list().entries(self.clone()).finish()
+                f.debug_struct("Drain").field("len", &self.len).finish_non_exhaustive()
             }
         }
 
-        impl<K, V, S> $type<K, V, S> {
-            /// Return an iterator over the key-value pairs of the multimap.
-            pub fn iter(&self) -> Iter<'_, $($generic_ids)*> {
-                let mut iter = self.inner.iter();
-                let inner = iter.next().map(|(k, v)| (k, v.iter()));
-                Iter {
-                    outer: iter,
-                    inner,
-                    len: self.len,
+        $crate::multimap_extract_if_iter!();
+
+        impl<$($type_params)*> $type<$($type_generics),*>
+        where
+            $($bounds)*
+        {
+            $crate::impl_drain!(@drain $kind, ($($generic_ids)*));
+
+            /// Removes the key and all associated values from the multimap,
+            /// returning them as an iterator of key-value pairs.
+            ///
+            /// The iterator is empty if `key` is not present. The key is cloned
+            /// for every value.
+            pub fn drain_key<Q>(&mut self, key: &Q) -> IntoIter<$($generic_ids)*>
+            where
+                Q: ?Sized,
+                $($keys_ref)*
+            {
+                let inner = $crate::impl_drain!(@remove_key_entry $kind, self, key);
+                let len = inner.as_ref().map_or(0, |(_key, values)| values.len());
+                IntoIter {
+                    outer: $crate::impl_drain!(@empty $kind),
+                    inner: inner.map(|(key, values)| (key, values.into_iter())),
+                    back: None,
+                    len,
                 }
             }
 
-            /// Return an iterator over the values of the multimap.
-            pub fn values(&self) -> Values<'_, $($generic_ids)*> {
-                Values {
-                    inner: self.iter(),
+            /// Removes all pairs `(k, v)` such that `f(&k, &v)` returns `true`,
+            /// returning them as an iterator.
+            ///
+            /// Unlike `Vec::extract_if()`, this method is eager: `f` is called
+            /// exactly once for every pair and all matching pairs are removed
+            /// before this method returns, dropping the iterator early does not
+            /// put them back. Only the values of keys with at least one match
+            /// are rebuilt. The key is cloned for every extracted value.
+            pub fn extract_if<F>(&mut self, mut f: F) -> ExtractIf<K, V>
+            where
+                F: FnMut(&K, &V) -> bool,
+                K: Clone,
+            {
+                let mut extracted = Vec::new();
+                self.inner.retain(|key, values| {
+                    let Some(first) = values.iter().position(|value| f(key, value)) else {
+                        return true;
+                    };
+                    let taken: $values = std::mem::take(values);
+                    for (index, value) in taken.into_iter().enumerate() {
+                        if index == first || (index > first && f(key, &value)) {
+                            extracted.push((key.clone(), value));
+                        } else {
+                            values.extend(std::iter::once(value));
+                        }
+                    }
+                    !values.is_empty()
+                });
+                self.len -= extracted.len();
+                ExtractIf {
+                    inner: extracted.into_iter(),
                 }
             }
         }
-
-        impl<'a, K, V, S> IntoIterator for &'a $type<K, V, S> {
-            type IntoIter = Iter<'a, $($generic_ids)*>;
-            type Item = (&'a K, &'a V);
-            fn into_iter(self) -> Self::IntoIter {
-                self.iter()
+    };
+    (@drain hash, ($($generic_ids:tt)*)) => {
+        /// Clears the multimap, returning all key-value pairs as an iterator.
+        ///
+        /// The key is cloned for every value. The multimap is empty once this
+        /// method returns, even if the iterator is not consumed.
+        pub fn drain(&mut self) -> Drain<'_, $($generic_ids)*> {
+            Drain {
+                outer: self.inner.drain(),
+                inner: None,
+                len: std::mem::take(&mut self.len),
+                _marker: std::marker::PhantomData,
             }
         }
-    }
+    };
+    (@drain btree, ($($generic_ids:tt)*)) => {
+        /// Clears the multimap, returning all key-value pairs as an iterator.
+        ///
+        /// The key is cloned for every value. The multimap is empty once this
+        /// method returns, even if the iterator is not consumed.
+        pub fn drain(&mut self) -> Drain<'_, $($generic_ids)*> {
+            Drain {
+                outer: std::mem::take(&mut self.inner).into_iter(),
+                inner: None,
+                len: std::mem::take(&mut self.len),
+                _marker: std::marker::PhantomData,
+            }
+        }
+    };
+    (@drain index, ($($generic_ids:tt)*)) => {
+        /// Removes the keys in the given range of key positions with all their
+        /// values, returning them as an iterator of key-value pairs.
+        ///
+        /// Use `drain(..)` to clear the multimap. The key is cloned for every
+        /// value. The range is removed once this method returns, even if the
+        /// iterator is not consumed.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the starting point is greater than the end point or if the
+        /// end point is greater than the number of keys.
+        pub fn drain<R>(&mut self, range: R) -> Drain<'_, $($generic_ids)*>
+        where
+            R: std::ops::RangeBounds<usize>,
+        {
+            let range = (range.start_bound().cloned(), range.end_bound().cloned());
+            let len = self
+                .inner
+                .get_range(range)
+                .map_or(0, |slice| slice.values().map(|values| values.len()).sum());
+            let outer = self.inner.drain(range);
+            self.len -= len;
+            Drain {
+                outer,
+                inner: None,
+                len,
+                _marker: std::marker::PhantomData,
+            }
+        }
+    };
+    (@remove_key_entry index, $self:ident, $key:ident) => {
+        $self.shift_remove_key_entry($key)
+    };
+    (@remove_key_entry $kind:tt, $self:ident, $key:ident) => {
+        $self.remove_key_entry($key)
+    };
+    (@empty hash) => {
+        std::collections::HashMap::new().into_iter()
+    };
+    (@empty index) => {
+        indexmap::IndexMap::new().into_iter()
+    };
+    (@empty btree) => {
+        std::collections::BTreeMap::new().into_iter()
+    };
 }
 
 #[doc(hidden)]
 #[macro_export]
-macro_rules! impl_keys {
-    ($type:tt, ($($generic_ids:tt)*), $inner_iter:ty) => {
-        /// An iterator over the keys of a multimap.
+macro_rules! multimap_extract_if_iter {
+    () => {
+        /// An iterator over the key-value pairs that were extracted from a
+        /// multimap.
         ///
-        /// This `struct` is created by the `keys` method on multimap.
-        pub struct Keys<'a, $($generic_ids)*> {
-            inner: $inner_iter,
+        /// This `struct` is created by the `extract_if` method on multimap.
+        #[derive(Debug)]
+        pub struct ExtractIf<K, V> {
+            inner: std::vec::IntoIter<(K, V)>,
         }
 
-        impl<'a, $($generic_ids)*> Iterator for Keys<'a, $($generic_ids)*> {
-            type Item = &'a K;
+        impl<K, V> Iterator for ExtractIf<K, V> {
+            type Item = (K, V);
 
             fn next(&mut self) -> Option<Self::Item> {
                 self.inner.next()
             }
-        }
 
-        impl<$($generic_ids)*> ExactSizeIterator for Keys<'_, $($generic_ids)*> {
-            fn len(&self) -> usize {
-                self.inner.len()
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
             }
         }
 
-        impl<$($generic_ids)*> std::iter::FusedIterator for Keys<'_, $($generic_ids)*> {}
-
-        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
-        impl<$($generic_ids)*> Clone for Keys<'_, $($generic_ids)*> {
-            fn clone(&self) -> Self {
-                Keys {
-                    inner: self.inner.clone(),
-                }
+        impl<K, V> DoubleEndedIterator for ExtractIf<K, V> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back()
             }
         }
 
-        impl<$($generic_ids)*> std::fmt::Debug for Keys<'_, $($generic_ids)*>
+        impl<K, V> ExactSizeIterator for ExtractIf<K, V> {}
+
+        impl<K, V> std::iter::FusedIterator for ExtractIf<K, V> {}
+    };
+}
+
+//////////////////////////////////////
+// Serde support
+//////////////////////////////////////
+
+/// Implements `Serialize` and `Deserialize` for a multimap.
+///
+/// Multimaps are serialized as a map from key to a sequence of values.
+/// Multimaps that are keyed by an `IndexKey` use the index of the key as map
+/// key. Deserialization merges the values of duplicate keys, drops keys without
+/// values and collapses duplicate values for set multimaps.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_serde_impl {
+    (keyed, $type:ident, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::multimap_serde_impl!(keyed, [$($generic_ids)*] $type, ($($generic_ids)*), $($rest)*);
+    };
+    (keyed, [$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_params)*> serde::Serialize for $type<$($generic_ids)*>
         where
-            K: std::fmt::Debug,
+            K: serde::Serialize,
+            V: serde::Serialize,
         {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.debug_list().entries(self.clone()).finish()
+            fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+            where
+                Ser: serde::Serializer,
+            {
+                serializer.collect_map(self.inner.iter())
             }
         }
 
-        impl<K, V, S> $type<K, V, S> {
-            /// Return an iterator over the keys of the multimap.
-            pub fn keys(&self) -> Keys<'_, $($generic_ids)*> {
-                Keys {
-                    inner: self.inner.keys(),
-                }
+        $crate::multimap_serde_impl!(@deserialize [$($generic_params)*] $type, ($($generic_ids)*), ($($type_bounds)*, K: serde::Deserialize<'de>), K, |key| Ok(key));
+    };
+    (index_key, $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        $crate::multimap_serde_impl!(@serialize_index_key $type, ($($generic_ids)*), ($($type_bounds)*));
+        $crate::multimap_serde_impl!(@deserialize [$($generic_ids)*] $type, ($($generic_ids)*), ($($type_bounds)*), usize, |index| Ok(K::from_index(index)));
+    };
+    (enum_key, $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        $crate::multimap_serde_impl!(@serialize_index_key $type, ($($generic_ids)*), ($($type_bounds)*));
+        $crate::multimap_serde_impl!(@deserialize [$($generic_ids)*] $type, ($($generic_ids)*), ($($type_bounds)*), usize, |index: usize| {
+            if index < K::VARIANT_COUNT {
+                Ok(K::from_index(index))
+            } else {
+                Err(serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Unsigned(index as u64),
+                    &"the index of a key",
+                ))
             }
-        }
-    }
-}
+        });
+    };
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! impl_into_iterator {
-    ($type:tt, ($($generic_ids:tt)*), $outer_iter:ty, $inner_iter:ty) => {
-        /// An owning iterator over the entries of a multimap.
-        #[derive(Debug)]
-        pub struct IntoIter<$($generic_ids)*> {
-            outer: $outer_iter,
-            inner: Option<(K, $inner_iter)>,
-            len: usize,
+    (@serialize_index_key $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> serde::Serialize for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            V: serde::Serialize,
+        {
+            fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+            where
+                Ser: serde::Serializer,
+            {
+                serializer.collect_map(
+                    self.keys()
+                        .filter_map(|key| self.get(key).map(|values| (key.as_index(), values))),
+                )
+            }
         }
+    };
 
-        impl<$($generic_ids)*> Iterator for IntoIter<$($generic_ids)*>
+    (@deserialize [$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*), $serialized_key:ty, $to_key:expr) => {
+        impl<'de, $($generic_params)*> serde::Deserialize<'de> for $type<$($generic_ids)*>
         where
-            K: Clone,
+            $($type_bounds)*,
+            V: serde::Deserialize<'de>,
         {
-            type Item = (K, V);
+            fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+            where
+                De: serde::Deserializer<'de>,
+            {
+                struct MultimapVisitor<T>(std::marker::PhantomData<T>);
 
-            fn next(&mut self) -> Option<Self::Item> {
-                if let Some((current_key, inner_iter)) = &mut self.inner {
-                    let next = inner_iter.next();
+                impl<'de, $($generic_params)*> serde::de::Visitor<'de> for MultimapVisitor<$type<$($generic_ids)*>>
+                where
+                    $($type_bounds)*,
+                    V: serde::Deserialize<'de>,
+                {
+                    type Value = $type<$($generic_ids)*>;
 
-                    if let Some(next_value) = next {
-                        Some((current_key.clone(), next_value))
-                    } else {
-                        if let Some((key, values)) = self.outer.next() {
-                            let mut new_inner_iter = values.into_iter();
-                            let v = new_inner_iter.next().unwrap();
-                            self.inner = Some((key.clone(), new_inner_iter));
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        formatter.write_str("a map of keys to sequences of values")
+                    }
 
-                            Some((key, v))
-                        } else {
-                            None
+                    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+                    where
+                        A: serde::de::MapAccess<'de>,
+                    {
+                        let to_key = $to_key;
+                        let mut map = $type::default();
+                        while let Some((key, values)) = access.next_entry::<$serialized_key, Vec<V>>()? {
+                            let _ = map.entry(to_key(key)?).extend(values);
                         }
+                        Ok(map)
                     }
-                } else {
-                    None
                 }
+
+                deserializer.deserialize_map(MultimapVisitor(std::marker::PhantomData))
             }
         }
+    };
+}
+
+//////////////////////////////////////
+// Inversion
+//////////////////////////////////////
 
-        impl<$($generic_ids)*> ExactSizeIterator for IntoIter<$($generic_ids)*>
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multimap_invert_impl {
+    ($type:ident, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::multimap_invert_impl!([$($generic_ids)*] $type, ($($generic_ids)*), $($rest)*);
+    };
+    ([$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), ($($inverted_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_params)*> $type<$($generic_ids)*>
         where
+            $($type_bounds)*,
             K: Clone,
         {
-            fn len(&self) -> usize {
-                self.len
+            /// Returns the inverse of the multimap, every `(key, value)` pair
+            /// becomes a `(value, key)` pair.
+            ///
+            /// Keys are inserted in the iteration order of the values of the
+            /// multimap, i.e. multimaps that preserve insertion order order
+            /// the keys of the inverse by the first occurrence of the value.
+            #[must_use]
+            pub fn invert(self) -> $type<$($inverted_ids)*> {
+                self.into_iter().map(|(key, value)| (value, key)).collect()
+            }
+
+            /// Returns the inverse of the multimap by cloning all keys and
+            /// values, every `(key, value)` pair becomes a `(value, key)` pair.
+            ///
+            /// See [`Self::invert()`] for the order of the inverse.
+            #[must_use]
+            pub fn inverted(&self) -> $type<$($inverted_ids)*>
+            where
+                V: Clone,
+            {
+                self.iter()
+                    .map(|(key, value)| (value.clone(), key.clone()))
+                    .collect()
             }
         }
+    };
+}
 
-        impl<$($generic_ids)*> std::iter::FusedIterator for IntoIter<$($generic_ids)*>
-        where
-           K: Clone,
-        {}
+//////////////////////////////////////
+// Multimap trait
+//////////////////////////////////////
 
-        impl<K, V, S> IntoIterator for $type<K, V, S>
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_multimap_trait {
+    (keyed, $insert:ident, $type:ident, ($($generic_ids:tt)*), $($rest:tt)*) => {
+        $crate::impl_multimap_trait!(keyed, $insert, [$($generic_ids)*] $type, ($($generic_ids)*), $($rest)*);
+    };
+    (keyed, $insert:ident, [$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), $values:ty, ($($type_bounds:tt)*), $remove:ident, $remove_key:ident) => {
+        impl<$($generic_params)*> $crate::Multimap for $type<$($generic_ids)*>
         where
-            K: Clone,
+            $($type_bounds)*,
         {
-            type Item = (K, V);
+            type Key = K;
+            type Value = V;
+            type Values = $values;
+            type KeyRef<'a> = &'a K where Self: 'a;
 
-            type IntoIter = IntoIter<$($generic_ids)*>;
+            fn len(&self) -> usize {
+                self.len()
+            }
 
-            fn into_iter(self) -> Self::IntoIter {
-                let mut iter = self.inner.into_iter();
-                let inner = iter.next().map(|(k, v)| (k, v.into_iter()));
-                IntoIter {
-                    outer: iter,
-                    inner,
-                    len: self.len,
-                }
+            fn keys_len(&self) -> usize {
+                self.keys_len()
             }
-        }
 
-        /// An owning iterator over the values of a multimap.
-        ///
-        /// This `struct` is created by the `into_values` method on multimap.
-        #[derive(Debug)]
-        pub struct IntoValues<$($generic_ids)*> {
-            outer: $outer_iter,
-            inner: Option<$inner_iter>,
-            len: usize,
-        }
+            fn get(&self, key: &K) -> Option<&$values> {
+                self.get(key)
+            }
 
-        impl<$($generic_ids)*> Iterator for IntoValues<$($generic_ids)*>        {
-            type Item = V;
+            fn contains(&self, key: &K, value: &V) -> bool {
+                self.contains(key, value)
+            }
 
-            fn next(&mut self) -> Option<Self::Item> {
-                if let Some(inner_iter) = &mut self.inner {
-                    let next = inner_iter.next();
+            fn remove(&mut self, key: &K, value: &V) -> Option<V> {
+                self.$remove(key, value)
+            }
 
-                    if let Some(next_value) = next {
-                        Some(next_value)
-                    } else {
-                        if let Some((_key, values)) = self.outer.next() {
-                            let mut new_inner_iter = values.into_iter();
-                            let v = new_inner_iter.next().unwrap();
-                            self.inner = Some(new_inner_iter);
-                            Some(v)
-                        } else {
-                            None
-                        }
-                    }
-                } else {
-                    None
-                }
+            fn remove_key(&mut self, key: &K) -> Option<$values> {
+                self.$remove_key(key)
             }
-        }
 
-        impl<$($generic_ids)*> ExactSizeIterator for IntoValues<$($generic_ids)*> {
-            fn len(&self) -> usize {
-                self.len
+            fn retain<F>(&mut self, f: F)
+            where
+                F: Fn(&K, &V) -> bool,
+            {
+                self.retain(f);
+            }
+
+            fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+                self.iter()
             }
-        }
 
-        impl<$($generic_ids)*> std::iter::FusedIterator for IntoValues<$($generic_ids)*> {}
+            fn keys(&self) -> impl Iterator<Item = &K> {
+                self.keys()
+            }
 
-        impl<K, V, S> $type<K, V, S> {
-            /// Return an iterator over the values of the multimap.
-            pub fn into_values(self) -> IntoValues<$($generic_ids)*> {
-                let mut iter = self.inner.into_iter();
-                let inner = iter.next().map(|(_k, v)| v.into_iter());
-                IntoValues {
-                    outer: iter,
-                    inner,
-                    len: self.len,
-                }
+            fn values(&self) -> impl Iterator<Item = &V> {
+                self.values()
             }
         }
+
+        $crate::impl_multimap_trait!(@insert $insert, [$($generic_params)*] $type, ($($generic_ids)*), ($($type_bounds)*));
     };
-}
+    (index_key, $insert:ident, $type:ident, ($($generic_ids:tt)*), $values:ty, ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> $crate::Multimap for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            type Key = K;
+            type Value = V;
+            type Values = $values;
+            type KeyRef<'a> = K where Self: 'a;
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! impl_into_keys {
-    ($type:tt, ($($generic_ids:tt)*), $inner_iter:ty) => {
-        /// An owning iterator over the keys of a multimap.
-        ///
-        /// This `struct` is created by the `into_keys` method on multimap.
-        #[derive(Debug)]
-        pub struct IntoKeys<$($generic_ids)*> {
-            inner: $inner_iter,
-        }
+            fn len(&self) -> usize {
+                self.len()
+            }
 
-        impl<$($generic_ids)*> Iterator for IntoKeys<$($generic_ids)*> {
-            type Item = K;
+            fn keys_len(&self) -> usize {
+                self.keys_len()
+            }
 
-            fn next(&mut self) -> Option<Self::Item> {
-                self.inner.next()
+            fn get(&self, key: &K) -> Option<&$values> {
+                self.get(*key)
             }
-        }
 
-        impl<$($generic_ids)*> ExactSizeIterator for IntoKeys<$($generic_ids)*> {
-            fn len(&self) -> usize {
-                self.inner.len()
+            fn contains(&self, key: &K, value: &V) -> bool {
+                self.contains(*key, value)
             }
-        }
 
-        impl<$($generic_ids)*> std::iter::FusedIterator for IntoKeys<$($generic_ids)*> {}
+            fn remove(&mut self, key: &K, value: &V) -> Option<V> {
+                self.remove(*key, value)
+            }
 
-        impl<K, V, S> $type<K, V, S> {
-            /// Return an owning iterator over the keys of the multimap.
-            pub fn into_keys(self) -> IntoKeys<$($generic_ids)*> {
-                IntoKeys {
-                    inner: self.inner.into_keys(),
-                }
+            fn remove_key(&mut self, key: &K) -> Option<$values> {
+                self.remove_key(*key)
+            }
+
+            fn retain<F>(&mut self, f: F)
+            where
+                F: Fn(&K, &V) -> bool,
+            {
+                self.retain(f);
+            }
+
+            fn iter(&self) -> impl Iterator<Item = (K, &V)> {
+                self.iter()
+            }
+
+            fn keys(&self) -> impl Iterator<Item = K> {
+                self.keys()
+            }
+
+            fn values(&self) -> impl Iterator<Item = &V> {
+                self.values()
             }
         }
-    }
+
+        $crate::impl_multimap_trait!(@insert $insert, [$($generic_ids)*] $type, ($($generic_ids)*), ($($type_bounds)*));
+    };
+    (@insert set, [$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_params)*> $crate::SetMultimap for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn insert(&mut self, key: K, value: V) -> bool {
+                self.insert(key, value)
+            }
+        }
+    };
+    (@insert list, [$($generic_params:tt)*] $type:ident, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_params)*> $crate::ListMultimap for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn insert(&mut self, key: K, value: V) {
+                self.insert(key, value);
+            }
+        }
+    };
 }
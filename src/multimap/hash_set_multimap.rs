@@ -26,6 +26,7 @@ where
     V: Hash + Eq,
     S: BuildHasher + Default,
 {
+    multimap_capacity_impl! {}
     multimap_mutators_impl! {
         HashMap<K, HashSet<V,S>, S>,
         HashSet<V,S>,
@@ -54,20 +55,93 @@ multimap_extend! {
 }
 multimap_eq! { HashSetMultimap, (Hash + Eq)}
 
-impl_iter! {
+set_multimap_algebra_impl! {
     HashSetMultimap,
     (K, V, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_entry! {
+    HashSetMultimap<K, V, S>,
+    (K, V, S),
+    (std::collections::hash_map),
+    HashSet<V, S>,
+    HashSet::with_hasher(S::default()),
+    set,
+    unordered,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_get_mut! {
+    HashSetMultimap<K, V, S>,
+    (K, V, S),
+    (std::collections::hash_map),
+    unordered,
+    HashSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (K: Hash + Eq)
+}
+
+impl_iter! {
+    HashSetMultimap<K, V, S>,
+    (K, V, S),
     std::collections::hash_map::Iter<'a, K, HashSet<V, S>>,
     std::collections::hash_set::Iter<'a, V>
 }
-impl_keys! {HashSetMultimap, (K, V, S), std::collections::hash_map::Keys<'a, K, HashSet<V, S>>}
+impl_keys! {HashSetMultimap<K, V, S>, (K, V, S), std::collections::hash_map::Keys<'a, K, HashSet<V, S>>}
 impl_into_iterator! {
-    HashSetMultimap,
+    HashSetMultimap<K, V, S>,
     (K, V, S),
     std::collections::hash_map::IntoIter<K, HashSet<V, S>>,
     std::collections::hash_set::IntoIter<V>
 }
-impl_into_keys! {HashSetMultimap, (K, V, S), std::collections::hash_map::IntoKeys<K, HashSet<V, S>>}
+impl_into_keys! {HashSetMultimap<K, V, S>, (K, V, S), std::collections::hash_map::IntoKeys<K, HashSet<V, S>>}
+
+impl_drain! {
+    hash,
+    HashSetMultimap<K, V, S>,
+    (K, V, S),
+    std::collections::hash_map::Drain<'a, K, HashSet<V, S>>,
+    std::collections::hash_set::IntoIter<V>,
+    HashSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (K: Borrow<Q>, Q: Hash + Eq)
+}
+
+impl_values_mut! {
+    set,
+    HashSetMultimap<K, V, S>,
+    (K, V, S),
+    HashSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+multimap_invert_impl! {
+    HashSetMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    keyed,
+    set,
+    HashSetMultimap,
+    (K, V, S),
+    HashSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    remove,
+    remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    HashSetMultimap,
+    (K, V, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
 
 #[macro_export]
 macro_rules! hashsetmultimap {
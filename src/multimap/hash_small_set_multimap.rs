@@ -0,0 +1,180 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+use crate::SmallSet;
+
+/// Multimap implementation that behaves like `HashMap<K, SmallSet<V, C>>`.
+///
+/// The values of a key are stored inline as long as there are at most `C` of
+/// them, which avoids an allocation per key when most keys have few values.
+/// Values are kept in insertion order, unless values are removed.
+#[derive(Debug, Clone)]
+pub struct HashSmallSetMultimap<K, V, const C: usize, S = RandomState> {
+    inner: HashMap<K, SmallSet<V, C, S>, S>,
+    len: usize,
+}
+
+impl<K, V, const C: usize> HashSmallSetMultimap<K, V, C, RandomState> {
+    multimap_base_impl! {HashMap<K, SmallSet<V, C>>}
+}
+
+impl<K, V, const C: usize, S> HashSmallSetMultimap<K, V, C, S> {
+    multimap_base2_impl! {HashMap}
+}
+
+impl<K, V, const C: usize, S> HashSmallSetMultimap<K, V, C, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    multimap_capacity_impl! {}
+    multimap_mutators_impl! {
+        HashMap<K, SmallSet<V, C, S>, S>,
+        SmallSet<V, C, S>,
+        SmallSet::default(),
+        small_set,
+        (K: Borrow<Q>, Q: Hash + Eq),
+        (V: Borrow<R>, R: Hash + Eq)
+    }
+    multimap_remove_impl! {
+        unordered,
+        SmallSet<V, C, S>,
+        small_set,
+        (K: Borrow<Q>, Q: Hash + Eq),
+        (V: Borrow<R>, R: Hash + Eq)
+    }
+}
+
+multimap_extend! {
+    [K, V, const C: usize, S]
+    HashSmallSetMultimap,
+    (K, V, C, S),
+    HashMap,
+    SmallSet<V, C, S>,
+    (K: Hash + Eq),
+    (V: Hash + Eq),
+    (K: Hash + Eq + Borrow<Q>, Q: Hash + Eq)
+}
+multimap_eq! { small, HashSmallSetMultimap, (Hash + Eq)}
+
+set_multimap_algebra_impl! {
+    [K, V, const C: usize, S]
+    HashSmallSetMultimap,
+    (K, V, C, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_entry! {
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    (std::collections::hash_map),
+    SmallSet<V, C, S>,
+    SmallSet::default(),
+    small_set,
+    unordered,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_get_mut! {
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    (std::collections::hash_map),
+    unordered,
+    SmallSet<V, C, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (K: Hash + Eq)
+}
+
+impl_iter! {
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    std::collections::hash_map::Iter<'a, K, SmallSet<V, C, S>>,
+    crate::small_set::Iter<'a, V>
+}
+impl_keys! {
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    std::collections::hash_map::Keys<'a, K, SmallSet<V, C, S>>
+}
+impl_into_iterator! {
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    std::collections::hash_map::IntoIter<K, SmallSet<V, C, S>>,
+    crate::small_set::IntoIter<V, C>
+}
+impl_into_keys! {
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    std::collections::hash_map::IntoKeys<K, SmallSet<V, C, S>>
+}
+
+impl_drain! {
+    hash,
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    std::collections::hash_map::Drain<'a, K, SmallSet<V, C, S>>,
+    crate::small_set::IntoIter<V, C>,
+    SmallSet<V, C, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (K: Borrow<Q>, Q: Hash + Eq)
+}
+
+impl_values_mut! {
+    set,
+    [K, V, const C: usize, S] HashSmallSetMultimap<K, V, C, S>,
+    [K, V, const C: usize, S] (K, V, C, S),
+    SmallSet<V, C, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+multimap_invert_impl! {
+    [K, V, const C: usize, S]
+    HashSmallSetMultimap,
+    (K, V, C, S),
+    (V, K, C, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    keyed,
+    set,
+    [K, V, const C: usize, S]
+    HashSmallSetMultimap,
+    (K, V, C, S),
+    SmallSet<V, C, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    remove,
+    remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    [K, V, const C: usize, S]
+    HashSmallSetMultimap,
+    (K, V, C, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[macro_export]
+macro_rules! hashsmallsetmultimap {
+    (@single $($x:tt)*) => (());
+    (@count $($rest:expr),*) => (<[()]>::len(&[$(hashsmallsetmultimap!(@single $rest)),*]));
+
+    ($($key:expr => {$($value:expr),* },)+) => { hashsmallsetmultimap!($($key => $($value,)* ),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let _cap = hashsmallsetmultimap!(@count $($key),*);
+            let mut _map = std::collections::HashMap::with_capacity(_cap);
+            $(
+                let _ = _map.insert($key, $crate::smallset!{$( $value ),*});
+            )*
+            HashSmallSetMultimap::from(_map)
+        }
+    };
+}
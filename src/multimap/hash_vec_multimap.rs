@@ -25,6 +25,7 @@ where
     V: Eq,
     S: BuildHasher + Default,
 {
+    multimap_capacity_impl! {}
     multimap_mutators_impl! {
         HashMap<K, Vec<V>, S>,
         Vec<V>,
@@ -51,22 +52,88 @@ multimap_extend! {
     (V: Eq),
     (K: Hash + Eq + Borrow<Q>, Q: Hash + Eq)
 }
-multimap_eq! { HashVecMultimap, (Eq)}
+multimap_eq! { list, HashVecMultimap, (Eq)}
+
+impl_entry! {
+    HashVecMultimap<K, V, S>,
+    (K, V),
+    (std::collections::hash_map),
+    Vec<V>,
+    Vec::new(),
+    vec,
+    unordered,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (V: Eq)
+}
+
+impl_get_mut! {
+    HashVecMultimap<K, V, S>,
+    (K, V),
+    (std::collections::hash_map),
+    unordered,
+    Vec<V>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (K: Hash + Eq)
+}
 
 impl_iter! {
-    HashVecMultimap,
+    HashVecMultimap<K, V, S>,
     (K,V),
     std::collections::hash_map::Iter<'a, K, Vec<V>>,
     std::slice::Iter<'a, V>
 }
-impl_keys! {HashVecMultimap, (K, V), std::collections::hash_map::Keys<'a, K, Vec<V>>}
+impl_keys! {HashVecMultimap<K, V, S>, (K, V), std::collections::hash_map::Keys<'a, K, Vec<V>>}
 impl_into_iterator! {
-    HashVecMultimap,
+    HashVecMultimap<K, V, S>,
     (K,V),
     std::collections::hash_map::IntoIter<K, Vec<V>>,
     std::vec::IntoIter<V>
 }
-impl_into_keys! {HashVecMultimap, (K, V), std::collections::hash_map::IntoKeys<K, Vec<V>>}
+impl_into_keys! {HashVecMultimap<K, V, S>, (K, V), std::collections::hash_map::IntoKeys<K, Vec<V>>}
+
+impl_drain! {
+    hash,
+    HashVecMultimap<K, V, S>,
+    (K, V),
+    std::collections::hash_map::Drain<'a, K, Vec<V>>,
+    std::vec::IntoIter<V>,
+    Vec<V>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (K: Borrow<Q>, Q: Hash + Eq)
+}
+
+impl_values_mut! {
+    list,
+    HashVecMultimap<K, V, S>,
+    (K, V),
+    std::collections::hash_map::IterMut<'a, K, Vec<V>>
+}
+
+multimap_invert_impl! {
+    HashVecMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    keyed,
+    list,
+    HashVecMultimap,
+    (K, V, S),
+    Vec<V>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    remove,
+    remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    HashVecMultimap,
+    (K, V, S),
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default)
+}
 
 #[macro_export]
 macro_rules! hashvecmultimap {
@@ -28,6 +28,7 @@ where
     V: Hash + Eq,
     S: BuildHasher + Default,
 {
+    multimap_capacity_impl! {}
     multimap_mutators_impl! {
         IndexMap<K, IndexSet<V,S>, S>,
         IndexSet<V,S>,
@@ -51,6 +52,11 @@ where
         (Q: Hash + Equivalent<K>),
         (R: Hash + Equivalent<V>)
     }
+    index_multimap_sort_impl! {
+        (K, V, S),
+        IndexSet<V,S>,
+        (Q: Hash + Equivalent<K>)
+    }
 }
 
 multimap_extend! {
@@ -64,20 +70,94 @@ multimap_extend! {
 }
 multimap_eq! { IndexSetMultimap, (Hash + Eq)}
 
-impl_iter! {
+set_multimap_algebra_impl! {
     IndexSetMultimap,
+    (K, V, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_entry! {
+    IndexSetMultimap<K, V, S>,
+    (K, V, S),
+    (indexmap::map),
+    IndexSet<V, S>,
+    IndexSet::with_hasher(S::default()),
+    set,
+    ordered,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_get_mut! {
+    IndexSetMultimap<K, V, S>,
+    (K, V, S),
+    (indexmap::map),
+    ordered,
+    IndexSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (K: Hash + Eq)
+}
+
+impl_iter! {
+    IndexSetMultimap<K, V, S>,
     (K,V,S),
     indexmap::map::Iter<'a, K, IndexSet<V, S>>,
     indexmap::set::Iter<'a, V>
 }
-impl_keys! {IndexSetMultimap, (K, V, S), indexmap::map::Keys<'a, K, IndexSet<V, S>>}
+impl_keys! {IndexSetMultimap<K, V, S>, (K, V, S), indexmap::map::Keys<'a, K, IndexSet<V, S>>}
 impl_into_iterator! {
-    IndexSetMultimap,
+    IndexSetMultimap<K, V, S>,
     (K,V,S),
     indexmap::map::IntoIter<K, IndexSet<V, S>>,
     indexmap::set::IntoIter<V>
 }
-impl_into_keys! {IndexSetMultimap, (K, V, S), indexmap::map::IntoKeys<K, IndexSet<V, S>>}
+impl_into_keys! {IndexSetMultimap<K, V, S>, (K, V, S), indexmap::map::IntoKeys<K, IndexSet<V, S>>}
+impl_double_ended! {IndexSetMultimap<K, V, S>, (K, V, S)}
+
+impl_drain! {
+    index,
+    IndexSetMultimap<K, V, S>,
+    (K, V, S),
+    indexmap::map::Drain<'a, K, IndexSet<V, S>>,
+    indexmap::set::IntoIter<V>,
+    IndexSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    (Q: Hash + Equivalent<K>)
+}
+
+impl_values_mut! {
+    set,
+    IndexSetMultimap<K, V, S>,
+    (K, V, S),
+    IndexSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+multimap_invert_impl! {
+    IndexSetMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    keyed,
+    set,
+    IndexSetMultimap,
+    (K, V, S),
+    IndexSet<V, S>,
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default),
+    shift_remove,
+    shift_remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    IndexSetMultimap,
+    (K, V, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
 
 #[macro_export]
 macro_rules! indexsetmultimap {
@@ -0,0 +1,191 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+use indexmap::Equivalent;
+use indexmap::IndexMap;
+use smallvec::SmallVec;
+
+/// Multimap implementation that behaves like `IndexMap<K, SmallVec<[V; C]>>`.
+///
+/// The values of a key are stored inline as long as there are at most `C` of
+/// them, which avoids an allocation per key when most keys have few values.
+#[derive(Debug, Clone)]
+pub struct IndexSmallVecMultimap<K, V, const C: usize, S = RandomState> {
+    inner: IndexMap<K, SmallVec<[V; C]>, S>,
+    len: usize,
+}
+
+impl<K, V, const C: usize> IndexSmallVecMultimap<K, V, C> {
+    multimap_base_impl! { IndexMap<K, SmallVec<[V; C]>>}
+}
+
+impl<K, V, const C: usize, S> IndexSmallVecMultimap<K, V, C, S> {
+    multimap_base2_impl! {IndexMap}
+}
+
+impl<K, V, const C: usize, S> IndexSmallVecMultimap<K, V, C, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher + Default,
+{
+    multimap_capacity_impl! {}
+    multimap_mutators_impl! {
+        IndexMap<K, SmallVec<[V; C]>, S>,
+        SmallVec<[V; C]>,
+        SmallVec::new(),
+        vec_equivalent,
+        (Q: Hash + Equivalent<K>),
+        (R: Equivalent<V>)
+    }
+    multimap_remove_impl! {
+        ordered,
+        SmallVec<[V; C]>,
+        vec_equivalent,
+        (Q: Hash + Equivalent<K>),
+        (R: Equivalent<V>)
+    }
+
+    index_multimap_impl! {
+        IndexMap<K, SmallVec<[V; C]>, S>,
+        SmallVec<[V; C]>,
+        SmallVec::new(),
+        vec,
+        (Q: Hash + Equivalent<K>),
+        (R: Equivalent<V>)
+    }
+    index_multimap_sort_impl! {
+        (K, V, C),
+        SmallVec<[V; C]>,
+        (Q: Hash + Equivalent<K>)
+    }
+}
+
+multimap_extend! {
+    [K, V, const C: usize, S]
+    IndexSmallVecMultimap,
+    (K, V, C, S),
+    IndexMap,
+    SmallVec<[V; C]>,
+    (K: Hash + Eq),
+    (V: Eq),
+    (K: Hash + Eq, Q: Hash + Equivalent<K>)
+}
+multimap_eq! { small, list, IndexSmallVecMultimap, (Eq)}
+
+impl_entry! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    (indexmap::map),
+    SmallVec<[V; C]>,
+    SmallVec::new(),
+    vec,
+    ordered,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (V: Eq)
+}
+
+impl_get_mut! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    (indexmap::map),
+    ordered,
+    SmallVec<[V; C]>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (K: Hash + Eq)
+}
+
+impl_iter! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    indexmap::map::Iter<'a, K, SmallVec<[V; C]>>,
+    std::slice::Iter<'a, V>
+}
+impl_keys! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    indexmap::map::Keys<'a, K, SmallVec<[V; C]>>
+}
+impl_into_iterator! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    indexmap::map::IntoIter<K, SmallVec<[V; C]>>,
+    smallvec::IntoIter<[V; C]>
+}
+
+impl_into_keys! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    indexmap::map::IntoKeys<K, SmallVec<[V; C]>>
+}
+impl_double_ended! {
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C)
+}
+
+impl_drain! {
+    index,
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    indexmap::map::Drain<'a, K, SmallVec<[V; C]>>,
+    smallvec::IntoIter<[V; C]>,
+    SmallVec<[V; C]>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (Q: Hash + Equivalent<K>)
+}
+
+impl_values_mut! {
+    list,
+    [K, V, const C: usize, S] IndexSmallVecMultimap<K, V, C, S>,
+    [K, V, const C: usize] (K, V, C),
+    indexmap::map::IterMut<'a, K, SmallVec<[V; C]>>
+}
+
+multimap_invert_impl! {
+    [K, V, const C: usize, S]
+    IndexSmallVecMultimap,
+    (K, V, C, S),
+    (V, K, C, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    keyed,
+    list,
+    [K, V, const C: usize, S]
+    IndexSmallVecMultimap,
+    (K, V, C, S),
+    SmallVec<[V; C]>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    shift_remove,
+    shift_remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    [K, V, const C: usize, S]
+    IndexSmallVecMultimap,
+    (K, V, C, S),
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default)
+}
+
+#[macro_export]
+macro_rules! indexsmallvecmultimap {
+    (@single $($x:tt)*) => (());
+    (@count $($rest:expr),*) => (<[()]>::len(&[$(indexsmallvecmultimap!(@single $rest)),*]));
+
+    ($($key:expr => {$($value:expr),* },)+) => { indexsmallvecmultimap!($($key => $($value,)* ),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let _cap = indexsmallvecmultimap!(@count $($key),*);
+            let mut _map = indexmap::IndexMap::with_capacity(_cap);
+            $(
+                let _ = _map.insert($key, smallvec::smallvec!{$( $value, )*});
+            )*
+            IndexSmallVecMultimap::from(_map)
+        }
+    };
+}
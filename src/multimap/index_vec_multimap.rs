@@ -27,6 +27,7 @@ where
     V: Eq,
     S: BuildHasher + Default,
 {
+    multimap_capacity_impl! {}
     multimap_mutators_impl! {
         IndexMap<K, Vec<V>, S>,
         Vec<V>,
@@ -51,6 +52,11 @@ where
         (Q: Hash + Equivalent<K>),
         (R: Equivalent<V>)
     }
+    index_multimap_sort_impl! {
+        (K, V),
+        Vec<V>,
+        (Q: Hash + Equivalent<K>)
+    }
 }
 
 multimap_extend! {
@@ -62,23 +68,90 @@ multimap_extend! {
     (V: Eq),
     (K: Hash + Eq, Q: Hash + Equivalent<K>)
 }
-multimap_eq! { IndexVecMultimap, (Eq)}
+multimap_eq! { list, IndexVecMultimap, (Eq)}
+
+impl_entry! {
+    IndexVecMultimap<K, V, S>,
+    (K, V),
+    (indexmap::map),
+    Vec<V>,
+    Vec::new(),
+    vec,
+    ordered,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (V: Eq)
+}
+
+impl_get_mut! {
+    IndexVecMultimap<K, V, S>,
+    (K, V),
+    (indexmap::map),
+    ordered,
+    Vec<V>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (K: Hash + Eq)
+}
 
 impl_iter! {
-    IndexVecMultimap,
+    IndexVecMultimap<K, V, S>,
     (K,V),
     indexmap::map::Iter<'a, K, Vec<V>>,
     std::slice::Iter<'a, V>
 }
-impl_keys! {IndexVecMultimap, (K,V), indexmap::map::Keys<'a, K, Vec<V>>}
+impl_keys! {IndexVecMultimap<K, V, S>, (K,V), indexmap::map::Keys<'a, K, Vec<V>>}
 impl_into_iterator! {
-    IndexVecMultimap,
+    IndexVecMultimap<K, V, S>,
     (K,V),
     indexmap::map::IntoIter<K, Vec<V>>,
     std::vec::IntoIter<V>
 }
 
-impl_into_keys! {IndexVecMultimap, (K,V), indexmap::map::IntoKeys<K, Vec<V>>}
+impl_into_keys! {IndexVecMultimap<K, V, S>, (K,V), indexmap::map::IntoKeys<K, Vec<V>>}
+impl_double_ended! {IndexVecMultimap<K, V, S>, (K, V)}
+
+impl_drain! {
+    index,
+    IndexVecMultimap<K, V, S>,
+    (K, V),
+    indexmap::map::Drain<'a, K, Vec<V>>,
+    std::vec::IntoIter<V>,
+    Vec<V>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    (Q: Hash + Equivalent<K>)
+}
+
+impl_values_mut! {
+    list,
+    IndexVecMultimap<K, V, S>,
+    (K, V),
+    indexmap::map::IterMut<'a, K, Vec<V>>
+}
+
+multimap_invert_impl! {
+    IndexVecMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: Hash + Eq, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    keyed,
+    list,
+    IndexVecMultimap,
+    (K, V, S),
+    Vec<V>,
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default),
+    shift_remove,
+    shift_remove_key
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    keyed,
+    IndexVecMultimap,
+    (K, V, S),
+    (K: Hash + Eq, V: Eq, S: BuildHasher + Default)
+}
 
 #[macro_export]
 macro_rules! indexvecmultimap {
@@ -1,11 +1,52 @@
 #[macro_use]
 mod gen_macros;
+mod conversions;
+mod traits;
+
+pub use traits::ListMultimap;
+pub use traits::Multimap;
+pub use traits::SetMultimap;
+
+#[cfg(all(feature = "indexmap", feature = "bimultimap"))]
+pub mod bi_multimap;
+#[cfg(all(feature = "indexmap", feature = "bimultimap"))]
+pub use bi_multimap::BiMultimap;
+
+#[cfg(feature = "btreesetmultimap")]
+pub mod btree_set_multimap;
+#[cfg(feature = "btreesetmultimap")]
+pub use btree_set_multimap::BTreeSetMultimap;
+
+#[cfg(feature = "btreevecmultimap")]
+pub mod btree_vec_multimap;
+#[cfg(feature = "btreevecmultimap")]
+pub use btree_vec_multimap::BTreeVecMultimap;
+
+#[cfg(feature = "enumhashsetmultimap")]
+pub mod enum_hash_set_multimap;
+#[cfg(feature = "enumhashsetmultimap")]
+pub use enum_hash_set_multimap::EnumHashSetMultimap;
+
+#[cfg(all(feature = "indexmap", feature = "enumindexsetmultimap"))]
+pub mod enum_index_set_multimap;
+#[cfg(all(feature = "indexmap", feature = "enumindexsetmultimap"))]
+pub use enum_index_set_multimap::EnumIndexSetMultimap;
+
+#[cfg(feature = "enumvecmultimap")]
+pub mod enum_vec_multimap;
+#[cfg(feature = "enumvecmultimap")]
+pub use enum_vec_multimap::EnumVecMultimap;
 
 #[cfg(all(feature = "hashsetmultimap", feature = "maplit"))]
 pub mod hash_set_multimap;
 #[cfg(feature = "hashsetmultimap")]
 pub use hash_set_multimap::HashSetMultimap;
 
+#[cfg(all(feature = "smallset", feature = "hashsmallsetmultimap"))]
+pub mod hash_small_set_multimap;
+#[cfg(all(feature = "smallset", feature = "hashsmallsetmultimap"))]
+pub use hash_small_set_multimap::HashSmallSetMultimap;
+
 #[cfg(feature = "hashvecmultimap")]
 pub mod hash_vec_multimap;
 #[cfg(feature = "hashvecmultimap")]
@@ -16,7 +57,61 @@ pub mod index_set_multimap;
 #[cfg(all(feature = "indexmap", feature = "indexsetmultimap"))]
 pub use index_set_multimap::IndexSetMultimap;
 
+#[cfg(all(
+    feature = "indexmap",
+    feature = "smallvec",
+    feature = "indexsmallvecmultimap"
+))]
+pub mod index_small_vec_multimap;
+#[cfg(all(
+    feature = "indexmap",
+    feature = "smallvec",
+    feature = "indexsmallvecmultimap"
+))]
+pub use index_small_vec_multimap::IndexSmallVecMultimap;
+
 #[cfg(all(feature = "indexmap", feature = "indexvecmultimap"))]
 pub mod index_vec_multimap;
 #[cfg(all(feature = "indexmap", feature = "indexvecmultimap"))]
 pub use index_vec_multimap::IndexVecMultimap;
+
+#[cfg(feature = "vecsetmultimap")]
+pub mod vec_set_multimap;
+#[cfg(feature = "vecsetmultimap")]
+pub use vec_set_multimap::VecSetMultimap;
+
+#[cfg(feature = "vecvecmultimap")]
+pub mod vec_vec_multimap;
+#[cfg(feature = "vecvecmultimap")]
+pub use vec_vec_multimap::VecVecMultimap;
+
+/// Returns `true` if both slices contain the same values with the same
+/// multiplicities, regardless of their order.
+///
+/// Values only need to implement `PartialEq`, so every value of `left` is
+/// searched for in `right`, which takes O(n²) comparisons.
+#[cfg(any(
+    feature = "btreevecmultimap",
+    feature = "enumvecmultimap",
+    feature = "hashvecmultimap",
+    feature = "indexsmallvecmultimap",
+    feature = "indexvecmultimap",
+    feature = "vecvecmultimap"
+))]
+fn slice_eq_unordered<A, B>(left: &[A], right: &[B]) -> bool
+where
+    A: PartialEq<B>,
+{
+    if left.len() != right.len() {
+        return false;
+    }
+    let mut matched = vec![false; right.len()];
+    left.iter().all(|value| {
+        right
+            .iter()
+            .zip(matched.iter_mut())
+            .find(|(other, matched)| !**matched && value == *other)
+            .map(|(_, matched)| *matched = true)
+            .is_some()
+    })
+}
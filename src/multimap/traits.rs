@@ -0,0 +1,105 @@
+/// Methods shared by all multimaps, allowing code to be generic over the
+/// multimap implementation.
+///
+/// Keys are always passed by reference, also for multimaps with
+/// [`IndexKey`](crate::IndexKey) keys which take keys by value in their
+/// inherent methods. Iterators yield keys as [`Self::KeyRef`].
+///
+/// Inserting is part of the [`SetMultimap`] and [`ListMultimap`] traits, as
+/// the return type depends on whether the multimap may contain duplicates.
+pub trait Multimap {
+    /// The type of the keys.
+    type Key;
+
+    /// The type of the values.
+    type Value;
+
+    /// The collection that stores the values of a single key.
+    type Values;
+
+    /// The key type yielded by iterators, `&'a Self::Key` for most multimaps
+    /// and `Self::Key` for multimaps with [`IndexKey`](crate::IndexKey) keys.
+    type KeyRef<'a>
+    where
+        Self: 'a;
+
+    /// Returns the number of elements in the multimap.
+    ///
+    /// Note that the number of elements in the multimap may not be the same as
+    /// the number of keys in the multimap. See [`Self::keys_len()`].
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the multimap contains no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of keys in the multimap.
+    fn keys_len(&self) -> usize;
+
+    /// Return a reference to the values stored for `key`, if it is present,
+    /// else `None`.
+    fn get(&self, key: &Self::Key) -> Option<&Self::Values>;
+
+    /// Returns `true` if the multimap contains a value for the specified key.
+    fn contains_key(&self, key: &Self::Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Return `true` if the `key` and `value` combination exists in the
+    /// multimap.
+    fn contains(&self, key: &Self::Key, value: &Self::Value) -> bool;
+
+    /// Remove the entry from the multimap, and return it if it was present.
+    ///
+    /// Multimaps that preserve insertion order shift the remaining elements to
+    /// preserve their relative order.
+    fn remove(&mut self, key: &Self::Key, value: &Self::Value) -> Option<Self::Value>;
+
+    /// Remove the key and all associated values from the multimap.
+    ///
+    /// Returns values if at least one value is associated to `key`, returns
+    /// `None` otherwise. Multimaps that preserve insertion order shift the
+    /// remaining keys to preserve their relative order.
+    fn remove_key(&mut self, key: &Self::Key) -> Option<Self::Values>;
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k, &v)`
+    /// returns `false`.
+    fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&Self::Key, &Self::Value) -> bool;
+
+    /// Return an iterator over all key-value pairs, in the iteration order of
+    /// the multimap.
+    fn iter(&self) -> impl Iterator<Item = (Self::KeyRef<'_>, &Self::Value)>;
+
+    /// Return an iterator over the keys, in the iteration order of the
+    /// multimap.
+    fn keys(&self) -> impl Iterator<Item = Self::KeyRef<'_>>;
+
+    /// Return an iterator over all values, in the iteration order of the
+    /// multimap.
+    fn values(&self) -> impl Iterator<Item = &Self::Value>;
+}
+
+/// A [`Multimap`] that stores the values of a key in a set, it never contains
+/// duplicate key-value pairs.
+pub trait SetMultimap: Multimap {
+    /// Insert the value into the multimap.
+    ///
+    /// If an equivalent entry already exists in the multimap, it returns
+    /// `false` leaving the original value in the set and without altering its
+    /// insertion order. Otherwise, it inserts the new entry and returns `true`.
+    fn insert(&mut self, key: Self::Key, value: Self::Value) -> bool;
+}
+
+/// A [`Multimap`] that stores the values of a key in a list, it may contain
+/// duplicate key-value pairs.
+pub trait ListMultimap: Multimap {
+    /// Insert the value into the multimap.
+    ///
+    /// Allows duplicates.
+    fn insert(&mut self, key: Self::Key, value: Self::Value);
+}
@@ -0,0 +1,78 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+use crate::IndexKey;
+use crate::VecMap;
+
+/// Multimap implementation that behaves like `VecMap<K, HashSet<V>>`.
+///
+/// Keys are [`IndexKey`]s, key lookups are therefore a direct index into the
+/// underlying [`VecMap`] without hashing. Keys are iterated following their
+/// natural order.
+#[derive(Clone)]
+pub struct VecSetMultimap<K, V, S = RandomState> {
+    inner: VecMap<K, HashSet<V, S>>,
+    len: usize,
+}
+
+impl<K, V> VecSetMultimap<K, V>
+where
+    K: IndexKey,
+{
+    vec_multimap_base_impl! {}
+}
+
+vec_multimap_impl! {
+    index_key,
+    VecSetMultimap,
+    (K, V, S),
+    HashSet<V, S>,
+    HashSet::with_hasher(S::default()),
+    set,
+    (unordered, set),
+    (K: IndexKey, V: Hash + Eq, S: BuildHasher + Default),
+    (V: Borrow<R>, R: Hash + Eq),
+    std::collections::hash_set::Iter<'a, V>,
+    std::collections::hash_set::IntoIter<V>
+}
+
+multimap_invert_impl! {
+    VecSetMultimap,
+    (K, V, S),
+    (V, K, S),
+    (K: IndexKey + Hash + Eq, V: IndexKey + Hash + Eq, S: BuildHasher + Default)
+}
+
+impl_multimap_trait! {
+    index_key,
+    set,
+    VecSetMultimap,
+    (K, V, S),
+    HashSet<V, S>,
+    (K: IndexKey, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    index_key,
+    VecSetMultimap,
+    (K, V, S),
+    (K: IndexKey, V: Hash + Eq, S: BuildHasher + Default)
+}
+
+#[macro_export]
+macro_rules! vecsetmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { vecsetmultimap!($($key => {$($value),*}),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = $crate::vec_map::VecMap::new();
+            $(
+                let _ = _map.insert($key, std::collections::HashSet::from([$( $value, )*]));
+            )*
+            VecSetMultimap::from(_map)
+        }
+    };
+}
@@ -0,0 +1,74 @@
+use std::borrow::Borrow;
+
+use crate::IndexKey;
+use crate::VecMap;
+
+/// Multimap implementation that behaves like `VecMap<K, Vec<V>>`.
+///
+/// Keys are [`IndexKey`]s, lookups are therefore a direct index into the
+/// underlying [`VecMap`] without hashing. Keys are iterated following their
+/// natural order.
+#[derive(Clone)]
+pub struct VecVecMultimap<K, V> {
+    inner: VecMap<K, Vec<V>>,
+    len: usize,
+}
+
+impl<K, V> VecVecMultimap<K, V>
+where
+    K: IndexKey,
+{
+    vec_multimap_base_impl! {}
+}
+
+vec_multimap_impl! {
+    index_key,
+    VecVecMultimap,
+    (K, V),
+    Vec<V>,
+    Vec::new(),
+    vec_equal,
+    (unordered, vec_equal),
+    (K: IndexKey, V: Eq),
+    (V: Borrow<R>, R: Eq),
+    std::slice::Iter<'a, V>,
+    std::vec::IntoIter<V>
+}
+
+multimap_invert_impl! {
+    VecVecMultimap,
+    (K, V),
+    (V, K),
+    (K: IndexKey + Eq, V: IndexKey + Eq)
+}
+
+impl_multimap_trait! {
+    index_key,
+    list,
+    VecVecMultimap,
+    (K, V),
+    Vec<V>,
+    (K: IndexKey, V: Eq)
+}
+
+#[cfg(feature = "serde")]
+multimap_serde_impl! {
+    index_key,
+    VecVecMultimap,
+    (K, V),
+    (K: IndexKey, V: Eq)
+}
+
+#[macro_export]
+macro_rules! vecvecmultimap {
+    ($($key:expr => {$($value:expr),* },)+) => { vecvecmultimap!($($key => {$($value),*}),+) };
+    ($($key:expr => {$($value:expr),* }),*) => {
+        {
+            let mut _map = $crate::vec_map::VecMap::new();
+            $(
+                let _ = _map.insert($key, vec![$( $value, )*]);
+            )*
+            VecVecMultimap::from(_map)
+        }
+    };
+}
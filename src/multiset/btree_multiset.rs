@@ -0,0 +1,177 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+
+/// Multiset implementation that behaves like `BTreeMap<T, usize>`, where the
+/// value is the number of occurrences of `T`.
+///
+/// Values are kept sorted, which enables rank queries such as
+/// [`Self::nth_smallest()`] and [`Self::count_less_than()`].
+#[derive(Debug, Clone)]
+pub struct BTreeMultiset<T> {
+    inner: BTreeMap<T, usize>,
+    len: usize,
+}
+
+impl<T> BTreeMultiset<T> {
+    multiset_base_impl! {btree, BTreeMap<T, usize>}
+
+    /// Returns the smallest value in the multiset, if any.
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.inner.keys().next()
+    }
+
+    /// Returns the largest value in the multiset, if any.
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.inner.keys().next_back()
+    }
+
+    /// Returns the value at position `index` in the sorted sequence of all
+    /// values, where each value is repeated as many times as it occurs in the
+    /// multiset. Returns `None` if `index >= self.len()`.
+    ///
+    /// This is equivalent to `self.iter().nth(index)`, but skips over all
+    /// occurrences of a value at once.
+    #[must_use]
+    pub fn nth_smallest(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        let mut remaining = index;
+        self.inner.iter().find_map(|(value, count)| {
+            if remaining < *count {
+                Some(value)
+            } else {
+                remaining -= count;
+                None
+            }
+        })
+    }
+}
+
+impl<T> BTreeMultiset<T>
+where
+    T: Ord,
+{
+    multiset_mutators_impl! {remove, (T: Borrow<Q>, Q: Ord)}
+
+    /// Return an iterator over the distinct values of the multiset whose
+    /// values are within `range` and their number of occurrences, in
+    /// ascending order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`. Panics if range `start == end` and both
+    /// bounds are `Excluded`.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        Q: ?Sized + Ord,
+        T: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.inner.range(range),
+        }
+    }
+
+    /// Returns the number of values within `range`, counting all occurrences
+    /// of each value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`. Panics if range `start == end` and both
+    /// bounds are `Excluded`.
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where
+        Q: ?Sized + Ord,
+        T: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        self.inner.range(range).map(|(_, count)| count).sum()
+    }
+
+    /// Returns the number of values strictly less than `value`, counting all
+    /// occurrences of each value.
+    ///
+    /// This is the rank of `value` in the sorted sequence of all values, e.g.
+    /// `count_less_than(&x) as f64 / len() as f64` is the percentile of `x`.
+    pub fn count_less_than<Q>(&self, value: &Q) -> usize
+    where
+        Q: ?Sized + Ord,
+        T: Borrow<Q>,
+    {
+        self.count_range::<Q, _>((Bound::Unbounded, Bound::Excluded(value)))
+    }
+}
+
+multiset_traits_impl! {BTreeMultiset, (T), (T: Ord)}
+multiset_algebra_impl! {BTreeMultiset, (T), (T: Ord)}
+
+impl_multiset_iter! {
+    BTreeMultiset<T>,
+    std::collections::btree_map::Iter<'a, T, usize>,
+    std::collections::btree_map::IntoIter<T, usize>
+}
+
+/// An iterator over a sub-range of the distinct values of a [`BTreeMultiset`]
+/// and their number of occurrences.
+///
+/// This `struct` is created by [`BTreeMultiset::range()`].
+pub struct Range<'a, T> {
+    inner: std::collections::btree_map::Range<'a, T, usize>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(value, count)| (value, *count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Range<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(value, count)| (value, *count))
+    }
+}
+
+impl<T> std::iter::FusedIterator for Range<'_, T> {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for Range<'_, T> {
+    fn clone(&self) -> Self {
+        Range {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Range<'_, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[macro_export]
+macro_rules! btreemultiset {
+    ($($value:expr,)+) => { btreemultiset!($($value),+) };
+    ($($value:expr),*) => {
+        {
+            let mut _set = BTreeMultiset::new();
+            $(
+                let _ = _set.insert($value);
+            )*
+            _set
+        }
+    };
+}
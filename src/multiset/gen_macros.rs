@@ -0,0 +1,616 @@
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_base_impl {
+    (btree, $map:ty) => {
+        /// Creates an empty multiset.
+        ///
+        /// Does not allocate anything on its own.
+        #[must_use]
+        pub const fn new() -> Self {
+            Self {
+                inner: <$map>::new(),
+                len: 0,
+            }
+        }
+
+        $crate::multiset_len_impl!();
+    };
+    ($map:ty) => {
+        /// Creates an empty multiset.
+        ///
+        /// The multiset is initially created with a capacity of 0, so it will
+        /// not allocate until it is first inserted into.
+        #[must_use]
+        pub fn new() -> Self {
+            Self {
+                inner: <$map>::new(),
+                len: 0,
+            }
+        }
+
+        /// Creates an empty multiset with the specified capacity for distinct
+        /// values.
+        ///
+        /// The multiset will be able to hold at least `capacity` distinct
+        /// values without reallocating. If `capacity` is 0, the multiset will
+        /// not allocate.
+        #[must_use]
+        pub fn with_capacity(capacity: usize) -> Self {
+            Self {
+                inner: <$map>::with_capacity(capacity),
+                len: 0,
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_base2_impl {
+    ($map:tt) => {
+        /// Creates an empty multiset which will use the given hash builder to
+        /// hash values.
+        #[inline]
+        pub fn with_hasher(hash_builder: S) -> Self {
+            Self::with_capacity_and_hasher(0, hash_builder)
+        }
+
+        /// Creates an empty multiset with the specified capacity for distinct
+        /// values, using `hash_builder` to hash the values.
+        #[inline]
+        pub fn with_capacity_and_hasher(n: usize, hash_builder: S) -> Self {
+            Self {
+                inner: $map::with_capacity_and_hasher(n, hash_builder),
+                len: 0,
+            }
+        }
+
+        /// Returns the number of distinct values the multiset can hold without
+        /// reallocating.
+        #[inline]
+        pub fn capacity(&self) -> usize {
+            self.inner.capacity()
+        }
+
+        $crate::multiset_len_impl!();
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_len_impl {
+    () => {
+        /// Returns the number of values in the multiset, counting all
+        /// occurrences of each value.
+        ///
+        /// See [`Self::distinct_len()`] for the number of distinct values.
+        #[must_use]
+        pub const fn len(&self) -> usize {
+            self.len
+        }
+
+        /// Returns `true` if the multiset contains no values.
+        #[must_use]
+        pub const fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        /// Returns the number of distinct values in the multiset.
+        ///
+        /// See [`Self::len()`] for the number of values including all
+        /// occurrences.
+        #[must_use]
+        pub fn distinct_len(&self) -> usize {
+            self.inner.len()
+        }
+
+        /// Removes all values from the multiset.
+        pub fn clear(&mut self) {
+            self.inner.clear();
+            self.len = 0;
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_capacity_impl {
+    () => {
+        /// Reserve capacity for `additional` more distinct values.
+        #[inline]
+        pub fn reserve(&mut self, additional: usize) {
+            self.inner.reserve(additional);
+        }
+
+        /// Shrinks the capacity of the multiset as much as possible. It will
+        /// drop down as much as possible while maintaining the internal rules
+        /// and possibly leaving some space in accordance with the resize
+        /// policy.
+        #[inline]
+        pub fn shrink_to_fit(&mut self) {
+            self.inner.shrink_to_fit();
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_mutators_impl {
+    ($remove:ident, ($($values_ref:tt)*)) => {
+        /// Adds one occurrence of `value` to the multiset.
+        ///
+        /// Returns the number of occurrences of `value` before the insertion.
+        pub fn insert(&mut self, value: T) -> usize {
+            self.insert_n(value, 1)
+        }
+
+        /// Adds `n` occurrences of `value` to the multiset.
+        ///
+        /// Returns the number of occurrences of `value` before the insertion.
+        /// If `n` is 0, the multiset is not modified.
+        pub fn insert_n(&mut self, value: T, n: usize) -> usize {
+            if n == 0 {
+                return self.count(&value);
+            }
+            let count = self.inner.entry(value).or_insert(0);
+            let previous = *count;
+            *count += n;
+            self.len += n;
+            previous
+        }
+
+        /// Returns the number of occurrences of `value` in the multiset.
+        pub fn count<Q>(&self, value: &Q) -> usize
+        where
+            Q: ?Sized,
+            $($values_ref)*
+        {
+            self.inner.get(value).copied().unwrap_or(0)
+        }
+
+        /// Returns `true` if the multiset contains at least one occurrence of
+        /// `value`.
+        pub fn contains<Q>(&self, value: &Q) -> bool
+        where
+            Q: ?Sized,
+            $($values_ref)*
+        {
+            self.inner.contains_key(value)
+        }
+
+        /// Removes one occurrence of `value` from the multiset.
+        ///
+        /// Returns `true` if `value` was present.
+        pub fn remove<Q>(&mut self, value: &Q) -> bool
+        where
+            Q: ?Sized,
+            $($values_ref)*
+        {
+            self.remove_n(value, 1) == 1
+        }
+
+        /// Removes up to `n` occurrences of `value` from the multiset.
+        ///
+        /// Returns the number of occurrences that were removed.
+        pub fn remove_n<Q>(&mut self, value: &Q, n: usize) -> usize
+        where
+            Q: ?Sized,
+            $($values_ref)*
+        {
+            let Some(count) = self.inner.get_mut(value) else {
+                return 0;
+            };
+            let removed = n.min(*count);
+            *count -= removed;
+            if *count == 0 {
+                self.inner.$remove(value);
+            }
+            self.len -= removed;
+            removed
+        }
+
+        /// Removes all occurrences of `value` from the multiset.
+        ///
+        /// Returns the number of occurrences that were removed.
+        pub fn remove_all<Q>(&mut self, value: &Q) -> usize
+        where
+            Q: ?Sized,
+            $($values_ref)*
+        {
+            let removed = self.inner.$remove(value).unwrap_or(0);
+            self.len -= removed;
+            removed
+        }
+
+        /// Retains only the values specified by the predicate.
+        ///
+        /// In other words, remove all values `v` for which `f(&v, count)`
+        /// returns `false`. The predicate is called once for each distinct
+        /// value.
+        pub fn retain<F>(&mut self, mut f: F)
+        where
+            F: FnMut(&T, usize) -> bool,
+        {
+            let mut len = 0;
+            self.inner.retain(|value, count| {
+                let retain = f(value, *count);
+                if retain {
+                    len += *count;
+                }
+                retain
+            });
+            self.len = len;
+        }
+
+        /// Returns the `n` most common values with their number of
+        /// occurrences, ordered from most to least common.
+        ///
+        /// Values with an equal number of occurrences are returned in
+        /// iteration order.
+        #[must_use]
+        pub fn most_common(&self, n: usize) -> Vec<(&T, usize)> {
+            let mut counts = self.iter_counts().collect::<Vec<_>>();
+            counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+            counts.truncate(n);
+            counts
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_algebra_impl {
+    ($type:tt, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            T: Clone,
+        {
+            /// Returns the sum of `self` and `other`, the number of occurrences
+            /// of each value is the sum of its occurrences in both multisets.
+            #[must_use]
+            pub fn sum(&self, other: &Self) -> Self {
+                let mut sum = Self::default();
+                self.iter_counts().chain(other.iter_counts()).for_each(|(value, count)| {
+                    sum.insert_n(value.clone(), count);
+                });
+                sum
+            }
+
+            /// Returns the union of `self` and `other`, the number of
+            /// occurrences of each value is the maximum of its occurrences in
+            /// both multisets.
+            #[must_use]
+            pub fn union(&self, other: &Self) -> Self {
+                let mut union = Self::default();
+                self.iter_counts().for_each(|(value, count)| {
+                    union.insert_n(value.clone(), count.max(other.count(value)));
+                });
+                other.iter_counts().for_each(|(value, count)| {
+                    if !self.contains(value) {
+                        union.insert_n(value.clone(), count);
+                    }
+                });
+                union
+            }
+
+            /// Returns the intersection of `self` and `other`, the number of
+            /// occurrences of each value is the minimum of its occurrences in
+            /// both multisets.
+            #[must_use]
+            pub fn intersection(&self, other: &Self) -> Self {
+                let mut intersection = Self::default();
+                self.iter_counts().for_each(|(value, count)| {
+                    intersection.insert_n(value.clone(), count.min(other.count(value)));
+                });
+                intersection
+            }
+
+            /// Returns the difference of `self` and `other`, the number of
+            /// occurrences of each value is the number of its occurrences in
+            /// `self` minus the number of its occurrences in `other`, if
+            /// positive.
+            #[must_use]
+            pub fn difference(&self, other: &Self) -> Self {
+                let mut difference = Self::default();
+                self.iter_counts().for_each(|(value, count)| {
+                    difference.insert_n(value.clone(), count.saturating_sub(other.count(value)));
+                });
+                difference
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::Add<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            T: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the sum of `self` and `rhs` as a new multiset.
+            fn add(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.sum(rhs)
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::BitOr<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            T: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the union of `self` and `rhs` as a new multiset.
+            fn bitor(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.union(rhs)
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::BitAnd<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            T: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the intersection of `self` and `rhs` as a new multiset.
+            fn bitand(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.intersection(rhs)
+            }
+        }
+
+        impl<$($generic_ids)*> std::ops::Sub<&$type<$($generic_ids)*>> for &$type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            T: Clone,
+        {
+            type Output = $type<$($generic_ids)*>;
+
+            /// Returns the difference of `self` and `rhs` as a new multiset.
+            fn sub(self, rhs: &$type<$($generic_ids)*>) -> Self::Output {
+                self.difference(rhs)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! multiset_traits_impl {
+    ($type:tt, ($($generic_ids:tt)*), ($($type_bounds:tt)*)) => {
+        impl<$($generic_ids)*> Default for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn default() -> Self {
+                Self {
+                    inner: Default::default(),
+                    len: 0,
+                }
+            }
+        }
+
+        impl<$($generic_ids)*> Extend<T> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+                iterable.into_iter().for_each(|value| {
+                    self.insert(value);
+                });
+            }
+        }
+
+        impl<'a, $($generic_ids)*> Extend<&'a T> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+            T: Copy,
+        {
+            fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iterable: I) {
+                self.extend(iterable.into_iter().copied());
+            }
+        }
+
+        impl<$($generic_ids)*> FromIterator<T> for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
+                let mut multiset = Self::default();
+                multiset.extend(iterable);
+                multiset
+            }
+        }
+
+        impl<$($generic_ids)*> PartialEq for $type<$($generic_ids)*>
+        where
+            $($type_bounds)*,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.len == other.len && self.inner == other.inner
+            }
+        }
+
+        impl<$($generic_ids)*> Eq for $type<$($generic_ids)*> where $($type_bounds)* {}
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_multiset_iter {
+    ($type:ident<$($type_generics:ident),*>, $inner_iter:ty, $inner_into_iter:ty) => {
+        /// An iterator over the values of a multiset, each value is repeated
+        /// as many times as it occurs in the multiset.
+        ///
+        /// This `struct` is created by the `iter` method on multiset.
+        pub struct Iter<'a, T> {
+            inner: $inner_iter,
+            current: Option<(&'a T, usize)>,
+            len: usize,
+        }
+
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    if let Some((value, remaining)) = &mut self.current {
+                        if *remaining > 0 {
+                            *remaining -= 1;
+                            self.len -= 1;
+                            return Some(*value);
+                        }
+                    }
+                    let (value, count) = self.inner.next()?;
+                    self.current = Some((value, *count));
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<T> ExactSizeIterator for Iter<'_, T> {
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<T> std::iter::FusedIterator for Iter<'_, T> {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<T> Clone for Iter<'_, T> {
+            fn clone(&self) -> Self {
+                Iter {
+                    inner: self.inner.clone(),
+                    current: self.current,
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<T> std::fmt::Debug for Iter<'_, T>
+        where
+            T: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// An iterator over the distinct values of a multiset and their number
+        /// of occurrences.
+        ///
+        /// This `struct` is created by the `iter_counts` method on multiset.
+        pub struct IterCounts<'a, T> {
+            inner: $inner_iter,
+        }
+
+        impl<'a, T> Iterator for IterCounts<'a, T> {
+            type Item = (&'a T, usize);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next().map(|(value, count)| (value, *count))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<T> ExactSizeIterator for IterCounts<'_, T> {
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+        }
+
+        impl<T> std::iter::FusedIterator for IterCounts<'_, T> {}
+
+        // FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+        impl<T> Clone for IterCounts<'_, T> {
+            fn clone(&self) -> Self {
+                IterCounts {
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+
+        impl<T> std::fmt::Debug for IterCounts<'_, T>
+        where
+            T: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_list().entries(self.clone()).finish()
+            }
+        }
+
+        /// An owning iterator over the distinct values of a multiset and their
+        /// number of occurrences.
+        ///
+        /// This `struct` is created by the `into_counts` method on multiset.
+        #[derive(Debug)]
+        pub struct IntoCounts<T> {
+            inner: $inner_into_iter,
+        }
+
+        impl<T> Iterator for IntoCounts<T> {
+            type Item = (T, usize);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl<T> ExactSizeIterator for IntoCounts<T> {
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+        }
+
+        impl<T> std::iter::FusedIterator for IntoCounts<T> {}
+
+        impl<$($type_generics),*> $type<$($type_generics),*> {
+            /// Return an iterator over the values of the multiset, each value
+            /// is repeated as many times as it occurs in the multiset.
+            #[must_use]
+            pub fn iter(&self) -> Iter<'_, T> {
+                Iter {
+                    inner: self.inner.iter(),
+                    current: None,
+                    len: self.len,
+                }
+            }
+
+            /// Return an iterator over the distinct values of the multiset and
+            /// their number of occurrences.
+            #[must_use]
+            pub fn iter_counts(&self) -> IterCounts<'_, T> {
+                IterCounts {
+                    inner: self.inner.iter(),
+                }
+            }
+
+            /// Return an owning iterator over the distinct values of the
+            /// multiset and their number of occurrences.
+            #[must_use]
+            pub fn into_counts(self) -> IntoCounts<T> {
+                IntoCounts {
+                    inner: self.inner.into_iter(),
+                }
+            }
+        }
+
+        impl<'a, $($type_generics),*> IntoIterator for &'a $type<$($type_generics),*> {
+            type IntoIter = Iter<'a, T>;
+            type Item = &'a T;
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+    };
+}
@@ -0,0 +1,53 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+/// Multiset implementation that behaves like `HashMap<T, usize>`, where the
+/// value is the number of occurrences of `T`.
+#[derive(Debug, Clone)]
+pub struct HashMultiset<T, S = RandomState> {
+    inner: HashMap<T, usize, S>,
+    len: usize,
+}
+
+impl<T> HashMultiset<T, RandomState> {
+    multiset_base_impl! {HashMap<T, usize>}
+}
+
+impl<T, S> HashMultiset<T, S> {
+    multiset_base2_impl! {HashMap}
+}
+
+impl<T, S> HashMultiset<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    multiset_capacity_impl! {}
+    multiset_mutators_impl! {remove, (T: Borrow<Q>, Q: Hash + Eq)}
+}
+
+multiset_traits_impl! {HashMultiset, (T, S), (T: Hash + Eq, S: BuildHasher + Default)}
+multiset_algebra_impl! {HashMultiset, (T, S), (T: Hash + Eq, S: BuildHasher + Default)}
+
+impl_multiset_iter! {
+    HashMultiset<T, S>,
+    std::collections::hash_map::Iter<'a, T, usize>,
+    std::collections::hash_map::IntoIter<T, usize>
+}
+
+#[macro_export]
+macro_rules! hashmultiset {
+    ($($value:expr,)+) => { hashmultiset!($($value),+) };
+    ($($value:expr),*) => {
+        {
+            let mut _set = HashMultiset::new();
+            $(
+                let _ = _set.insert($value);
+            )*
+            _set
+        }
+    };
+}
@@ -0,0 +1,57 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+use indexmap::Equivalent;
+use indexmap::IndexMap;
+
+/// Multiset implementation that behaves like `IndexMap<T, usize>`, where the
+/// value is the number of occurrences of `T`.
+///
+/// Distinct values are kept in insertion order. Removing the last occurrence
+/// of a value shifts all following values to preserve that order.
+#[derive(Debug, Clone)]
+pub struct IndexMultiset<T, S = RandomState> {
+    inner: IndexMap<T, usize, S>,
+    len: usize,
+}
+
+impl<T> IndexMultiset<T> {
+    multiset_base_impl! {IndexMap<T, usize>}
+}
+
+impl<T, S> IndexMultiset<T, S> {
+    multiset_base2_impl! {IndexMap}
+}
+
+impl<T, S> IndexMultiset<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    multiset_capacity_impl! {}
+    multiset_mutators_impl! {shift_remove, (Q: Hash + Equivalent<T>)}
+}
+
+multiset_traits_impl! {IndexMultiset, (T, S), (T: Hash + Eq, S: BuildHasher + Default)}
+multiset_algebra_impl! {IndexMultiset, (T, S), (T: Hash + Eq, S: BuildHasher + Default)}
+
+impl_multiset_iter! {
+    IndexMultiset<T, S>,
+    indexmap::map::Iter<'a, T, usize>,
+    indexmap::map::IntoIter<T, usize>
+}
+
+#[macro_export]
+macro_rules! indexmultiset {
+    ($($value:expr,)+) => { indexmultiset!($($value),+) };
+    ($($value:expr),*) => {
+        {
+            let mut _set = IndexMultiset::new();
+            $(
+                let _ = _set.insert($value);
+            )*
+            _set
+        }
+    };
+}
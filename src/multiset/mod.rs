@@ -0,0 +1,22 @@
+#[macro_use]
+mod gen_macros;
+
+#[cfg(feature = "btreemultiset")]
+pub mod btree_multiset;
+#[cfg(feature = "btreemultiset")]
+pub use btree_multiset::BTreeMultiset;
+
+#[cfg(feature = "hashmultiset")]
+pub mod hash_multiset;
+#[cfg(feature = "hashmultiset")]
+pub use hash_multiset::HashMultiset;
+
+#[cfg(all(feature = "indexmap", feature = "indexmultiset"))]
+pub mod index_multiset;
+#[cfg(all(feature = "indexmap", feature = "indexmultiset"))]
+pub use index_multiset::IndexMultiset;
+
+#[cfg(feature = "vecmultiset")]
+pub mod vec_multiset;
+#[cfg(feature = "vecmultiset")]
+pub use vec_multiset::VecMultiset;
@@ -0,0 +1,591 @@
+use std::iter::FusedIterator;
+
+use crate::IndexKey;
+use crate::VecMap;
+
+/// Multiset implementation that behaves like `VecMap<K, usize>`, where the
+/// value is the number of occurrences of `K`.
+///
+/// Keys are [`IndexKey`]s, lookups are therefore a direct index into the
+/// underlying [`VecMap`] without hashing. Keys are iterated following their
+/// natural order.
+#[derive(Clone)]
+pub struct VecMultiset<K> {
+    inner: VecMap<K, usize>,
+    len: usize,
+}
+
+impl<K> VecMultiset<K>
+where
+    K: IndexKey,
+{
+    /// Creates an empty multiset.
+    ///
+    /// For performance reasons it's almost always better to avoid dynamic
+    /// resizing by using [`Self::with_capacity()`] instead.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: VecMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty multiset with capacity to hold keys in the index
+    /// range of `0..n` without reallocating.
+    #[must_use]
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            inner: VecMap::with_capacity(n),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys the multiset can hold without reallocating.
+    ///
+    /// The index range of keys that the multiset can hold without
+    /// reallocating is `0..capacity`.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Reserve capacity for `additional` keys, starting from the current
+    /// capacity.
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
+    /// Returns the number of keys in the multiset, counting all occurrences
+    /// of each key.
+    ///
+    /// See [`Self::distinct_len()`] for the number of distinct keys.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the multiset contains no keys.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of distinct keys in the multiset.
+    ///
+    /// See [`Self::len()`] for the number of keys including all occurrences.
+    #[must_use]
+    pub const fn distinct_len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Removes all keys from the multiset without changing the capacity.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.len = 0;
+    }
+
+    /// Adds one occurrence of `key` to the multiset.
+    ///
+    /// Returns the number of occurrences of `key` before the insertion.
+    pub fn insert(&mut self, key: K) -> usize {
+        self.insert_n(key, 1)
+    }
+
+    /// Adds `n` occurrences of `key` to the multiset.
+    ///
+    /// Returns the number of occurrences of `key` before the insertion. If `n`
+    /// is 0, the multiset is not modified.
+    pub fn insert_n(&mut self, key: K, n: usize) -> usize {
+        if n == 0 {
+            return self.count(key);
+        }
+        let count = self.inner.entry(key).or_insert(0);
+        let previous = *count;
+        *count += n;
+        self.len += n;
+        previous
+    }
+
+    /// Adds one occurrence of `key` to the multiset.
+    ///
+    /// Returns the number of occurrences of `key` after the increment.
+    pub fn increment(&mut self, key: K) -> usize {
+        self.insert(key) + 1
+    }
+
+    /// Removes one occurrence of `key` from the multiset, `key` is removed
+    /// from the multiset when its count reaches zero.
+    ///
+    /// Returns the number of occurrences of `key` after the decrement, or
+    /// `None` if `key` was not present.
+    pub fn decrement(&mut self, key: K) -> Option<usize> {
+        let previous = self.count(key);
+        self.remove(key).then(|| previous - 1)
+    }
+
+    /// Returns the number of occurrences of `key` in the multiset.
+    #[must_use]
+    pub fn count(&self, key: K) -> usize {
+        self.inner.get(key).copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the multiset contains at least one occurrence of
+    /// `key`.
+    #[must_use]
+    pub fn contains(&self, key: K) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Removes one occurrence of `key` from the multiset.
+    ///
+    /// Returns `true` if `key` was present.
+    pub fn remove(&mut self, key: K) -> bool {
+        self.remove_n(key, 1) == 1
+    }
+
+    /// Removes up to `n` occurrences of `key` from the multiset.
+    ///
+    /// Returns the number of occurrences that were removed.
+    pub fn remove_n(&mut self, key: K, n: usize) -> usize {
+        let Some(count) = self.inner.get_mut(key) else {
+            return 0;
+        };
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.inner.remove(key);
+        }
+        self.len -= removed;
+        removed
+    }
+
+    /// Removes all occurrences of `key` from the multiset.
+    ///
+    /// Returns the number of occurrences that were removed.
+    pub fn remove_all(&mut self, key: K) -> usize {
+        let removed = self.inner.remove(key).unwrap_or(0);
+        self.len -= removed;
+        removed
+    }
+
+    /// Retains only the keys specified by the predicate.
+    ///
+    /// In other words, remove all keys `k` for which `f(k, count)` returns
+    /// `false`. The keys are visited in order.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(K, usize) -> bool,
+    {
+        let mut len = 0;
+        self.inner.retain(|key, count| {
+            let retain = f(key, *count);
+            if retain {
+                len += *count;
+            }
+            retain
+        });
+        self.len = len;
+    }
+
+    /// Returns the `n` most common keys with their number of occurrences,
+    /// ordered from most to least common.
+    ///
+    /// Keys with an equal number of occurrences are returned in key order.
+    #[must_use]
+    pub fn most_common(&self, n: usize) -> Vec<(K, usize)> {
+        let mut counts = self.iter_counts().collect::<Vec<_>>();
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        counts.truncate(n);
+        counts
+    }
+
+    /// Return an iterator over the keys of the multiset in key order, each
+    /// key is repeated as many times as it occurs in the multiset.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter {
+            inner: self.inner.iter(),
+            current: None,
+            len: self.len,
+        }
+    }
+
+    /// Return an iterator over the distinct keys of the multiset and their
+    /// number of occurrences, in key order.
+    #[must_use]
+    pub fn iter_counts(&self) -> IterCounts<'_, K> {
+        IterCounts {
+            inner: self.inner.iter(),
+        }
+    }
+
+    /// Return an owning iterator over the distinct keys of the multiset and
+    /// their number of occurrences, in key order.
+    #[must_use]
+    pub fn into_counts(self) -> IntoCounts<K> {
+        IntoCounts {
+            inner: self.inner.into_iter(),
+        }
+    }
+
+    /// Returns the element-wise sum of `self` and `other`, the number of
+    /// occurrences of each key is the sum of its occurrences in both
+    /// multisets.
+    #[must_use]
+    pub fn sum(&self, other: &Self) -> Self {
+        let mut sum = self.clone();
+        other.iter_counts().for_each(|(key, count)| {
+            sum.insert_n(key, count);
+        });
+        sum
+    }
+
+    /// Returns the element-wise maximum of `self` and `other`, the number of
+    /// occurrences of each key is the maximum of its occurrences in both
+    /// multisets.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.iter_counts().for_each(|(key, count)| {
+            let current = self.count(key);
+            if count > current {
+                union.insert_n(key, count - current);
+            }
+        });
+        union
+    }
+
+    /// Returns the element-wise minimum of `self` and `other`, the number of
+    /// occurrences of each key is the minimum of its occurrences in both
+    /// multisets.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = self.clone();
+        self.iter_counts().for_each(|(key, count)| {
+            let other_count = other.count(key);
+            if other_count < count {
+                intersection.remove_n(key, count - other_count);
+            }
+        });
+        intersection
+    }
+
+    /// Returns the element-wise difference of `self` and `other`, the number
+    /// of occurrences of each key is the number of its occurrences in `self`
+    /// minus the number of its occurrences in `other`, if positive.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        other.iter_counts().for_each(|(key, count)| {
+            difference.remove_n(key, count);
+        });
+        difference
+    }
+}
+
+impl<K> Default for VecMultiset<K>
+where
+    K: IndexKey,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Extend<K> for VecMultiset<K>
+where
+    K: IndexKey,
+{
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iterable: I) {
+        iterable.into_iter().for_each(|key| {
+            self.insert(key);
+        });
+    }
+}
+
+impl<'a, K> Extend<&'a K> for VecMultiset<K>
+where
+    K: IndexKey,
+{
+    fn extend<I: IntoIterator<Item = &'a K>>(&mut self, iterable: I) {
+        self.extend(iterable.into_iter().copied());
+    }
+}
+
+impl<K> FromIterator<K> for VecMultiset<K>
+where
+    K: IndexKey,
+{
+    fn from_iter<I: IntoIterator<Item = K>>(iterable: I) -> Self {
+        let mut multiset = Self::default();
+        multiset.extend(iterable);
+        multiset
+    }
+}
+
+impl<K> PartialEq for VecMultiset<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.inner == other.inner
+    }
+}
+
+impl<K> Eq for VecMultiset<K> {}
+
+impl<K> std::fmt::Debug for VecMultiset<K>
+where
+    K: IndexKey + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter_counts()).finish()
+    }
+}
+
+impl<K> std::ops::Add<&VecMultiset<K>> for &VecMultiset<K>
+where
+    K: IndexKey,
+{
+    type Output = VecMultiset<K>;
+
+    /// Returns the element-wise sum of `self` and `rhs` as a new multiset.
+    fn add(self, rhs: &VecMultiset<K>) -> Self::Output {
+        self.sum(rhs)
+    }
+}
+
+impl<K> std::ops::BitOr<&VecMultiset<K>> for &VecMultiset<K>
+where
+    K: IndexKey,
+{
+    type Output = VecMultiset<K>;
+
+    /// Returns the element-wise maximum of `self` and `rhs` as a new multiset.
+    fn bitor(self, rhs: &VecMultiset<K>) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<K> std::ops::BitAnd<&VecMultiset<K>> for &VecMultiset<K>
+where
+    K: IndexKey,
+{
+    type Output = VecMultiset<K>;
+
+    /// Returns the element-wise minimum of `self` and `rhs` as a new multiset.
+    fn bitand(self, rhs: &VecMultiset<K>) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<K> std::ops::Sub<&VecMultiset<K>> for &VecMultiset<K>
+where
+    K: IndexKey,
+{
+    type Output = VecMultiset<K>;
+
+    /// Returns the element-wise difference of `self` and `rhs` as a new
+    /// multiset.
+    fn sub(self, rhs: &VecMultiset<K>) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+impl<'a, K> IntoIterator for &'a VecMultiset<K>
+where
+    K: IndexKey,
+{
+    type IntoIter = Iter<'a, K>;
+    type Item = K;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the keys of a [`VecMultiset`] in key order, each key is
+/// repeated as many times as it occurs in the multiset.
+///
+/// This `struct` is created by [`VecMultiset::iter()`].
+pub struct Iter<'a, K> {
+    inner: crate::vec_map::Iter<'a, K, usize>,
+    current: Option<(K, usize)>,
+    len: usize,
+}
+
+impl<K> Iterator for Iter<'_, K>
+where
+    K: IndexKey,
+{
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, remaining)) = &mut self.current {
+                if *remaining > 0 {
+                    *remaining -= 1;
+                    self.len -= 1;
+                    return Some(*key);
+                }
+            }
+            let (key, count) = self.inner.next()?;
+            self.current = Some((key, *count));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K> ExactSizeIterator for Iter<'_, K>
+where
+    K: IndexKey,
+{
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K> FusedIterator for Iter<'_, K> where K: IndexKey {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K> Clone for Iter<'_, K>
+where
+    K: IndexKey,
+{
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+            current: self.current,
+            len: self.len,
+        }
+    }
+}
+
+impl<K> std::fmt::Debug for Iter<'_, K>
+where
+    K: IndexKey + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the distinct keys of a [`VecMultiset`] and their number of
+/// occurrences, in key order.
+///
+/// This `struct` is created by [`VecMultiset::iter_counts()`].
+pub struct IterCounts<'a, K> {
+    inner: crate::vec_map::Iter<'a, K, usize>,
+}
+
+impl<K> Iterator for IterCounts<'_, K>
+where
+    K: IndexKey,
+{
+    type Item = (K, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, count)| (key, *count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K> DoubleEndedIterator for IterCounts<'_, K>
+where
+    K: IndexKey,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, count)| (key, *count))
+    }
+}
+
+impl<K> ExactSizeIterator for IterCounts<'_, K>
+where
+    K: IndexKey,
+{
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K> FusedIterator for IterCounts<'_, K> where K: IndexKey {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K> Clone for IterCounts<'_, K> {
+    fn clone(&self) -> Self {
+        IterCounts {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K> std::fmt::Debug for IterCounts<'_, K>
+where
+    K: IndexKey + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An owning iterator over the distinct keys of a [`VecMultiset`] and their
+/// number of occurrences, in key order.
+///
+/// This `struct` is created by [`VecMultiset::into_counts()`].
+#[derive(Debug)]
+pub struct IntoCounts<K> {
+    inner: crate::vec_map::IntoIter<K, usize>,
+}
+
+impl<K> Iterator for IntoCounts<K>
+where
+    K: IndexKey,
+{
+    type Item = (K, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K> DoubleEndedIterator for IntoCounts<K>
+where
+    K: IndexKey,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K> ExactSizeIterator for IntoCounts<K>
+where
+    K: IndexKey,
+{
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K> FusedIterator for IntoCounts<K> where K: IndexKey {}
+
+#[macro_export]
+macro_rules! vecmultiset {
+    ($($key:expr,)+) => { vecmultiset!($($key),+) };
+    ($($key:expr),*) => {
+        {
+            let mut _set = VecMultiset::new();
+            $(
+                let _ = _set.insert($key);
+            )*
+            _set
+        }
+    };
+}
@@ -45,6 +45,13 @@ use smallvec::SmallVec;
 /// assert_eq!(4, map.len());
 /// assert!(!map.is_inline());
 /// ```
+///
+/// # Serialization and deserialization
+///
+/// An optional feature that can be unlocked with the `serde` feature.
+/// `SmallMap`s are serialized as an ordered map, like `IndexMap`.
+/// Deserialization stores the data inline and only moves it to the heap when it
+/// exceeds `C`.
 #[derive(Clone)]
 pub struct SmallMap<K, V, const C: usize, S = RandomState> {
     data: MapData<K, V, C, S>,
@@ -104,6 +111,19 @@ impl<K, V, const C: usize, S> SmallMap<K, V, C, S> {
         matches!(self.data, MapData::Inline(_))
     }
 
+    /// The number of key-values the map can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            MapData::Inline(vec) => vec.capacity(),
+            MapData::Heap(map) => map.capacity(),
+        }
+    }
+
+    /// Removes all key-values from the map, the data is moved inline.
+    pub fn clear(&mut self) {
+        self.data = MapData::Inline(SmallVec::new());
+    }
+
     /// Returns an iterator over the key-values in insertion order.
     pub fn iter(&'_ self) -> Iter<'_, K, V> {
         match &self.data {
@@ -331,6 +351,39 @@ where
         }
     }
 
+    /// Retains only the key-value pairs specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k, &mut v)`
+    /// returns `false`. The order of the remaining pairs is preserved.
+    ///
+    /// If the removals cause the size of the `SmallMap` to be equal to or
+    /// below the inline capacity, all remaining data is moved inline.
+    ///
+    /// Computational complexity: O(n)
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        match &mut self.data {
+            MapData::Inline(vec) => vec.retain(|(k, v)| f(k, v)),
+            MapData::Heap(map) => {
+                map.retain(|k, v| f(k, v));
+                if map.len() <= C {
+                    self.data = MapData::Inline(map.drain(0..map.len()).collect());
+                }
+            }
+        }
+    }
+
+    /// Shrinks the capacity of the map as much as possible.
+    ///
+    /// Data that is stored inline always occupies the inline capacity.
+    pub fn shrink_to_fit(&mut self) {
+        if let MapData::Heap(map) = &mut self.data {
+            map.shrink_to_fit();
+        }
+    }
+
     /// Binary searches this map with a comparator function.
     ///
     /// The comparator function should implement an order consistent with the
@@ -839,6 +892,65 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<K, V, const C: usize, S> serde::Serialize for SmallMap<K, V, C, S>
+where
+    K: serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// Deserializes the key-value pairs in inline storage, the data is only moved
+/// to an `IndexMap` when the number of distinct keys exceeds `C`. Like
+/// `IndexMap`, a duplicate key overwrites the value of the earlier key without
+/// changing its position.
+#[cfg(feature = "serde")]
+impl<'de, K, V, const C: usize, S> serde::Deserialize<'de> for SmallMap<K, V, C, S>
+where
+    K: serde::Deserialize<'de> + Hash + Eq,
+    V: serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SmallMapVisitor<K, V, const C: usize, S>(std::marker::PhantomData<(K, V, S)>);
+
+        impl<'de, K, V, const C: usize, S> serde::de::Visitor<'de> for SmallMapVisitor<K, V, C, S>
+        where
+            K: serde::Deserialize<'de> + Hash + Eq,
+            V: serde::Deserialize<'de>,
+            S: BuildHasher + Default,
+        {
+            type Value = SmallMap<K, V, C, S>;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut map = SmallMap::default();
+                while let Some((key, value)) = access.next_entry()? {
+                    map.insert(key, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(SmallMapVisitor(std::marker::PhantomData))
+    }
+}
+
 #[macro_export]
 macro_rules! smallmap {
     // count helper: transform any expression into 1
@@ -1660,6 +1772,22 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn retain_moves_data_inline_when_below_capacity() {
+        let mut map: SmallMap<_, _, 2> = smallmap! {0=>6, 1=>5, 2=>4, 3=>3};
+        assert!(!map.is_inline());
+        map.retain(|k, v| {
+            *v += 1;
+            k % 2 == 1
+        });
+        assert!(map.is_inline());
+        assert_eq!(smallmap_inline! {1=>6, 3=>4}, map);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert!(map.is_inline());
+    }
+
     #[test]
     #[should_panic(
         expected = "Cannot instantiate SmallMap with no inline capacity, use positive capacity or use IndexMap instead"
@@ -1753,4 +1881,63 @@ mod test {
             );
         }
     }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn test_serde() {
+            let input: SmallMap<usize, usize, 2> = smallmap! {2 => 20, 0 => 0};
+            let serialized_str = serde_json::to_string(&input).unwrap();
+            assert_eq!(r#"{"2":20,"0":0}"#, serialized_str);
+
+            let deserialized =
+                serde_json::from_str::<SmallMap<usize, usize, 2>>(&serialized_str).unwrap();
+            assert_eq!(input, deserialized);
+            assert_eq!(vec![2, 0], deserialized.keys().copied().collect::<Vec<_>>());
+            assert!(deserialized.is_inline());
+        }
+
+        #[test]
+        fn deserialize_spills_to_heap_when_exceeding_capacity() {
+            let deserialized =
+                serde_json::from_str::<SmallMap<usize, usize, 2>>(r#"{"3":3,"1":1,"2":2}"#)
+                    .unwrap();
+            assert!(!deserialized.is_inline());
+            assert_eq!(3, deserialized.len());
+            assert_eq!(
+                vec![(&3, &3), (&1, &1), (&2, &2)],
+                deserialized.iter().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn deserialize_overwrites_duplicate_keys_inline() {
+            let deserialized =
+                serde_json::from_str::<SmallMap<usize, usize, 2>>(r#"{"1":1,"2":2,"1":3}"#)
+                    .unwrap();
+            assert!(deserialized.is_inline());
+            assert_eq!(
+                vec![(&1, &3), (&2, &2)],
+                deserialized.iter().collect::<Vec<_>>()
+            );
+
+            let deserialized =
+                serde_json::from_str::<SmallMap<usize, usize, 2>>(r#"{"1":1,"2":2,"3":3,"2":4}"#)
+                    .unwrap();
+            assert!(!deserialized.is_inline());
+            assert_eq!(
+                vec![(&1, &1), (&2, &4), (&3, &3)],
+                deserialized.iter().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn deserialize_empty() {
+            let deserialized = serde_json::from_str::<SmallMap<usize, usize, 2>>("{}").unwrap();
+            assert!(deserialized.is_empty());
+            assert!(deserialized.is_inline());
+        }
+    }
 }
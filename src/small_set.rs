@@ -44,7 +44,14 @@ use crate::SmallMap;
 /// assert_eq!(4, set.len());
 /// assert!(!set.is_inline());
 /// ```
-#[derive(Default, Clone)]
+///
+/// # Serialization and deserialization
+///
+/// An optional feature that can be unlocked with the `serde` feature.
+/// `SmallSet`s are serialized as an ordered sequence, like `IndexSet`.
+/// Deserialization stores the data inline and only moves it to the heap when it
+/// exceeds `C`.
+#[derive(Clone)]
 pub struct SmallSet<T, const C: usize, S = RandomState> {
     data: SmallMap<T, (), C, S>,
 }
@@ -90,6 +97,16 @@ impl<T, const C: usize, S> SmallSet<T, C, S> {
         self.data.is_inline()
     }
 
+    /// The number of values the set can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Removes all values from the set, the data is moved inline.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
     /// Returns an iterator over the values in insertion order.
     pub fn iter(&'_ self) -> Iter<'_, T> {
         Iter {
@@ -191,6 +208,41 @@ where
         self.data.remove(key).is_some()
     }
 
+    /// Remove the value equivalent to `value` and return it.
+    ///
+    /// If `value` is not present `None` is returned. Like
+    /// [`Self::remove()`], order is not preserved.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(1)
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        Q: Hash + Equivalent<T> + ?Sized,
+    {
+        self.data.swap_remove_full(value).map(|(_, k, ())| k)
+    }
+
+    /// Retains only the values specified by the predicate.
+    ///
+    /// In other words, remove all values `v` such that `f(&v)` returns
+    /// `false`. The order of the remaining values is preserved.
+    ///
+    /// Computational complexity: O(n)
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.data.retain(|k, ()| f(k));
+    }
+
+    /// Shrinks the capacity of the set as much as possible.
+    ///
+    /// Data that is stored inline always occupies the inline capacity.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
     /// Return an iterator over the values that are in `self` but not `other`.
     ///
     /// Values are produced in the same order that they appear in `self`.
@@ -271,6 +323,14 @@ where
     }
 }
 
+impl<T, const C: usize, S> Default for SmallSet<T, C, S> {
+    fn default() -> Self {
+        Self {
+            data: SmallMap::default(),
+        }
+    }
+}
+
 impl<T, const C: usize, S> Hash for SmallSet<T, C, S>
 where
     T: Hash + Eq,
@@ -383,9 +443,21 @@ where
     }
 }
 
+impl<T, const C: usize, S> Extend<T> for SmallSet<T, C, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| {
+            self.insert(value);
+        });
+    }
+}
+
 impl<T, const C: usize, S> Debug for SmallSet<T, C, S>
 where
-    T: Hash + Eq + Debug,
+    T: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_set().entries(self.iter()).finish()
@@ -670,6 +742,63 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T, const C: usize, S> serde::Serialize for SmallSet<T, C, S>
+where
+    T: serde::Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserializes the values in inline storage, the data is only moved to an
+/// `IndexSet` when the number of distinct values exceeds `C`. Duplicate values
+/// are ignored.
+#[cfg(feature = "serde")]
+impl<'de, T, const C: usize, S> serde::Deserialize<'de> for SmallSet<T, C, S>
+where
+    T: serde::Deserialize<'de> + Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SmallSetVisitor<T, const C: usize, S>(std::marker::PhantomData<(T, S)>);
+
+        impl<'de, T, const C: usize, S> serde::de::Visitor<'de> for SmallSetVisitor<T, C, S>
+        where
+            T: serde::Deserialize<'de> + Hash + Eq,
+            S: BuildHasher + Default,
+        {
+            type Value = SmallSet<T, C, S>;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut set = SmallSet {
+                    data: SmallMap::default(),
+                };
+                while let Some(value) = access.next_element()? {
+                    set.insert(value);
+                }
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_seq(SmallSetVisitor(std::marker::PhantomData))
+    }
+}
+
 /// Create a [`SmallSet`] with with the specified values.
 #[macro_export]
 macro_rules! smallset {
@@ -754,6 +883,21 @@ mod test {
         test::<3>(true);
     }
 
+    #[test]
+    fn take_and_retain_remove_values() {
+        let mut set: SmallSet<_, 2> = smallset! {0, 1, 2, 3};
+        assert_eq!(Some(1), set.take(&1));
+        assert_eq!(None, set.take(&1));
+        set.retain(|v| *v != 0);
+        assert!(set.is_inline());
+        assert_eq!(vec![&3, &2], set.iter().collect::<Vec<_>>());
+
+        set.extend([4, 2]);
+        assert_eq!(3, set.len());
+        set.clear();
+        assert!(set.is_empty());
+    }
+
     #[test]
     fn insert_and_insert_full_tests() {
         // Test cases:
@@ -1058,4 +1202,35 @@ mod test {
             &self.0.to_string() == key
         }
     }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn test_serde() {
+            let input: SmallSet<usize, 2> = smallset! {2, 0};
+            let serialized_str = serde_json::to_string(&input).unwrap();
+            assert_eq!("[2,0]", serialized_str);
+
+            let deserialized = serde_json::from_str::<SmallSet<usize, 2>>(&serialized_str).unwrap();
+            assert_eq!(input, deserialized);
+            assert_eq!(vec![2, 0], deserialized.iter().copied().collect::<Vec<_>>());
+            assert!(deserialized.is_inline());
+        }
+
+        #[test]
+        fn deserialize_ignores_duplicates_and_spills_to_heap() {
+            let deserialized = serde_json::from_str::<SmallSet<usize, 2>>("[1,2,1,2]").unwrap();
+            assert!(deserialized.is_inline());
+            assert_eq!(vec![1, 2], deserialized.iter().copied().collect::<Vec<_>>());
+
+            let deserialized = serde_json::from_str::<SmallSet<usize, 2>>("[1,2,1,3]").unwrap();
+            assert!(!deserialized.is_inline());
+            assert_eq!(
+                vec![1, 2, 3],
+                deserialized.iter().copied().collect::<Vec<_>>()
+            );
+        }
+    }
 }
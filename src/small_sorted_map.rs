@@ -0,0 +1,1498 @@
+use std::borrow::Borrow;
+use std::collections::btree_map;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::hash::Hash;
+use std::iter::FusedIterator;
+use std::mem;
+use std::ops::Bound;
+use std::ops::Index;
+use std::ops::RangeBounds;
+
+use smallvec::SmallVec;
+
+/// A sorted map-like container that can store a specified number of elements
+/// inline.
+///
+/// `SmallSortedMap` shares most of its API with, and behaves like
+/// [`BTreeMap`]. It can store a limited amount of data inline in a sorted
+/// [`SmallVec`], lookups are done using binary search. If the data exceeds the
+/// limit `C`, `SmallSortedMap` will move _all_ its data over to the heap in the
+/// form of a `BTreeMap`. For performance reasons, transitions between heap and
+/// inline storage should generally be avoided.
+///
+/// The `SmallSortedMap` datastructure is meant for situations where the data
+/// does not exceed `C` _most of the time_ but it still needs to support cases
+/// where the data _does_ exceed `C`.
+///
+/// # Example
+///
+/// ```
+/// use more_collections::SmallSortedMap;
+///
+/// let mut map = SmallSortedMap::<usize, String, 3>::new();
+/// // The map can hold up to three items inline
+/// map.insert(2, "two".to_string());
+/// map.insert(0, "zero".to_string());
+/// map.insert(1, "one".to_string());
+/// assert_eq!(3, map.len());
+/// assert!(map.is_inline());
+/// // Keys are always sorted
+/// assert_eq!(vec![&0, &1, &2], map.keys().collect::<Vec<_>>());
+///
+/// // Adding the fourth item will move the map to the heap
+/// map.insert(3, "three".to_string());
+/// assert_eq!(4, map.len());
+/// assert!(!map.is_inline());
+/// ```
+///
+/// # Serialization and deserialization
+///
+/// An optional feature that can be unlocked with the `serde` feature.
+/// `SmallSortedMap`s are serialized as a sorted map, like `BTreeMap`.
+/// Deserialization stores the data inline and only moves it to the heap when it
+/// exceeds `C`.
+#[derive(Clone)]
+pub struct SmallSortedMap<K, V, const C: usize> {
+    data: MapData<K, V, C>,
+}
+
+#[derive(Debug, Clone)]
+enum MapData<K, V, const C: usize> {
+    Inline(SmallVec<[(K, V); C]>),
+    Heap(BTreeMap<K, V>),
+}
+
+impl<K, V, const C: usize> MapData<K, V, C> {
+    /// Returns `true` if the data is on the heap, holds `C + 1` key-values and
+    /// contains `key`.
+    fn is_heap_at_capacity_with<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q> + Ord,
+        Q: Ord + ?Sized,
+    {
+        matches!(self, MapData::Heap(map) if map.len() == C + 1 && map.contains_key(key))
+    }
+
+    fn heap(&self) -> &BTreeMap<K, V> {
+        match self {
+            MapData::Heap(map) => map,
+            MapData::Inline(_) => unreachable!("data is on the heap"),
+        }
+    }
+
+    fn heap_mut(&mut self) -> &mut BTreeMap<K, V> {
+        match self {
+            MapData::Heap(map) => map,
+            MapData::Inline(_) => unreachable!("data is on the heap"),
+        }
+    }
+}
+
+impl<K, V, const C: usize> SmallSortedMap<K, V, C> {
+    /// Create a new map.
+    #[must_use]
+    pub fn new() -> Self {
+        debug_assert!(
+            C > 0,
+            "Cannot instantiate SmallSortedMap with no inline capacity, use positive capacity or use BTreeMap instead",
+        );
+        Self::default()
+    }
+
+    /// The number of key-values stored in the map.
+    pub fn len(&self) -> usize {
+        match &self.data {
+            MapData::Inline(sv) => sv.len(),
+            MapData::Heap(map) => map.len(),
+        }
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The memory capacity that will be allocated inline. If the number of
+    /// values exceeds the inline capacity, the map will move to the heap.
+    pub const fn inline_capacity(&self) -> usize {
+        C
+    }
+
+    /// Is the data contained by this map stored inline (`true`) or on the heap
+    /// (`false`).
+    pub const fn is_inline(&self) -> bool {
+        matches!(self.data, MapData::Inline(_))
+    }
+
+    /// Returns an iterator over the key-values in sorted order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        match &self.data {
+            MapData::Inline(vec) => Iter::Inline(vec.iter()),
+            MapData::Heap(map) => Iter::Heap(map.iter()),
+        }
+    }
+
+    /// Returns an iterator over the key-values in sorted order, with mutable
+    /// references to the values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        match &mut self.data {
+            MapData::Inline(vec) => IterMut::Inline(vec.iter_mut()),
+            MapData::Heap(map) => IterMut::Heap(map.iter_mut()),
+        }
+    }
+
+    /// Returns an iterator over the keys in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Returns an iterator over the values in the sorted order of their keys.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+}
+
+impl<K, V, const C: usize> SmallSortedMap<K, V, C>
+where
+    K: Ord,
+{
+    /// Convert the specified map and turn it into a `SmallSortedMap`.
+    ///
+    /// If the map len is smaller or equal the inline capacity, the data will be
+    /// moved inline.
+    #[must_use]
+    pub fn from_map(map: BTreeMap<K, V>) -> Self {
+        if map.len() <= C {
+            Self {
+                data: MapData::Inline(map.into_iter().collect()),
+            }
+        } else {
+            Self {
+                data: MapData::Heap(map),
+            }
+        }
+    }
+
+    /// Returns the first key-value pair in the map, the key in this pair is
+    /// the minimum key in the map.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        match &self.data {
+            MapData::Inline(vec) => vec.first().map(|(k, v)| (k, v)),
+            MapData::Heap(map) => map.first_key_value(),
+        }
+    }
+
+    /// Returns the last key-value pair in the map, the key in this pair is the
+    /// maximum key in the map.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        match &self.data {
+            MapData::Inline(vec) => vec.last().map(|(k, v)| (k, v)),
+            MapData::Heap(map) => map.last_key_value(),
+        }
+    }
+
+    /// Return a reference to the value stored for `key`, if it is present,
+    /// else `None`.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_k, v)| v)
+    }
+
+    /// Return references to the key-value pair stored for `key`, if it is
+    /// present, else `None`.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match &self.data {
+            MapData::Inline(vec) => search(vec, key).ok().map(|index| {
+                let (k, v) = &vec[index];
+                (k, v)
+            }),
+            MapData::Heap(map) => map.get_key_value(key),
+        }
+    }
+
+    /// Return a mutable reference to the value stored for `key`, if it is
+    /// present, else `None`.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match &mut self.data {
+            MapData::Inline(vec) => search(vec, key).ok().map(|index| &mut vec[index].1),
+            MapData::Heap(map) => map.get_mut(key),
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).is_some()
+    }
+
+    /// Inserts the specified key-value pair into this map.
+    ///
+    /// If a value for the specified `key` already exists, the new value will
+    /// overwrite the existing value and the old value is returned. The key is
+    /// not updated.
+    ///
+    /// If a new key is added that causes the size of the `SmallSortedMap` to
+    /// exceed the inline capacity, all existing data and the new key-value pair
+    /// is moved to the heap.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(log n)
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place
+    /// manipulation.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, C> {
+        let inline_index = match &self.data {
+            MapData::Inline(vec) => Some(search(vec, &key)),
+            MapData::Heap(_) => None,
+        };
+        match (inline_index, &mut self.data) {
+            (Some(Ok(index)), MapData::Inline(vec)) => Entry::Occupied(OccupiedEntry {
+                inner: OccupiedEntryInner::Inline { vec, index },
+            }),
+            (Some(Err(index)), data) => Entry::Vacant(VacantEntry {
+                inner: VacantEntryInner::Inline { data, key, index },
+            }),
+            (_, data) if data.is_heap_at_capacity_with(&key) => Entry::Occupied(OccupiedEntry {
+                inner: OccupiedEntryInner::HeapAtCapacity { data, key },
+            }),
+            (_, MapData::Heap(map)) => match map.entry(key) {
+                btree_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry {
+                    inner: OccupiedEntryInner::Heap(entry),
+                }),
+                btree_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
+                    inner: VacantEntryInner::Heap(entry),
+                }),
+            },
+            (None, MapData::Inline(_)) => unreachable!("inline data is always searched"),
+        }
+    }
+
+    /// Remove the key-value pair equivalent to `key` and return its value.
+    ///
+    /// If `key` is not present `None` is returned.
+    ///
+    /// If an existing key is removed that causes the size of the
+    /// `SmallSortedMap` to be equal to or below the inline capacity, all
+    /// remaining data after removal of the specified key-value pair is moved
+    /// inline.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(log n)
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_k, v)| v)
+    }
+
+    /// Remove the key-value pair equivalent to `key` and return it.
+    ///
+    /// If `key` is not present `None` is returned.
+    ///
+    /// If an existing key is removed that causes the size of the
+    /// `SmallSortedMap` to be equal to or below the inline capacity, all
+    /// remaining data after removal of the specified key-value pair is moved
+    /// inline.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(log n)
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let removed = match &mut self.data {
+            MapData::Inline(vec) => search(vec, key).ok().map(|index| vec.remove(index)),
+            MapData::Heap(map) => map.remove_entry(key),
+        };
+        self.move_inline_if_fits();
+        removed
+    }
+
+    /// Removes and returns the first key-value pair in the map, the key in
+    /// this pair is the minimum key in the map.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(log n)
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let removed = match &mut self.data {
+            MapData::Inline(vec) => (!vec.is_empty()).then(|| vec.remove(0)),
+            MapData::Heap(map) => map.pop_first(),
+        };
+        self.move_inline_if_fits();
+        removed
+    }
+
+    /// Removes and returns the last key-value pair in the map, the key in this
+    /// pair is the maximum key in the map.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(1)
+    ///  - heap: O(log n)
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let removed = match &mut self.data {
+            MapData::Inline(vec) => vec.pop(),
+            MapData::Heap(map) => map.pop_last(),
+        };
+        self.move_inline_if_fits();
+        removed
+    }
+
+    /// Returns an iterator over the key-values in the specified range of keys,
+    /// in sorted order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end` or if range `start == end` and both
+    /// bounds are `Excluded`, like [`BTreeMap::range()`].
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match &self.data {
+            MapData::Inline(vec) => {
+                let (start, end) = range_indices(vec, &range);
+                Range::Inline(vec[start..end].iter())
+            }
+            MapData::Heap(map) => Range::Heap(map.range(range)),
+        }
+    }
+
+    fn move_inline_if_fits(&mut self) {
+        if let MapData::Heap(map) = &mut self.data {
+            if map.len() <= C {
+                self.data = MapData::Inline(mem::take(map).into_iter().collect());
+            }
+        }
+    }
+}
+
+/// Binary searches the sorted `vec` for `key`.
+fn search<K, V, Q, const C: usize>(vec: &SmallVec<[(K, V); C]>, key: &Q) -> Result<usize, usize>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    vec.binary_search_by(|(k, _v)| k.borrow().cmp(key))
+}
+
+/// Computes the start (inclusive) and end (exclusive) indices of `range` in
+/// the sorted `vec`.
+fn range_indices<K, V, Q, R, const C: usize>(
+    vec: &SmallVec<[(K, V); C]>,
+    range: &R,
+) -> (usize, usize)
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Excluded(start), Bound::Excluded(end)) if start == end => {
+            panic!("range start and end are equal and excluded in SmallSortedMap")
+        }
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) if start > end => {
+            panic!("range start is greater than range end in SmallSortedMap")
+        }
+        _ => {}
+    }
+    let start = match range.start_bound() {
+        Bound::Included(start) => vec.partition_point(|(k, _v)| k.borrow() < start),
+        Bound::Excluded(start) => vec.partition_point(|(k, _v)| k.borrow() <= start),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => vec.partition_point(|(k, _v)| k.borrow() <= end),
+        Bound::Excluded(end) => vec.partition_point(|(k, _v)| k.borrow() < end),
+        Bound::Unbounded => vec.len(),
+    };
+    (start, end)
+}
+
+impl<K, V, const C: usize> Default for SmallSortedMap<K, V, C> {
+    fn default() -> Self {
+        Self {
+            data: MapData::Inline(SmallVec::new()),
+        }
+    }
+}
+
+impl<K, V, const C: usize> Hash for SmallSortedMap<K, V, C>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.iter().for_each(|(k, v)| {
+            k.hash(state);
+            v.hash(state);
+        });
+    }
+}
+
+impl<K, V, const C: usize> Eq for SmallSortedMap<K, V, C>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, const C: usize> PartialEq for SmallSortedMap<K, V, C>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V, Q, const C: usize> Index<&Q> for SmallSortedMap<K, V, C>
+where
+    K: Borrow<Q> + Ord,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("SmallSortedMap: key not found")
+    }
+}
+
+impl<K, V, const C: usize> Debug for SmallSortedMap<K, V, C>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, const C: usize> FromIterator<(K, V)> for SmallSortedMap<K, V, C>
+where
+    K: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        let iter = iterable.into_iter();
+        let (lower_bound, _) = iter.size_hint();
+        if lower_bound <= C {
+            let mut map = Self::default();
+            map.extend(iter);
+            map
+        } else {
+            Self::from_map(iter.collect())
+        }
+    }
+}
+
+impl<K, V, const C: usize> Extend<(K, V)> for SmallSortedMap<K, V, C>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(key, value)| {
+            self.insert(key, value);
+        });
+    }
+}
+
+impl<K, V, const C: usize> IntoIterator for SmallSortedMap<K, V, C> {
+    type Item = (K, V);
+
+    type IntoIter = IntoIter<K, V, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self.data {
+            MapData::Inline(vec) => IntoIter::Inline(vec.into_iter()),
+            MapData::Heap(map) => IntoIter::Heap(map.into_iter()),
+        }
+    }
+}
+
+impl<'a, K, V, const C: usize> IntoIterator for &'a SmallSortedMap<K, V, C> {
+    type IntoIter = Iter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, const C: usize> IntoIterator for &'a mut SmallSortedMap<K, V, C> {
+    type IntoIter = IterMut<'a, K, V>;
+    type Item = (&'a K, &'a mut V);
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub enum Iter<'a, K, V> {
+    Inline(std::slice::Iter<'a, (K, V)>),
+    Heap(btree_map::Iter<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Inline(iter) => iter.next().map(|(k, v)| (k, v)),
+            Iter::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    fn len(&self) -> usize {
+        match self {
+            Iter::Inline(iter) => iter.len(),
+            Iter::Heap(iter) => iter.len(),
+        }
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Inline(iter) => iter.next_back().map(|(k, v)| (k, v)),
+            Iter::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Inline(iter) => Self::Inline(iter.clone()),
+            Self::Heap(iter) => Self::Heap(iter.clone()),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[derive(Debug)]
+pub enum IterMut<'a, K, V> {
+    Inline(std::slice::IterMut<'a, (K, V)>),
+    Heap(btree_map::IterMut<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            IterMut::Inline(iter) => iter.next().map(|(k, v)| (&*k, v)),
+            IterMut::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {
+    fn len(&self) -> usize {
+        match self {
+            IterMut::Inline(iter) => iter.len(),
+            IterMut::Heap(iter) => iter.len(),
+        }
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            IterMut::Inline(iter) => iter.next_back().map(|(k, v)| (&*k, v)),
+            IterMut::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _v)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _v)| k)
+    }
+}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V> Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_k, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_k, v)| v)
+    }
+}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V: Debug> Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+pub enum Range<'a, K, V> {
+    Inline(std::slice::Iter<'a, (K, V)>),
+    Heap(btree_map::Range<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Range::Inline(iter) => iter.next().map(|(k, v)| (k, v)),
+            Range::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Range::Inline(iter) => iter.size_hint(),
+            Range::Heap(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Range<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Range::Inline(iter) => iter.next_back().map(|(k, v)| (k, v)),
+            Range::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<K, V> FusedIterator for Range<'_, K, V> {}
+
+impl<K, V> Clone for Range<'_, K, V> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Inline(iter) => Self::Inline(iter.clone()),
+            Self::Heap(iter) => Self::Heap(iter.clone()),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for Range<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[derive(Debug)]
+pub enum IntoIter<K, V, const C: usize> {
+    Inline(smallvec::IntoIter<[(K, V); C]>),
+    Heap(btree_map::IntoIter<K, V>),
+}
+
+impl<K, V, const C: usize> Iterator for IntoIter<K, V, C> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            IntoIter::Inline(iter) => iter.next(),
+            IntoIter::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<K, V, const C: usize> ExactSizeIterator for IntoIter<K, V, C> {
+    fn len(&self) -> usize {
+        match self {
+            IntoIter::Inline(iter) => iter.len(),
+            IntoIter::Heap(iter) => iter.len(),
+        }
+    }
+}
+
+impl<K, V, const C: usize> DoubleEndedIterator for IntoIter<K, V, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            IntoIter::Inline(iter) => iter.next_back(),
+            IntoIter::Heap(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<K, V, const C: usize> FusedIterator for IntoIter<K, V, C> {}
+
+/// A view into a single entry in a [`SmallSortedMap`], which may either be
+/// vacant or occupied.
+pub enum Entry<'a, K, V, const C: usize> {
+    Occupied(OccupiedEntry<'a, K, V, C>),
+    Vacant(VacantEntry<'a, K, V, C>),
+}
+
+impl<'a, K, V, const C: usize> Entry<'a, K, V, C>
+where
+    K: Ord,
+{
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Modifies the entry if it is occupied. Otherwise this is a no-op.
+    #[must_use]
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            x @ Entry::Vacant(_) => x,
+        }
+    }
+
+    /// Inserts the given default value in the entry if it is vacant and returns
+    /// a mutable reference to it. Otherwise a mutable reference to an
+    /// already existent value is returned.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Inserts the value returned by `default` in the entry if it is vacant
+    /// and returns a mutable reference to it. Otherwise a mutable reference to
+    /// an already existent value is returned.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<K, V, const C: usize> Debug for Entry<'_, K, V, C>
+where
+    K: Ord + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+            Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+        }
+    }
+}
+
+/// A view into an occupied entry in a [`SmallSortedMap`]. It is part of the
+/// [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, const C: usize> {
+    inner: OccupiedEntryInner<'a, K, V, C>,
+}
+
+enum OccupiedEntryInner<'a, K, V, const C: usize> {
+    Inline {
+        vec: &'a mut SmallVec<[(K, V); C]>,
+        index: usize,
+    },
+    Heap(btree_map::OccupiedEntry<'a, K, V>),
+    /// The map holds `C + 1` key-values on the heap, removing the entry moves
+    /// the remaining data inline.
+    HeapAtCapacity {
+        data: &'a mut MapData<K, V, C>,
+        key: K,
+    },
+}
+
+impl<'a, K, V, const C: usize> OccupiedEntry<'a, K, V, C>
+where
+    K: Ord,
+{
+    /// Returns a reference to the key in the entry.
+    #[must_use]
+    pub fn key(&self) -> &K {
+        match &self.inner {
+            OccupiedEntryInner::Inline { vec, index } => &vec[*index].0,
+            OccupiedEntryInner::Heap(entry) => entry.key(),
+            OccupiedEntryInner::HeapAtCapacity { data, key } => {
+                data.heap().get_key_value(key).expect("entry is occupied").0
+            }
+        }
+    }
+
+    /// Returns a reference to the value in the entry.
+    #[must_use]
+    pub fn get(&self) -> &V {
+        match &self.inner {
+            OccupiedEntryInner::Inline { vec, index } => &vec[*index].1,
+            OccupiedEntryInner::Heap(entry) => entry.get(),
+            OccupiedEntryInner::HeapAtCapacity { data, key } => {
+                data.heap().get(key).expect("entry is occupied")
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        match &mut self.inner {
+            OccupiedEntryInner::Inline { vec, index } => &mut vec[*index].1,
+            OccupiedEntryInner::Heap(entry) => entry.get_mut(),
+            OccupiedEntryInner::HeapAtCapacity { data, key } => {
+                data.heap_mut().get_mut(key).expect("entry is occupied")
+            }
+        }
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut V {
+        match self.inner {
+            OccupiedEntryInner::Inline { vec, index } => &mut vec[index].1,
+            OccupiedEntryInner::Heap(entry) => entry.into_mut(),
+            OccupiedEntryInner::HeapAtCapacity { data, key } => {
+                data.heap_mut().get_mut(&key).expect("entry is occupied")
+            }
+        }
+    }
+
+    /// Sets the value of the entry and returns the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map and returns its key-value pair.
+    ///
+    /// Like [`SmallSortedMap::remove_entry()`], all remaining data is moved
+    /// inline if the size of the map becomes equal to the inline capacity.
+    pub fn remove_entry(self) -> (K, V) {
+        match self.inner {
+            OccupiedEntryInner::Inline { vec, index } => vec.remove(index),
+            OccupiedEntryInner::Heap(entry) => entry.remove_entry(),
+            OccupiedEntryInner::HeapAtCapacity { data, key } => {
+                let map = data.heap_mut();
+                let removed = map.remove_entry(&key).expect("entry is occupied");
+                *data = MapData::Inline(mem::take(map).into_iter().collect());
+                removed
+            }
+        }
+    }
+
+    /// Removes the entry from the map and returns its value.
+    ///
+    /// Like [`SmallSortedMap::remove()`], all remaining data is moved inline
+    /// if the size of the map becomes equal to the inline capacity.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+}
+
+impl<K, V, const C: usize> Debug for OccupiedEntry<'_, K, V, C>
+where
+    K: Ord + Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in a [`SmallSortedMap`]. It is part of the
+/// [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, const C: usize> {
+    inner: VacantEntryInner<'a, K, V, C>,
+}
+
+enum VacantEntryInner<'a, K, V, const C: usize> {
+    Inline {
+        data: &'a mut MapData<K, V, C>,
+        key: K,
+        index: usize,
+    },
+    Heap(btree_map::VacantEntry<'a, K, V>),
+}
+
+impl<'a, K, V, const C: usize> VacantEntry<'a, K, V, C>
+where
+    K: Ord,
+{
+    /// Returns a reference to the key that would be used when inserting a
+    /// value through the `VacantEntry`.
+    pub fn key(&self) -> &K {
+        match &self.inner {
+            VacantEntryInner::Inline { key, .. } => key,
+            VacantEntryInner::Heap(entry) => entry.key(),
+        }
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        match self.inner {
+            VacantEntryInner::Inline { key, .. } => key,
+            VacantEntryInner::Heap(entry) => entry.into_key(),
+        }
+    }
+
+    /// Inserts the value into the map and returns a mutable reference to it.
+    ///
+    /// If this causes the size of the `SmallSortedMap` to exceed the inline
+    /// capacity, all existing data and the new key-value pair is moved to the
+    /// heap.
+    pub fn insert(self, value: V) -> &'a mut V {
+        match self.inner {
+            VacantEntryInner::Inline { data, key, index } => {
+                if let MapData::Inline(vec) = data {
+                    if vec.len() >= C {
+                        // Move to heap
+                        let map = mem::take(vec).into_iter().collect();
+                        *data = MapData::Heap(map);
+                    }
+                }
+                match data {
+                    MapData::Inline(vec) => {
+                        vec.insert(index, (key, value));
+                        &mut vec[index].1
+                    }
+                    MapData::Heap(map) => map.entry(key).or_insert(value),
+                }
+            }
+            VacantEntryInner::Heap(entry) => entry.insert(value),
+        }
+    }
+}
+
+impl<K, V, const C: usize> Debug for VacantEntry<'_, K, V, C>
+where
+    K: Ord + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V, const C: usize> serde::Serialize for SmallSortedMap<K, V, C>
+where
+    K: serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// Deserializes the key-value pairs in inline storage, the data is only moved
+/// to a `BTreeMap` when the number of distinct keys exceeds `C`. Like
+/// `BTreeMap`, a duplicate key overwrites the value of the earlier key.
+#[cfg(feature = "serde")]
+impl<'de, K, V, const C: usize> serde::Deserialize<'de> for SmallSortedMap<K, V, C>
+where
+    K: serde::Deserialize<'de> + Ord,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SmallSortedMapVisitor<K, V, const C: usize>(std::marker::PhantomData<(K, V)>);
+
+        impl<'de, K, V, const C: usize> serde::de::Visitor<'de> for SmallSortedMapVisitor<K, V, C>
+        where
+            K: serde::Deserialize<'de> + Ord,
+            V: serde::Deserialize<'de>,
+        {
+            type Value = SmallSortedMap<K, V, C>;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut map = SmallSortedMap::default();
+                while let Some((key, value)) = access.next_entry()? {
+                    map.insert(key, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(SmallSortedMapVisitor(std::marker::PhantomData))
+    }
+}
+
+/// Creates a [`SmallSortedMap`] containing the arguments.
+///
+/// ```
+/// use more_collections::smallsortedmap;
+/// use more_collections::SmallSortedMap;
+///
+/// let map: SmallSortedMap<_, _, 3> = smallsortedmap! {
+///     2 => "two",
+///     1 => "one",
+/// };
+/// assert_eq!(
+///     vec![(&1, &"one"), (&2, &"two")],
+///     map.iter().collect::<Vec<_>>()
+/// );
+/// ```
+#[macro_export]
+macro_rules! smallsortedmap {
+    // count helper: transform any expression into 1
+    (@one $x:expr) => (1usize);
+    ($($key:expr => $value:expr),*$(,)*) => ({
+        let count = 0usize $(+ $crate::smallsortedmap!(@one $key))*;
+        #[allow(unused_mut, reason = "false positive")]
+        let mut map = $crate::SmallSortedMap::new();
+        if count <= map.inline_capacity() {
+            $(map.insert($key, $value);)*
+            map
+        } else {
+            $crate::SmallSortedMap::from_map(std::collections::BTreeMap::from([$(($key, $value),)*]))
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_len_and_inline_capacity() {
+        let mut map: SmallSortedMap<usize, usize, 1> = SmallSortedMap::new();
+        assert_eq!(0, map.len());
+        assert!(map.is_empty());
+        map.insert(0, 1);
+        assert_eq!(1, map.len());
+        assert!(map.is_inline());
+        map.insert(1, 1);
+        assert_eq!(2, map.len());
+        assert!(!map.is_inline());
+
+        let map: SmallSortedMap<_, _, 10> = smallsortedmap! {
+            0 => 1,
+            4 => 9,
+            1 => 7,
+        };
+        assert_eq!(3, map.len());
+        assert_eq!(10, map.inline_capacity());
+    }
+
+    #[test]
+    fn smallsortedmap_macro_removes_duplicates() {
+        let map: SmallSortedMap<_, _, 10> = smallsortedmap! { 0 => 1, 0 => 2};
+        assert_eq!(1, map.len());
+        assert_eq!(Some(&2), map.get(&0));
+
+        let map: SmallSortedMap<_, _, 1> = smallsortedmap! { 0 => 1, 0 => 2};
+        assert_eq!(1, map.len());
+        assert!(map.is_inline());
+    }
+
+    #[test]
+    fn iter_iterates_in_sorted_order() {
+        fn test<const C: usize>(inline: bool) {
+            let mut map: SmallSortedMap<_, _, C> = smallsortedmap! {
+                1 => 7,
+                0 => 1,
+                4 => 9
+            };
+            assert_eq!(inline, map.is_inline());
+            assert_eq!(
+                vec![(&0, &1), (&1, &7), (&4, &9)],
+                map.iter().collect::<Vec<_>>(),
+                "iter() does not return values in the correct order"
+            );
+            assert_eq!(
+                vec![(&4, &9), (&1, &7), (&0, &1)],
+                map.iter().rev().collect::<Vec<_>>()
+            );
+            assert_eq!(3, map.iter().len());
+            assert_eq!(vec![&0, &1, &4], map.keys().collect::<Vec<_>>());
+            assert_eq!(vec![&1, &7, &9], map.values().collect::<Vec<_>>());
+            map.iter_mut().for_each(|(_k, v)| *v += 1);
+            assert_eq!(
+                vec![(0, 2), (1, 8), (4, 10)],
+                map.into_iter().collect::<Vec<_>>(),
+                "into_iter() does not return values in the correct order"
+            );
+        }
+        test::<1>(false);
+        test::<3>(true);
+    }
+
+    #[test]
+    fn insert_get_and_remove() {
+        fn test<const C: usize>(inline: bool) {
+            let mut map = SmallSortedMap::<&'static str, usize, C>::new();
+            assert_eq!(None, map.insert("c", 3));
+            assert_eq!(None, map.insert("a", 1));
+            assert_eq!(None, map.insert("b", 2));
+            assert_eq!(Some(2), map.insert("b", 22));
+            assert_eq!(inline, map.is_inline());
+            assert_eq!(3, map.len());
+
+            assert_eq!(Some(&22), map.get("b"));
+            assert_eq!(Some((&"a", &1)), map.get_key_value("a"));
+            assert_eq!(None, map.get("d"));
+            assert!(map.contains_key("c"));
+            assert!(!map.contains_key("d"));
+            *map.get_mut("c").unwrap() += 30;
+            assert_eq!(33, map["c"]);
+
+            assert_eq!(None, map.remove("d"));
+            assert_eq!(Some(22), map.remove("b"));
+            assert_eq!(Some(("a", 1)), map.remove_entry("a"));
+            assert_eq!(vec![(&"c", &33)], map.iter().collect::<Vec<_>>());
+            // a map of len <= 1 is always stored inline
+            assert!(map.is_inline());
+        }
+        test::<1>(false);
+        test::<3>(true);
+    }
+
+    #[test]
+    fn first_last_and_pop() {
+        fn test<const C: usize>(inline: bool) {
+            let mut map: SmallSortedMap<_, _, C> = smallsortedmap! {
+                3 => "c",
+                1 => "a",
+                2 => "b"
+            };
+            assert_eq!(inline, map.is_inline());
+            assert_eq!(Some((&1, &"a")), map.first_key_value());
+            assert_eq!(Some((&3, &"c")), map.last_key_value());
+            assert_eq!(Some((1, "a")), map.pop_first());
+            assert_eq!(Some((3, "c")), map.pop_last());
+            assert_eq!(Some((2, "b")), map.pop_last());
+            assert_eq!(None, map.pop_first());
+            assert_eq!(None, map.pop_last());
+            assert_eq!(None, map.first_key_value());
+            assert!(map.is_inline());
+        }
+        test::<1>(false);
+        test::<3>(true);
+    }
+
+    #[test]
+    fn range_returns_key_values_within_bounds() {
+        fn test<const C: usize>(inline: bool) {
+            let map: SmallSortedMap<_, _, C> = smallsortedmap! {
+                1 => 'a',
+                3 => 'c',
+                5 => 'e',
+                7 => 'g'
+            };
+            assert_eq!(inline, map.is_inline());
+            let keys = |range: Range<'_, i32, char>| range.map(|(k, _v)| *k).collect::<Vec<_>>();
+            assert_eq!(vec![3, 5], keys(map.range(2..7)));
+            assert_eq!(vec![3, 5, 7], keys(map.range(3..=7)));
+            assert_eq!(vec![1, 3], keys(map.range(..5)));
+            assert_eq!(vec![5, 7], keys(map.range(5..)));
+            assert_eq!(vec![1, 3, 5, 7], keys(map.range(..)));
+            assert_eq!(Vec::<i32>::new(), keys(map.range(8..)));
+            assert_eq!(Vec::<i32>::new(), keys(map.range(4..5)));
+            assert_eq!(
+                vec![5, 3],
+                keys(map.range((Bound::Excluded(1), Bound::Excluded(7))))
+                    .into_iter()
+                    .rev()
+                    .collect::<Vec<_>>()
+            );
+        }
+        test::<1>(false);
+        test::<4>(true);
+    }
+
+    #[test]
+    #[should_panic(expected = "range start is greater than range end")]
+    fn range_panics_when_start_is_greater_than_end() {
+        let map: SmallSortedMap<i32, i32, 4> = smallsortedmap! {1 => 1};
+        #[expect(clippy::reversed_empty_ranges, reason = "testing panic")]
+        let _ = map.range(3..1);
+    }
+
+    #[test]
+    fn entry_test() {
+        fn test<const C: usize>(inline: bool) {
+            let mut map: SmallSortedMap<&'static str, usize, C> =
+                smallsortedmap! {"2" => 222, "1" => 111};
+            assert_eq!(inline, map.is_inline());
+
+            // not existing -> insert new
+            assert_eq!(&777, map.entry("0").or_insert(777));
+            assert_eq!(Some(&777), map.get("0"));
+
+            // existing -> no-op
+            let ret = map.entry("1").or_insert(999);
+            assert_eq!(&111, ret);
+            *ret += 1;
+            assert_eq!(Some(&112), map.get("1"));
+
+            map.entry("2").and_modify(|v| *v += 1).or_default();
+            map.entry("3").and_modify(|v| *v += 1).or_default();
+            assert_eq!(
+                vec![(&"0", &777), (&"1", &112), (&"2", &223), (&"3", &0)],
+                map.iter().collect::<Vec<_>>()
+            );
+            assert_eq!(inline, map.is_inline());
+
+            match map.entry("1") {
+                Entry::Occupied(entry) => {
+                    assert_eq!(&"1", entry.key());
+                    assert_eq!(("1", 112), entry.remove_entry());
+                }
+                Entry::Vacant(_) => panic!("entry should be occupied"),
+            }
+            match map.entry("4") {
+                Entry::Occupied(_) => panic!("entry should be vacant"),
+                Entry::Vacant(entry) => assert_eq!("4", entry.into_key()),
+            }
+            assert_eq!(3, map.len());
+        }
+        test::<1>(false);
+        test::<4>(true);
+    }
+
+    #[test]
+    fn occupied_entry_remove_moves_data_inline_when_it_fits() {
+        let mut map: SmallSortedMap<_, _, 2> =
+            smallsortedmap! {1 => 'a', 2 => 'b', 3 => 'c', 4 => 'd'};
+        let Entry::Occupied(entry) = map.entry(4) else {
+            panic!("entry should be occupied");
+        };
+        assert_eq!('d', entry.remove());
+        assert!(!map.is_inline());
+
+        let Entry::Occupied(mut entry) = map.entry(2) else {
+            panic!("entry should be occupied");
+        };
+        assert_eq!(&2, entry.key());
+        assert_eq!('b', entry.insert('B'));
+        assert_eq!(&'B', entry.get());
+        assert_eq!((2, 'B'), entry.remove_entry());
+        assert!(map.is_inline());
+        assert_eq!(vec![(&1, &'a'), (&3, &'c')], map.iter().collect::<Vec<_>>());
+
+        map.insert(2, 'b');
+        *map.entry(1).or_insert('x') = 'A';
+        assert_eq!(Some(&'A'), map.get(&1));
+        assert!(!map.is_inline());
+    }
+
+    #[test]
+    fn equality_and_debug() {
+        let heap: SmallSortedMap<_, _, 1> = smallsortedmap! {2 => 3, 1 => 2};
+        let mut inline: SmallSortedMap<_, _, 1> = SmallSortedMap::new();
+        inline.insert(1, 2);
+        assert_ne!(heap, inline);
+        inline.insert(2, 3);
+        assert_eq!(heap, inline);
+        assert_eq!("{1: 2, 2: 3}", format!("{heap:?}"));
+        assert_eq!("[1, 2]", format!("{:?}", heap.keys()));
+    }
+
+    #[test]
+    fn from_iterator_and_from_map() {
+        fn test<const C: usize>(inline: bool) {
+            let data = vec![("hi", 2), ("hello", 5), ("hamburg", 7), ("berlin", 6)];
+            let map = SmallSortedMap::<&'static str, usize, C>::from_iter(data.clone());
+            assert_eq!(inline, map.is_inline());
+            let mut sorted = data.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, map.clone().into_iter().collect::<Vec<_>>());
+
+            let from_map = SmallSortedMap::<_, _, C>::from_map(data.into_iter().collect());
+            assert_eq!(inline, from_map.is_inline());
+            assert_eq!(map, from_map);
+        }
+        test::<1>(false);
+        test::<4>(true);
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn test_serde() {
+            let input: SmallSortedMap<usize, usize, 2> = smallsortedmap! {2 => 20, 0 => 0};
+            let serialized_str = serde_json::to_string(&input).unwrap();
+            assert_eq!(r#"{"0":0,"2":20}"#, serialized_str);
+
+            let deserialized =
+                serde_json::from_str::<SmallSortedMap<usize, usize, 2>>(r#"{"2":20,"0":0,"2":2}"#)
+                    .unwrap();
+            assert_eq!(smallsortedmap! {0 => 0, 2 => 2}, deserialized);
+            assert!(deserialized.is_inline());
+
+            let deserialized =
+                serde_json::from_str::<SmallSortedMap<usize, usize, 2>>(r#"{"2":2,"0":0,"1":1}"#)
+                    .unwrap();
+            assert!(!deserialized.is_inline());
+            assert_eq!(vec![&0, &1, &2], deserialized.keys().collect::<Vec<_>>());
+        }
+    }
+}
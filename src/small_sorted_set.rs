@@ -0,0 +1,897 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::hash::Hash;
+use std::iter::FusedIterator;
+use std::iter::Peekable;
+use std::ops::RangeBounds;
+
+use crate::small_sorted_map;
+use crate::SmallSortedMap;
+
+/// A sorted set-like container that can store a specified number of elements
+/// inline.
+///
+/// `SmallSortedSet` shares most of its API with, and behaves like,
+/// [`BTreeSet`](std::collections::BTreeSet). It can store a limited amount of
+/// data inline in a sorted [`SmallVec`](smallvec::SmallVec). If the data
+/// exceeds the limit `C`, `SmallSortedSet` will move _all_ its data over to the
+/// heap in the form of a `BTreeSet`. For performance reasons, transitions
+/// between heap and inline storage should generally be avoided.
+///
+/// Since values are always sorted, set operations such as
+/// [`union()`](Self::union) and [`intersection()`](Self::intersection) merge
+/// both sets in linear time.
+///
+/// # Example
+///
+/// ```
+/// use more_collections::SmallSortedSet;
+///
+/// let mut set = SmallSortedSet::<usize, 3>::new();
+/// // The set can hold up to three items inline
+/// set.insert(2);
+/// set.insert(0);
+/// set.insert(1);
+/// assert_eq!(3, set.len());
+/// assert!(set.is_inline());
+/// // Values are always sorted
+/// assert_eq!(vec![&0, &1, &2], set.iter().collect::<Vec<_>>());
+///
+/// // Adding the fourth element will move the set to the heap
+/// set.insert(3);
+/// assert_eq!(4, set.len());
+/// assert!(!set.is_inline());
+/// ```
+///
+/// # Serialization and deserialization
+///
+/// An optional feature that can be unlocked with the `serde` feature.
+/// `SmallSortedSet`s are serialized as a sorted sequence, like `BTreeSet`.
+/// Deserialization stores the data inline and only moves it to the heap when it
+/// exceeds `C`.
+#[derive(Clone)]
+pub struct SmallSortedSet<T, const C: usize> {
+    data: SmallSortedMap<T, (), C>,
+}
+
+impl<T, const C: usize> SmallSortedSet<T, C> {
+    /// Create a new set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            data: SmallSortedMap::new(),
+        }
+    }
+
+    pub const fn from_keys(map: SmallSortedMap<T, (), C>) -> Self {
+        Self { data: map }
+    }
+
+    /// The number of values stored in the set.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The memory capacity that will be allocated inline. If the number of
+    /// values exceeds the inline capacity, the set will move to the heap.
+    pub const fn inline_capacity(&self) -> usize {
+        self.data.inline_capacity()
+    }
+
+    /// Is the data contained by this set stored inline (`true`) or on the heap
+    /// (`false`).
+    pub const fn is_inline(&self) -> bool {
+        self.data.is_inline()
+    }
+
+    /// Returns an iterator over the values in sorted order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.data.iter(),
+        }
+    }
+}
+
+impl<T, const C: usize> SmallSortedSet<T, C>
+where
+    T: Ord,
+{
+    /// Adds a value to the set.
+    ///
+    /// Returns `true` if the value was not yet present in the set. If an equal
+    /// value already exists, the set is not modified and `false` is returned.
+    ///
+    /// If a new value is added that causes the size of the `SmallSortedSet` to
+    /// exceed the inline capacity, all existing data and the new value is
+    /// moved to the heap.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(log n)
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.data.entry(value) {
+            small_sorted_map::Entry::Occupied(_) => false,
+            small_sorted_map::Entry::Vacant(entry) => {
+                entry.insert(());
+                true
+            }
+        }
+    }
+
+    /// Returns `true` if the set contains a value equal to `value`.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.data.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set that is equal to `value`,
+    /// if any.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(log n)
+    ///  - heap: O(log n)
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.data.get_key_value(value).map(|(t, ())| t)
+    }
+
+    /// Removes the value that is equal to `value` from the set. Returns
+    /// whether such a value was present.
+    ///
+    /// If a value is removed that causes the size of the `SmallSortedSet` to be
+    /// equal to or below the inline capacity, all remaining data is moved
+    /// inline.
+    ///
+    /// Computational complexity:
+    ///  - inline: O(n)
+    ///  - heap: O(log n)
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.data.remove(value).is_some()
+    }
+
+    /// Returns the minimum value in the set, if any.
+    pub fn first(&self) -> Option<&T> {
+        self.data.first_key_value().map(|(t, ())| t)
+    }
+
+    /// Returns the maximum value in the set, if any.
+    pub fn last(&self) -> Option<&T> {
+        self.data.last_key_value().map(|(t, ())| t)
+    }
+
+    /// Removes and returns the minimum value in the set, if any.
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.data.pop_first().map(|(t, ())| t)
+    }
+
+    /// Removes and returns the maximum value in the set, if any.
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.data.pop_last().map(|(t, ())| t)
+    }
+
+    /// Returns an iterator over the values in the specified range, in sorted
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end` or if range `start == end` and both
+    /// bounds are `Excluded`, like
+    /// [`BTreeSet::range()`](std::collections::BTreeSet::range).
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.data.range(range),
+        }
+    }
+
+    /// Return an iterator over the values that are in `self` but not `other`,
+    /// in sorted order.
+    ///
+    /// Computational complexity: O(n + m)
+    pub fn difference<'a, const C2: usize>(
+        &'a self,
+        other: &'a SmallSortedSet<T, C2>,
+    ) -> Difference<'a, T> {
+        Difference {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+
+    /// Return an iterator over the values that are in `self` or `other`, but
+    /// not in both, in sorted order.
+    ///
+    /// Computational complexity: O(n + m)
+    pub fn symmetric_difference<'a, const C2: usize>(
+        &'a self,
+        other: &'a SmallSortedSet<T, C2>,
+    ) -> SymmetricDifference<'a, T> {
+        SymmetricDifference {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+
+    /// Return an iterator over the values that are in both `self` and `other`,
+    /// in sorted order.
+    ///
+    /// Computational complexity: O(n + m)
+    pub fn intersection<'a, const C2: usize>(
+        &'a self,
+        other: &'a SmallSortedSet<T, C2>,
+    ) -> Intersection<'a, T> {
+        Intersection {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+
+    /// Return an iterator over all values that are in `self` or `other`, in
+    /// sorted order.
+    ///
+    /// Computational complexity: O(n + m)
+    pub fn union<'a, const C2: usize>(&'a self, other: &'a SmallSortedSet<T, C2>) -> Union<'a, T> {
+        Union {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+        }
+    }
+}
+
+impl<T, const C: usize> Default for SmallSortedSet<T, C> {
+    fn default() -> Self {
+        Self {
+            data: SmallSortedMap::default(),
+        }
+    }
+}
+
+impl<T, const C: usize> Hash for SmallSortedSet<T, C>
+where
+    T: Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.data.hash(state);
+    }
+}
+
+impl<T, const C: usize> Eq for SmallSortedSet<T, C> where T: Eq {}
+
+impl<T, const C: usize> PartialEq for SmallSortedSet<T, C>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<T, const C: usize> Debug for SmallSortedSet<T, C>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const C: usize> FromIterator<T> for SmallSortedSet<T, C>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            data: iter.into_iter().map(|t| (t, ())).collect(),
+        }
+    }
+}
+
+impl<T, const C: usize> Extend<T> for SmallSortedSet<T, C>
+where
+    T: Ord,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|t| {
+            self.insert(t);
+        });
+    }
+}
+
+impl<T, const C: usize> IntoIterator for SmallSortedSet<T, C> {
+    type Item = T;
+
+    type IntoIter = IntoIter<T, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.data.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, const C: usize> IntoIterator for &'a SmallSortedSet<T, C> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: small_sorted_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(t, ())| t)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(t, ())| t)
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct IntoIter<T, const C: usize> {
+    inner: small_sorted_map::IntoIter<T, (), C>,
+}
+
+impl<T, const C: usize> Iterator for IntoIter<T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(t, ())| t)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, const C: usize> ExactSizeIterator for IntoIter<T, C> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T, const C: usize> DoubleEndedIterator for IntoIter<T, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(t, ())| t)
+    }
+}
+
+impl<T, const C: usize> FusedIterator for IntoIter<T, C> {}
+
+pub struct Range<'a, T> {
+    inner: small_sorted_map::Range<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(t, ())| t)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Range<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(t, ())| t)
+    }
+}
+
+impl<T> FusedIterator for Range<'_, T> {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for Range<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Range<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values of a `SmallSortedSet` that are not in another
+/// set, in sorted order.
+///
+/// This `struct` is created by the [`difference`](SmallSortedSet::difference)
+/// method on [`SmallSortedSet`].
+pub struct Difference<'a, T> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Difference<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let a: &T = self.a.peek()?;
+            match self.b.peek().map(|b| a.cmp(b)) {
+                None | Some(Ordering::Less) => return self.a.next(),
+                Some(Ordering::Equal) => {
+                    self.a.next();
+                    self.b.next();
+                }
+                Some(Ordering::Greater) => {
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let a_len = self.a.len();
+        (a_len.saturating_sub(self.b.len()), Some(a_len))
+    }
+}
+
+impl<T> FusedIterator for Difference<'_, T> where T: Ord {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for Difference<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl<T> Debug for Difference<'_, T>
+where
+    T: Debug + Ord,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values that are in one of two `SmallSortedSet`s but
+/// not in both, in sorted order.
+///
+/// This `struct` is created by the
+/// [`symmetric_difference`](SmallSortedSet::symmetric_difference) method on
+/// [`SmallSortedSet`].
+pub struct SymmetricDifference<'a, T> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for SymmetricDifference<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match (self.a.peek(), self.b.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => return self.a.next(),
+                (None, Some(_)) => return self.b.next(),
+                (Some(a), Some(b)) => match a.cmp(b) {
+                    Ordering::Less => return self.a.next(),
+                    Ordering::Greater => return self.b.next(),
+                    Ordering::Equal => {
+                        self.a.next();
+                        self.b.next();
+                    }
+                },
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len() + self.b.len()))
+    }
+}
+
+impl<T> FusedIterator for SymmetricDifference<'_, T> where T: Ord {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for SymmetricDifference<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl<T> Debug for SymmetricDifference<'_, T>
+where
+    T: Debug + Ord,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values that are in both of two `SmallSortedSet`s, in
+/// sorted order.
+///
+/// This `struct` is created by the
+/// [`intersection`](SmallSortedSet::intersection) method on
+/// [`SmallSortedSet`].
+pub struct Intersection<'a, T> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Intersection<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let a: &T = self.a.peek()?;
+            let b: &T = self.b.peek()?;
+            match a.cmp(b) {
+                Ordering::Less => {
+                    self.a.next();
+                }
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len().min(self.b.len())))
+    }
+}
+
+impl<T> FusedIterator for Intersection<'_, T> where T: Ord {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for Intersection<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl<T> Debug for Intersection<'_, T>
+where
+    T: Debug + Ord,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values that are in either of two `SmallSortedSet`s,
+/// in sorted order and without duplicates.
+///
+/// This `struct` is created by the [`union`](SmallSortedSet::union) method on
+/// [`SmallSortedSet`].
+pub struct Union<'a, T> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Union<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.peek(), self.b.peek()) {
+            (None, None) => None,
+            (Some(_), None) => self.a.next(),
+            (None, Some(_)) => self.b.next(),
+            (Some(a), Some(b)) => match a.cmp(b) {
+                Ordering::Less => self.a.next(),
+                Ordering::Greater => self.b.next(),
+                Ordering::Equal => {
+                    self.b.next();
+                    self.a.next()
+                }
+            },
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_len, b_len) = (self.a.len(), self.b.len());
+        (a_len.max(b_len), Some(a_len + b_len))
+    }
+}
+
+impl<T> FusedIterator for Union<'_, T> where T: Ord {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<T> Clone for Union<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+}
+
+impl<T> Debug for Union<'_, T>
+where
+    T: Debug + Ord,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const C: usize> serde::Serialize for SmallSortedSet<T, C>
+where
+    T: serde::Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserializes the values in inline storage, the data is only moved to the
+/// heap when the number of distinct values exceeds `C`. Duplicate values are
+/// ignored.
+#[cfg(feature = "serde")]
+impl<'de, T, const C: usize> serde::Deserialize<'de> for SmallSortedSet<T, C>
+where
+    T: serde::Deserialize<'de> + Ord,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SmallSortedSetVisitor<T, const C: usize>(std::marker::PhantomData<T>);
+
+        impl<'de, T, const C: usize> serde::de::Visitor<'de> for SmallSortedSetVisitor<T, C>
+        where
+            T: serde::Deserialize<'de> + Ord,
+        {
+            type Value = SmallSortedSet<T, C>;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut set = SmallSortedSet::default();
+                while let Some(value) = access.next_element()? {
+                    set.insert(value);
+                }
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_seq(SmallSortedSetVisitor(std::marker::PhantomData))
+    }
+}
+
+/// Create a [`SmallSortedSet`] with the specified values.
+#[macro_export]
+macro_rules! smallsortedset {
+    ($($x:expr),*$(,)*) => ({
+        let map = $crate::smallsortedmap!( $($x => (),)* );
+        $crate::SmallSortedSet::from_keys(map)
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_contains_and_remove() {
+        fn test<const C: usize>(inline: bool) {
+            let mut set = SmallSortedSet::<usize, C>::new();
+            assert!(set.insert(3));
+            assert!(set.insert(1));
+            assert!(!set.insert(3));
+            assert!(set.insert(2));
+            assert_eq!(inline, set.is_inline());
+            assert_eq!(3, set.len());
+            assert!(set.contains(&2));
+            assert!(!set.contains(&4));
+            assert_eq!(Some(&1), set.get(&1));
+            assert_eq!(vec![&1, &2, &3], set.iter().collect::<Vec<_>>());
+            assert_eq!(
+                vec![3, 2, 1],
+                set.clone().into_iter().rev().collect::<Vec<_>>()
+            );
+
+            assert!(set.remove(&2));
+            assert!(!set.remove(&2));
+            assert_eq!(vec![&1, &3], set.iter().collect::<Vec<_>>());
+        }
+        test::<1>(false);
+        test::<3>(true);
+    }
+
+    #[test]
+    fn first_last_and_pop() {
+        fn test<const C: usize>(inline: bool) {
+            let mut set: SmallSortedSet<_, C> = smallsortedset! {5, 1, 3};
+            assert_eq!(inline, set.is_inline());
+            assert_eq!(Some(&1), set.first());
+            assert_eq!(Some(&5), set.last());
+            assert_eq!(Some(1), set.pop_first());
+            assert_eq!(Some(5), set.pop_last());
+            assert_eq!(Some(3), set.pop_first());
+            assert_eq!(None, set.pop_first());
+            assert_eq!(None, set.pop_last());
+            assert_eq!(None, set.first());
+            assert!(set.is_empty());
+        }
+        test::<1>(false);
+        test::<3>(true);
+    }
+
+    #[test]
+    fn range_returns_values_within_bounds() {
+        fn test<const C: usize>(inline: bool) {
+            let set: SmallSortedSet<_, C> = smallsortedset! {1, 3, 5, 7};
+            assert_eq!(inline, set.is_inline());
+            assert_eq!(vec![&3, &5], set.range(2..7).collect::<Vec<_>>());
+            assert_eq!(vec![&7, &5], set.range(5..).rev().collect::<Vec<_>>());
+            assert_eq!(vec![&1], set.range(..=1).collect::<Vec<_>>());
+        }
+        test::<1>(false);
+        test::<4>(true);
+    }
+
+    #[test]
+    fn set_operations_merge_sorted_values() {
+        fn test<const C1: usize, const C2: usize>() {
+            let a: SmallSortedSet<_, C1> = smallsortedset! {1, 3, 5, 7, 9};
+            let b: SmallSortedSet<_, C2> = smallsortedset! {0, 3, 4, 9, 10};
+            assert_eq!(vec![&1, &5, &7], a.difference(&b).collect::<Vec<_>>());
+            assert_eq!(vec![&0, &4, &10], b.difference(&a).collect::<Vec<_>>());
+            assert_eq!(
+                vec![&0, &1, &4, &5, &7, &10],
+                a.symmetric_difference(&b).collect::<Vec<_>>()
+            );
+            assert_eq!(vec![&3, &9], a.intersection(&b).collect::<Vec<_>>());
+            assert_eq!(
+                vec![&0, &1, &3, &4, &5, &7, &9, &10],
+                a.union(&b).collect::<Vec<_>>()
+            );
+
+            let empty = SmallSortedSet::<i32, C2>::new();
+            assert_eq!(5, a.difference(&empty).count());
+            assert_eq!(5, a.union(&empty).count());
+            assert_eq!(0, a.intersection(&empty).count());
+            assert_eq!(5, empty.symmetric_difference(&a).count());
+        }
+        test::<1, 1>();
+        test::<1, 8>();
+        test::<8, 8>();
+    }
+
+    #[test]
+    fn set_operation_size_hints_are_bounds() {
+        let a: SmallSortedSet<_, 4> = smallsortedset! {1, 2, 3};
+        let b: SmallSortedSet<_, 4> = smallsortedset! {2};
+        assert_eq!((2, Some(3)), a.difference(&b).size_hint());
+        assert_eq!((0, Some(1)), a.intersection(&b).size_hint());
+        assert_eq!((3, Some(4)), a.union(&b).size_hint());
+        assert_eq!((0, Some(4)), a.symmetric_difference(&b).size_hint());
+    }
+
+    #[test]
+    fn equality_debug_and_collect() {
+        let heap: SmallSortedSet<_, 1> = [2, 1, 2].into_iter().collect();
+        let mut inline: SmallSortedSet<_, 1> = SmallSortedSet::new();
+        inline.insert(1);
+        assert_ne!(heap, inline);
+        inline.extend([2]);
+        assert_eq!(heap, inline);
+        assert_eq!("{1, 2}", format!("{heap:?}"));
+        let other: SmallSortedSet<_, 4> = smallsortedset! {2, 3};
+        assert_eq!("[1, 3]", format!("{:?}", heap.symmetric_difference(&other)));
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn test_serde() {
+            let input: SmallSortedSet<usize, 2> = smallsortedset! {2, 0};
+            let serialized_str = serde_json::to_string(&input).unwrap();
+            assert_eq!("[0,2]", serialized_str);
+
+            let deserialized = serde_json::from_str::<SmallSortedSet<usize, 2>>("[2,0,2]").unwrap();
+            assert_eq!(input, deserialized);
+            assert!(deserialized.is_inline());
+
+            let deserialized = serde_json::from_str::<SmallSortedSet<usize, 2>>("[2,0,1]").unwrap();
+            assert!(!deserialized.is_inline());
+            assert_eq!(vec![&0, &1, &2], deserialized.iter().collect::<Vec<_>>());
+        }
+    }
+}
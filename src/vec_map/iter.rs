@@ -40,7 +40,6 @@ impl<K: IndexKey, V> IntoIterator for VecMap<K, V> {
 
 /// An iterator that iterates over the key-value pairs following the key
 /// ordering.
-#[derive(Clone)]
 pub struct Iter<'a, K, V> {
     pub(super) inner: Enumerate<core::slice::Iter<'a, Option<V>>>,
     pub(super) len: usize,
@@ -88,15 +87,20 @@ impl<K: IndexKey, V> ExactSizeIterator for Iter<'_, K, V> {
 
 impl<K: IndexKey, V> FusedIterator for Iter<'_, K, V> {}
 
-impl<'a, K: IndexKey + fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'a, K, V> {
+impl<K: IndexKey + fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // TODO why can't we use self.clone()
-        let iter: Iter<'a, K, V> = Iter {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+// `#[derive(Clone)]` would require `K: Clone` and `V: Clone`
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
             inner: self.inner.clone(),
             len: self.len,
             _marker: PhantomData,
-        };
-        f.debug_list().entries(iter).finish()
+        }
     }
 }
 
@@ -201,7 +205,6 @@ impl<K: IndexKey, V> ExactSizeIterator for IntoIter<K, V> {
 impl<K: IndexKey, V> FusedIterator for IntoIter<K, V> {}
 
 /// An iterator over the keys following the key natural order.
-#[derive(Clone)]
 pub struct Keys<'a, K, V> {
     pub(super) inner: Enumerate<core::slice::Iter<'a, Option<V>>>,
     pub(super) len: usize,
@@ -249,19 +252,24 @@ impl<K: IndexKey, V> ExactSizeIterator for Keys<'_, K, V> {
 
 impl<K: IndexKey, V> FusedIterator for Keys<'_, K, V> {}
 
-impl<'a, K, V> fmt::Debug for Keys<'a, K, V>
+impl<K, V> fmt::Debug for Keys<'_, K, V>
 where
     K: IndexKey + fmt::Debug,
     V: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // TODO why can't we use self.clone()
-        let iter: Keys<'a, K, V> = Keys {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+// `#[derive(Clone)]` would require `K: Clone` and `V: Clone`
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
             inner: self.inner.clone(),
             len: self.len,
             _marker: PhantomData,
-        };
-        f.debug_list().entries(iter).finish()
+        }
     }
 }
 
@@ -38,6 +38,16 @@ where
     }
 }
 
+/// An [`IndexKey`] with a fixed number of distinct keys, typically a fieldless
+/// enum.
+///
+/// All keys must map to an index in the range `0..VARIANT_COUNT`. This allows
+/// collections to allocate storage for every possible key upfront.
+pub trait EnumKey: IndexKey {
+    /// The number of distinct keys (variants) of this type.
+    const VARIANT_COUNT: usize;
+}
+
 /// A [`Vec`]-backed map.
 ///
 /// It has faster random access performance and slower iteration speed compared
@@ -0,0 +1,128 @@
+#![cfg(test)]
+
+use more_collections::GroupByExt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Sample {
+    host: &'static str,
+    latency: u32,
+}
+
+fn samples() -> Vec<Sample> {
+    vec![
+        Sample {
+            host: "b",
+            latency: 10,
+        },
+        Sample {
+            host: "a",
+            latency: 30,
+        },
+        Sample {
+            host: "b",
+            latency: 20,
+        },
+        Sample {
+            host: "b",
+            latency: 10,
+        },
+    ]
+}
+
+#[test]
+fn into_hash_vec_multimap_by() {
+    let map = samples()
+        .into_iter()
+        .into_hash_vec_multimap_by(|sample| sample.host);
+    assert_eq!(4, map.len());
+    assert_eq!(2, map.keys_len());
+    assert_eq!(
+        vec![10, 20, 10],
+        map.get("b")
+            .unwrap()
+            .iter()
+            .map(|sample| sample.latency)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn into_hash_set_multimap_by() {
+    let map = samples()
+        .into_iter()
+        .into_hash_set_multimap_by(|sample| sample.host, |sample| sample.latency);
+    assert_eq!(3, map.len());
+    assert_eq!(&maplit::hashset! {10, 20}, &map["b"]);
+}
+
+#[test]
+fn into_index_multimaps_by_keep_first_occurrence_order() {
+    let map = samples()
+        .into_iter()
+        .into_index_vec_multimap_by(|sample| sample.host);
+    assert_eq!(vec![&"b", &"a"], map.keys().collect::<Vec<_>>());
+
+    let map = samples()
+        .into_iter()
+        .into_index_set_multimap_by(|sample| sample.host, |sample| sample.latency);
+    assert_eq!(
+        vec![(&"b", &10), (&"b", &20), (&"a", &30)],
+        map.iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn into_btree_multimaps_by() {
+    let map = samples()
+        .into_iter()
+        .into_btree_vec_multimap_by(|sample| sample.host);
+    assert_eq!(vec![&"a", &"b"], map.keys().collect::<Vec<_>>());
+    assert_eq!(4, map.len());
+
+    let map = samples()
+        .into_iter()
+        .into_btree_set_multimap_by(|sample| sample.host, |sample| sample.latency);
+    assert_eq!(
+        vec![(&"a", &30), (&"b", &10), (&"b", &20)],
+        map.iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn into_vec_multimaps_by() {
+    let map = (0..10usize).into_vec_vec_multimap_by(|value| value % 3);
+    assert_eq!(Some(&vec![0, 3, 6, 9]), map.get(0));
+    assert_eq!(3, map.keys_len());
+
+    let map = (0..10usize).into_vec_set_multimap_by(|value| value % 3, |value| value / 6);
+    assert_eq!(&maplit::hashset! {0, 1}, &map[0]);
+    assert_eq!(6, map.len());
+}
+
+#[test]
+fn into_multisets_by() {
+    let set = samples()
+        .into_iter()
+        .into_hash_multiset_by(|sample| sample.host);
+    assert_eq!(3, set.count("b"));
+    assert_eq!(1, set.count("a"));
+
+    let set = samples()
+        .into_iter()
+        .into_index_multiset_by(|sample| sample.latency);
+    assert_eq!(
+        vec![(&10, 2), (&30, 1), (&20, 1)],
+        set.iter_counts().collect::<Vec<_>>()
+    );
+
+    let set = samples()
+        .into_iter()
+        .into_btree_multiset_by(|sample| sample.latency);
+    assert_eq!(Some(&20), set.nth_smallest(2));
+
+    let set = (0..10usize).into_vec_multiset_by(|value| value % 3);
+    assert_eq!(
+        vec![(0, 4), (1, 3), (2, 3)],
+        set.iter_counts().collect::<Vec<_>>()
+    );
+}
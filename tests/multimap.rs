@@ -15,11 +15,224 @@ macro_rules! set_multimap_tests {
             assert_eq!(1, map.len());
             assert!(map.contains(&0, &"A".to_string()));
         }
+
+        #[test]
+        fn deserialize_collapses_duplicate_values() {
+            let map: $type<u8, u8> = serde_json::from_str(r#"{"0": [1, 1, 2]}"#).unwrap();
+            assert_eq!(2, map.len());
+            assert!(map.contains(&0, &1));
+            assert!(map.contains(&0, &2));
+        }
+
+        #[test]
+        fn try_map_values_in_place_merges_equal_values() {
+            let mut map = $type::new();
+            map.insert(0, " a".to_string());
+            map.insert(0, "a".to_string());
+            map.insert(0, "b ".to_string());
+            map.insert(1, "c".to_string());
+
+            let result: Result<(), ()> = map.try_map_values_in_place(|_key, value| {
+                *value = value.trim().to_string();
+                Ok(())
+            });
+            assert_eq!(Ok(()), result);
+            assert_eq!(3, map.len());
+            assert!(map.contains(&0, &"a".to_string()));
+            assert!(map.contains(&0, &"b".to_string()));
+            assert!(map.contains(&1, &"c".to_string()));
+        }
+
+        #[test]
+        fn try_map_values_in_place_stops_at_first_error() {
+            let mut map = $type::new();
+            map.insert(0, "a".to_string());
+            map.insert(0, "b".to_string());
+            map.insert(1, "c".to_string());
+
+            let result = map.try_map_values_in_place(|_key, value| {
+                value.push('!');
+                Err(value.clone())
+            });
+            let changed = result.unwrap_err();
+            assert_eq!(3, map.len());
+            assert_eq!(
+                vec![&changed],
+                map.values()
+                    .filter(|value| value.ends_with('!'))
+                    .collect::<Vec<_>>()
+            );
+        }
+    };
+}
+
+macro_rules! list_multimap_tests {
+    ($multimap_macro:tt) => {
+        #[test]
+        fn equality_test_counts_duplicate_values() {
+            let a = $multimap_macro! {0 => { 1, 1, 2 }};
+            let b = $multimap_macro! {0 => { 1, 2, 2 }};
+            assert_ne!(a, b);
+            assert!(!a.eq_ordered(&b));
+            assert!(!a.eq_unordered(&b));
+        }
+
+        #[test]
+        fn eq_ordered_takes_value_order_into_account() {
+            let a = $multimap_macro! {
+                0 => { 1, 2, 1 },
+                1 => { 3 }
+            };
+            let b = $multimap_macro! {
+                1 => { 3 },
+                0 => { 2, 1, 1 }
+            };
+            assert_eq!(a, b);
+            assert!(a.eq_unordered(&b));
+            assert!(!a.eq_ordered(&b));
+            assert!(a.eq_ordered(&a.clone()));
+        }
+
+        #[test]
+        fn iter_mut_and_values_mut_modify_values_in_place() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                1 => { 3 }
+            };
+            let mut iter = map.iter_mut();
+            assert_eq!(3, iter.len());
+            assert!(iter.next().is_some());
+            assert_eq!(2, iter.len());
+
+            for (key, value) in &mut map {
+                *value += *key * 10;
+            }
+            map.values_mut().for_each(|value| *value *= 2);
+            assert_eq!(3, map.len());
+            assert!(map.contains(&0, &2));
+            assert!(map.contains(&0, &4));
+            assert!(map.contains(&1, &26));
+        }
+    };
+}
+
+macro_rules! set_algebra_multimap_tests {
+    ($type:tt, $multimap_macro:tt) => {
+        fn pairs<'a>(
+            iter: impl Iterator<Item = (&'a i32, &'a i32)>,
+        ) -> std::collections::HashSet<(i32, i32)> {
+            iter.map(|(k, v)| (*k, *v)).collect()
+        }
+
+        fn left_and_right() -> ($type<i32, i32>, $type<i32, i32>) {
+            let left = $multimap_macro! {
+                0 => { 1, 2 },
+                1 => { 3 }
+            };
+            let right = $multimap_macro! {
+                0 => { 2, 4 },
+                2 => { 5 }
+            };
+            (left, right)
+        }
+
+        #[test]
+        fn lazy_set_operations_visit_expected_pairs() {
+            let (left, right) = left_and_right();
+            assert_eq!(
+                maplit::hashset! {(0, 1), (1, 3)},
+                pairs(left.difference(&right))
+            );
+            assert_eq!(maplit::hashset! {(0, 2)}, pairs(left.intersection(&right)));
+            assert_eq!(
+                maplit::hashset! {(0, 1), (1, 3), (0, 4), (2, 5)},
+                pairs(left.symmetric_difference(&right))
+            );
+            let union = left.union(&right).collect::<Vec<_>>();
+            assert_eq!(5, union.len());
+            assert_eq!(
+                maplit::hashset! {(0, 1), (0, 2), (1, 3), (0, 4), (2, 5)},
+                pairs(union.into_iter())
+            );
+        }
+
+        #[test]
+        fn set_operators_match_lazy_set_operations() {
+            let (left, right) = left_and_right();
+            assert_eq!(left.union(&right).count(), (&left | &right).len());
+            assert_eq!($multimap_macro! { 0 => { 2 } }, &left & &right);
+            assert_eq!($multimap_macro! { 0 => { 1 }, 1 => { 3 } }, &left - &right);
+            assert_eq!(
+                $multimap_macro! { 0 => { 1, 4 }, 1 => { 3 }, 2 => { 5 } },
+                &left ^ &right
+            );
+        }
+
+        #[test]
+        fn set_predicates() {
+            let (left, right) = left_and_right();
+            let subset = $multimap_macro! { 0 => { 2 } };
+            let disjoint = $multimap_macro! { 3 => { 2 } };
+            assert!(!left.is_disjoint(&right));
+            assert!(left.is_disjoint(&disjoint));
+            assert!(subset.is_subset(&left));
+            assert!(subset.is_subset(&right));
+            assert!(!left.is_subset(&right));
+            assert!(left.is_superset(&subset));
+            assert!(!subset.is_superset(&left));
+            assert!(left.is_subset(&left));
+        }
+
+        #[test]
+        fn in_place_set_operations_update_len() {
+            let (left, right) = left_and_right();
+
+            let mut union = left.clone();
+            union.union_with(&right);
+            assert_eq!(5, union.len());
+            assert_eq!(3, union.keys_len());
+            assert_eq!(&left | &right, union);
+
+            let mut intersection = left.clone();
+            intersection.intersect_with(&right);
+            assert_eq!(1, intersection.len());
+            assert_eq!(1, intersection.keys_len());
+            assert_eq!(&left & &right, intersection);
+
+            let mut difference = left.clone();
+            difference.subtract(&right);
+            assert_eq!(2, difference.len());
+            assert_eq!(2, difference.keys_len());
+            assert_eq!(&left - &right, difference);
+        }
     };
 }
 
 macro_rules! index_multimap_tests {
     ($type:tt, $multimap_macro:tt, $values_macro:tt) => {
+        #[test]
+        fn drain_range_removes_key_positions_in_order() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6, 7 }
+            };
+            let pairs = map.drain(1..).collect::<Vec<_>>();
+            assert_eq!(vec![(3, 4), (5, 6), (5, 7)], pairs);
+            assert_eq!(2, map.len());
+
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6, 7 }
+            };
+            let mut drain = map.drain(..2);
+            assert_eq!(Some((0, 1)), drain.next());
+            drop(drain);
+            assert_eq!(2, map.len());
+            assert_eq!(vec![&5], map.keys().collect::<Vec<_>>());
+        }
+
         #[test]
         fn shift_remove_removes_key_when_needed() {
             let data = vec![(0, "A1"), (0, "A2"), (0, "A3"), (1, "B1"), (2, "C1")];
@@ -195,6 +408,231 @@ macro_rules! index_multimap_tests {
             assert_eq!(None, map.swap_remove_key_entry(&0));
         }
 
+        #[test]
+        fn entry_swap_remove_entry_removes_key_and_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6 }
+            };
+            let Entry::Occupied(entry) = map.entry(0) else {
+                panic!("entry should be occupied")
+            };
+            assert_eq!((0, $values_macro! { 1, 2 }), entry.swap_remove_entry());
+            assert_eq!(2, map.len());
+            assert_eq!(
+                vec![(5, 6), (3, 4)],
+                map.clone().into_iter().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn entry_shift_remove_entry_removes_key_and_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6 }
+            };
+            let Entry::Occupied(entry) = map.entry(0) else {
+                panic!("entry should be occupied")
+            };
+            assert_eq!((0, $values_macro! { 1, 2 }), entry.shift_remove_entry());
+            assert_eq!(2, map.len());
+            assert_eq!(
+                vec![(3, 4), (5, 6)],
+                map.clone().into_iter().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn get_mut_preserves_key_order_when_values_are_empty() {
+            let mut map = $multimap_macro! {
+                0 => { 1 },
+                2 => { 3 },
+                4 => { 5 }
+            };
+            map.get_mut(0).unwrap().clear();
+            assert_eq!(vec![2, 4], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(2, map.len());
+        }
+
+        #[test]
+        fn get_mut_preserves_key_order() {
+            let mut map = $multimap_macro! {
+                0 => { 1 },
+                2 => { 3, 4 },
+                5 => { 6 }
+            };
+            map.get_mut(2).unwrap().extend(vec![7]);
+            map.get_mut(0).unwrap().extend(vec![8]);
+            assert_eq!(vec![0, 2, 5], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(6, map.len());
+        }
+
+        #[test]
+        fn first_last_and_pop() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            assert_eq!(Some((&0, &$values_macro! { 1, 2 })), map.first());
+            assert_eq!(Some((&3, &$values_macro! { 4 })), map.last());
+            assert_eq!(Some((3, $values_macro! { 4 })), map.pop());
+            assert_eq!(2, map.len());
+            assert_eq!(Some((0, $values_macro! { 1, 2 })), map.pop());
+            assert!(map.is_empty());
+            assert_eq!(None, map.pop());
+            assert_eq!(None, map.first());
+            assert_eq!(None, map.last());
+        }
+
+        #[test]
+        fn swap_and_shift_remove_index_update_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6 },
+                7 => { 8 }
+            };
+            assert_eq!(Some((0, $values_macro! { 1, 2 })), map.swap_remove_index(0));
+            assert_eq!(vec![7, 3, 5], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(3, map.len());
+            assert_eq!(Some((7, $values_macro! { 8 })), map.shift_remove_index(0));
+            assert_eq!(vec![3, 5], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(2, map.len());
+            assert_eq!(None, map.swap_remove_index(2));
+            assert_eq!(None, map.shift_remove_index(2));
+        }
+
+        #[test]
+        fn get_index_mut_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6 }
+            };
+            assert!(map.get_index_mut(3).is_none());
+            {
+                let mut values = map.get_index_mut(0).unwrap();
+                assert_eq!(&0, values.key());
+                values.extend([7]);
+            }
+            assert_eq!(5, map.len());
+            map.get_index_mut(1).unwrap().clear();
+            assert_eq!(vec![0, 5], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(4, map.len());
+        }
+
+        #[test]
+        fn serde_preserves_order() {
+            let map = $multimap_macro! {
+                2 => { 3, 1 },
+                0 => { 2 }
+            };
+            let json = serde_json::to_string(&map).unwrap();
+            assert_eq!(r#"{"2":[3,1],"0":[2]}"#, json);
+            let deserialized: $type<u8, u8> = serde_json::from_str(&json).unwrap();
+            assert_eq!(
+                map.iter().collect::<Vec<_>>(),
+                deserialized.iter().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn insert_full_indices_can_be_used_for_positional_access() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+            let (key_index, value_index, ..) = map.insert_full(2, 3);
+            let _ = map.insert_full(2, 4);
+            assert_eq!(Some(&3), map.get_value_at(key_index, value_index));
+            assert_eq!(None, map.get_value_at(key_index, 2));
+            assert_eq!(None, map.get_value_at(2, 0));
+
+            assert_eq!(None, map.remove_at(key_index, 2));
+            assert_eq!(Some(3), map.remove_at(key_index, value_index));
+            assert_eq!(Some(&4), map.get_value_at(key_index, value_index));
+            assert_eq!(2, map.len());
+
+            assert_eq!(Some(1), map.remove_at(0, 0));
+            assert_eq!(1, map.len());
+            assert_eq!(vec![2], map.keys().copied().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn sort_keys_and_values() {
+            let mut map = $multimap_macro! {
+                2 => { 3, 1 },
+                0 => { 5, 4 },
+                1 => { 2 }
+            };
+            map.sort_keys();
+            assert_eq!(
+                vec![(0, 5), (0, 4), (1, 2), (2, 3), (2, 1)],
+                map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+            map.sort_values();
+            assert_eq!(
+                vec![(0, 4), (0, 5), (1, 2), (2, 1), (2, 3)],
+                map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+            map.sort_values_by(|a, b| b.cmp(a));
+            assert_eq!(
+                vec![5, 4, 2, 3, 1],
+                map.values().copied().collect::<Vec<_>>()
+            );
+            assert_eq!(5, map.len());
+        }
+
+        #[test]
+        fn sort_by_and_sorted_by() {
+            let mut map = $multimap_macro! {
+                0 => { 1 },
+                1 => { 2, 3, 4 },
+                2 => { 5, 6 }
+            };
+            map.sort_by(|_, v1, _, v2| v2.len().cmp(&v1.len()));
+            assert_eq!(vec![1, 2, 0], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(
+                vec![(2, 5), (2, 6), (1, 2), (1, 3), (1, 4), (0, 1)],
+                map.sorted_by(|k1, _, k2, _| k2.cmp(k1)).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn sort_key_values_only_sorts_values_of_key() {
+            let mut map = $multimap_macro! {
+                0 => { 2, 1 },
+                1 => { 4, 3 }
+            };
+            assert!(map.sort_key_values(&0));
+            assert!(map.sort_key_values_by(&1, |a, b| b.cmp(a)));
+            assert!(!map.sort_key_values(&2));
+            assert_eq!(vec![1, 2, 4, 3], map.values().copied().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn reverse_swap_indices_and_move_index_reorder_keys() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                1 => { 3 },
+                2 => { 4 },
+                3 => { 5 }
+            };
+            map.reverse();
+            assert_eq!(vec![3, 2, 1, 0], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(
+                vec![5, 4, 3, 1, 2],
+                map.values().copied().collect::<Vec<_>>()
+            );
+            map.swap_indices(0, 3);
+            assert_eq!(vec![0, 2, 1, 3], map.keys().copied().collect::<Vec<_>>());
+            map.move_index(0, 2);
+            assert_eq!(vec![2, 1, 0, 3], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(Some(2), map.get_key_index(&0));
+            assert_eq!(5, map.len());
+        }
+
         #[test]
         fn shift_remove_is_noop_when_key_value_is_not_there() {
             let data = vec![(0, "A1".to_string()), (0, "A2".to_string())];
@@ -433,6 +871,21 @@ macro_rules! hash_multimap_tests {
             assert_eq!(None, map.remove_key_entry(&0));
         }
 
+        #[test]
+        fn entry_remove_entry_removes_key_and_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            let Entry::Occupied(entry) = map.entry(0) else {
+                panic!("entry should be occupied")
+            };
+            assert_eq!((0, $values_macro! { 1, 2 }), entry.remove_entry());
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+            assert!(!map.contains_key(&0));
+        }
+
         #[test]
         fn remove_is_noop_when_key_value_is_not_there() {
             let data = vec![(0, "A1".to_string()), (0, "A2".to_string())];
@@ -568,77 +1021,132 @@ macro_rules! hash_multimap_tests {
     };
 }
 
-macro_rules! general_multimap_tests {
-    ($type:tt, $multimap_macro:tt, $keys_macro:tt, $values_macro:tt) => {
+macro_rules! btree_multimap_tests {
+    ($type:tt, $multimap_macro:tt, $values_macro:tt) => {
         #[test]
-        fn retain_on_key_filter() {
+        fn remove_removes_key_when_needed() {
             let mut map = $multimap_macro! {
-                0 => {1, 2, 3 },
-                4 => {5},
-                7 => {3, 9}
+                0 => { 1, 2 },
+                3 => { 4 }
             };
-            map.retain(|k, _v| k == &4);
+            assert_eq!(Some(1), map.remove(&0, &1));
+            assert_eq!(2, map.len());
+            assert_eq!(2, map.keys_len());
+
+            assert_eq!(Some(2), map.remove(&0, &2));
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+            assert!(!map.contains_key(&0));
+
+            assert_eq!(None, map.remove(&3, &5));
             assert_eq!(1, map.len());
-            let expected = $multimap_macro! {
-                4 => {5}
-            };
-            assert_eq!(expected, map);
         }
 
         #[test]
-        fn retain_on_value_filter() {
+        fn remove_key_entry_returns_entire_value_collection_when_present() {
             let mut map = $multimap_macro! {
-                0 => {1, 2, 3 },
-                4 => {5},
-                7 => {3, 9}
-            };
-            map.retain(|_k, v| v == &3);
-            assert_eq!(2, map.len());
-            let expected = $multimap_macro! {
-                0 => {3},
-                7 => {3}
+                0 => { 1, 2 },
+                3 => { 4 }
             };
-            assert_eq!(expected, map);
+            assert_eq!(Some((0, $values_macro! { 1, 2 })), map.remove_key_entry(&0));
+            assert_eq!(1, map.len());
+            assert_eq!(None, map.remove_key_entry(&0));
+            assert_eq!(Some($values_macro! { 4 }), map.remove_key(&3));
+            assert!(map.is_empty());
         }
 
         #[test]
-        fn retain_on_key_value_filter() {
+        fn entry_remove_entry_removes_key_and_updates_len() {
             let mut map = $multimap_macro! {
-                0 => {1, 2, 3 },
-                4 => {5},
-                7 => {3, 9}
+                0 => { 1, 2 },
+                3 => { 4 }
             };
-            map.retain(|k, v| (k + v) % 2 == 0);
-            assert_eq!(3, map.len());
-            let expected = $multimap_macro! {
-                0 => {2},
-                7 => {3, 9}
+            let Entry::Occupied(entry) = map.entry(0) else {
+                panic!("entry should be occupied")
             };
-            assert_eq!(expected, map);
+            assert_eq!((0, $values_macro! { 1, 2 }), entry.remove_entry());
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
         }
 
         #[test]
-        fn len_is_consistent() {
-            let data = vec![
-                (0, "A".to_string()),
-                (1, "B".to_string()),
-                (2, "C".to_string()),
-                (3, "D".to_string()),
-                (4, "E".to_string()),
-                (4, "E2".to_string()),
-                (0, "A2".to_string()),
-            ];
-            let mut map = $type::new();
-            for (i, (k, v)) in data.iter().enumerate() {
-                assert_eq!(map.len(), i);
-                map.insert(*k, v.to_string());
-                assert_eq!(map.len(), i + 1);
-            }
-            let map = data.into_iter().collect::<$type<_, _>>();
-            assert_eq!(7, map.len());
-            assert_eq!(5, map.keys_len());
+        fn iterators_have_sorted_key_order() {
+            let map = $multimap_macro! {
+                7 => { 8 },
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            assert_eq!(vec![0, 3, 7], map.keys().copied().collect::<Vec<_>>());
+            assert_eq!(
+                vec![(0, 1), (0, 2), (3, 4), (7, 8)],
+                map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+            assert_eq!(vec![1, 2, 4, 8], map.values().copied().collect::<Vec<_>>());
+            assert_eq!(
+                vec![(0, 1), (0, 2), (3, 4), (7, 8)],
+                map.clone().into_iter().collect::<Vec<_>>()
+            );
+            assert_eq!(vec![0, 3, 7], map.clone().into_keys().collect::<Vec<_>>());
+            assert_eq!(vec![1, 2, 4, 8], map.into_values().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn range_returns_key_value_pairs_within_range() {
+            let map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6, 7 },
+                9 => { 10 }
+            };
+            assert_eq!(
+                vec![(3, 4), (5, 6), (5, 7)],
+                map.range(1..9).map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(5, 6), (5, 7), (9, 10)],
+                map.range(5..).map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(0, 1), (0, 2)],
+                map.range(..=0).map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+            assert_eq!(None, map.range(6..9).next());
+        }
+
+        #[test]
+        fn first_and_last_key_value() {
+            let map = $multimap_macro! {
+                3 => { 4 },
+                0 => { 1, 2 },
+                5 => { 6, 7 }
+            };
+            assert_eq!(Some((&0, &$values_macro! { 1, 2 })), map.first_key_value());
+            assert_eq!(Some((&5, &$values_macro! { 6, 7 })), map.last_key_value());
+        }
+
+        #[test]
+        fn pop_first_and_pop_last_update_len() {
+            let mut map = $multimap_macro! {
+                3 => { 4 },
+                0 => { 1, 2 },
+                5 => { 6, 7 }
+            };
+            assert_eq!(Some((0, $values_macro! { 1, 2 })), map.pop_first());
+            assert_eq!(3, map.len());
+            assert_eq!(Some((5, $values_macro! { 6, 7 })), map.pop_last());
+            assert_eq!(1, map.len());
+            assert_eq!(Some((3, $values_macro! { 4 })), map.pop_last());
+            assert!(map.is_empty());
+            assert_eq!(None, map.pop_first());
+            assert_eq!(None, map.pop_last());
+            assert_eq!(None, map.first_key_value());
+            assert_eq!(None, map.last_key_value());
         }
+    };
+}
 
+macro_rules! capacity_multimap_tests {
+    ($type:tt) => {
         #[test]
         fn shrink_keys_to_fit_sets_capacity_at_most_len() {
             // empty sets it to zero
@@ -667,6 +1175,86 @@ macro_rules! general_multimap_tests {
             assert!(map[&1].capacity() >= 1);
         }
 
+        #[test]
+        fn from_iter_sizes_keys_from_distinct_keys() {
+            let map: $type<usize, usize> = (0..1000).map(|value| (value % 2, value)).collect();
+            assert_eq!(2, map.keys_len());
+            assert!(map.key_capacity() < 16);
+        }
+    };
+}
+
+macro_rules! general_multimap_tests {
+    ($type:tt, $multimap_macro:tt, $keys_macro:tt, $values_macro:tt) => {
+        #[test]
+        fn retain_on_key_filter() {
+            let mut map = $multimap_macro! {
+                0 => {1, 2, 3 },
+                4 => {5},
+                7 => {3, 9}
+            };
+            map.retain(|k, _v| k == &4);
+            assert_eq!(1, map.len());
+            let expected = $multimap_macro! {
+                4 => {5}
+            };
+            assert_eq!(expected, map);
+        }
+
+        #[test]
+        fn retain_on_value_filter() {
+            let mut map = $multimap_macro! {
+                0 => {1, 2, 3 },
+                4 => {5},
+                7 => {3, 9}
+            };
+            map.retain(|_k, v| v == &3);
+            assert_eq!(2, map.len());
+            let expected = $multimap_macro! {
+                0 => {3},
+                7 => {3}
+            };
+            assert_eq!(expected, map);
+        }
+
+        #[test]
+        fn retain_on_key_value_filter() {
+            let mut map = $multimap_macro! {
+                0 => {1, 2, 3 },
+                4 => {5},
+                7 => {3, 9}
+            };
+            map.retain(|k, v| (k + v) % 2 == 0);
+            assert_eq!(3, map.len());
+            let expected = $multimap_macro! {
+                0 => {2},
+                7 => {3, 9}
+            };
+            assert_eq!(expected, map);
+        }
+
+        #[test]
+        fn len_is_consistent() {
+            let data = vec![
+                (0, "A".to_string()),
+                (1, "B".to_string()),
+                (2, "C".to_string()),
+                (3, "D".to_string()),
+                (4, "E".to_string()),
+                (4, "E2".to_string()),
+                (0, "A2".to_string()),
+            ];
+            let mut map = $type::new();
+            for (i, (k, v)) in data.iter().enumerate() {
+                assert_eq!(map.len(), i);
+                map.insert(*k, v.to_string());
+                assert_eq!(map.len(), i + 1);
+            }
+            let map = data.into_iter().collect::<$type<_, _>>();
+            assert_eq!(7, map.len());
+            assert_eq!(5, map.keys_len());
+        }
+
         #[test]
         fn contains_key_returns_correct_value() {
             let map = $multimap_macro! {
@@ -831,6 +1419,181 @@ macro_rules! general_multimap_tests {
             let _ = &a[&1];
         }
 
+        #[test]
+        fn entry_insert_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 }
+            };
+
+            // occupied
+            let values = map.entry(0).insert(3);
+            assert_eq!(&$values_macro! { 1, 2, 3 }, values);
+            assert_eq!(3, map.len());
+
+            // vacant
+            let values = map.entry(7).insert(8);
+            assert_eq!(&$values_macro! { 8 }, values);
+            assert_eq!(4, map.len());
+            assert_eq!(2, map.keys_len());
+
+            let expected = $multimap_macro! {
+                0 => { 1, 2, 3 },
+                7 => { 8 }
+            };
+            assert_eq!(expected, map);
+        }
+
+        #[test]
+        fn entry_extend_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+
+            // occupied
+            let values = map.entry(0).extend(vec![2, 3]);
+            assert_eq!(Some(&$values_macro! { 1, 2, 3 }), values);
+            assert_eq!(3, map.len());
+
+            // vacant
+            let values = map.entry(4).extend(vec![5, 6]);
+            assert_eq!(Some(&$values_macro! { 5, 6 }), values);
+            assert_eq!(5, map.len());
+            assert_eq!(2, map.keys_len());
+        }
+
+        #[test]
+        fn entry_extend_with_no_values_does_not_insert_key() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+            assert_eq!(None, map.entry(4).extend(vec![]));
+            assert!(!map.contains_key(&4));
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+
+            assert_eq!(None, map.entry(4).or_insert_with_values(Vec::new));
+            assert!(!map.contains_key(&4));
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+        }
+
+        #[test]
+        fn entry_or_insert_with_values_only_inserts_when_vacant() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+
+            // occupied
+            let values = map
+                .entry(0)
+                .or_insert_with_values(|| -> Vec<usize> { panic!("should not be called") });
+            assert_eq!(Some(&$values_macro! { 1 }), values);
+            assert_eq!(1, map.len());
+
+            // vacant
+            let values = map.entry(2).or_insert_with_values(|| vec![3, 4]);
+            assert_eq!(Some(&$values_macro! { 3, 4 }), values);
+            assert_eq!(3, map.len());
+            assert_eq!(2, map.keys_len());
+        }
+
+        #[test]
+        fn entry_key_returns_key() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+            assert_eq!(&0, map.entry(0).key());
+            assert_eq!(&5, map.entry(5).key());
+            assert_eq!(1, map.keys_len());
+        }
+
+        #[test]
+        fn entry_occupied_and_vacant() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+            match map.entry(0) {
+                Entry::Occupied(mut entry) => {
+                    assert_eq!(&0, entry.key());
+                    assert_eq!(&$values_macro! { 1 }, entry.get());
+                    entry.insert(2);
+                    entry.extend(vec![3]);
+                    assert_eq!(&$values_macro! { 1, 2, 3 }, entry.get());
+                }
+                Entry::Vacant(_) => panic!("entry should be occupied"),
+            }
+            assert_eq!(3, map.len());
+
+            match map.entry(1) {
+                Entry::Occupied(_) => panic!("entry should be vacant"),
+                Entry::Vacant(entry) => {
+                    assert_eq!(1, entry.into_key());
+                }
+            }
+            assert_eq!(3, map.len());
+            assert_eq!(1, map.keys_len());
+        }
+
+        #[test]
+        fn get_mut_updates_len() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            {
+                let mut values = map.get_mut(0).unwrap();
+                values.extend(vec![5, 6]);
+                assert_eq!(&$values_macro! { 1, 2, 5, 6 }, &*values);
+            }
+            assert_eq!(5, map.len());
+            assert_eq!(2, map.keys_len());
+
+            map.get_mut(0).unwrap().retain(|v| *v != 1);
+            assert_eq!(4, map.len());
+
+            let expected = $multimap_macro! {
+                0 => { 2, 5, 6 },
+                3 => { 4 }
+            };
+            assert_eq!(expected, map);
+        }
+
+        #[test]
+        fn get_mut_removes_key_when_values_are_empty() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            map.get_mut(0).unwrap().clear();
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+            assert!(!map.contains_key(&0));
+            assert_eq!(None, map.get(&0));
+        }
+
+        #[test]
+        fn get_mut_returns_none_when_key_is_absent() {
+            let mut map = $multimap_macro! {
+                0 => { 1 }
+            };
+            assert!(map.get_mut(7).is_none());
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+        }
+
+        #[test]
+        fn get_mut_keeps_key_when_guard_is_leaked() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            std::mem::forget(map.get_mut(0).unwrap());
+            assert_eq!(3, map.len());
+            assert_eq!(2, map.keys_len());
+            assert!(map.contains(&0, &1));
+            assert!(map.contains(&0, &2));
+        }
+
         #[test]
         fn iter_supports_clone() {
             let map = $multimap_macro! {
@@ -891,36 +1654,1052 @@ macro_rules! general_multimap_tests {
     };
 }
 
-mod hash_set_multimap {
-    use maplit::hashmap;
-    use maplit::hashset;
-    use more_collections::hashsetmultimap;
-    use more_collections::HashSetMultimap;
-
-    general_multimap_tests! {HashSetMultimap, hashsetmultimap, hashmap, hashset}
-    hash_multimap_tests! {HashSetMultimap, hashsetmultimap, hashset}
-    set_multimap_tests! {HashSetMultimap}
-}
+macro_rules! double_ended_multimap_tests {
+    ($multimap_macro:tt) => {
+        #[test]
+        fn iterators_can_be_reversed() {
+            let map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 },
+                5 => { 6, 7 }
+            };
+            assert_eq!(
+                vec![(&5, &7), (&5, &6), (&3, &4), (&0, &2), (&0, &1)],
+                map.iter().rev().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![&7, &6, &4, &2, &1],
+                map.values().rev().collect::<Vec<_>>()
+            );
+            assert_eq!(vec![&5, &3, &0], map.keys().rev().collect::<Vec<_>>());
+            assert_eq!(
+                vec![(5, 7), (5, 6), (3, 4), (0, 2), (0, 1)],
+                map.clone().into_iter().rev().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![7, 6, 4, 2, 1],
+                map.clone().into_values().rev().collect::<Vec<_>>()
+            );
+            assert_eq!(vec![5, 3, 0], map.into_keys().rev().collect::<Vec<_>>());
+        }
 
-mod hash_vec_multimap {
+        #[test]
+        fn iterating_from_both_ends_crosses_key_boundaries() {
+            let map = $multimap_macro! {
+                0 => { 1, 2, 3 }
+            };
+            let mut iter = map.iter();
+            assert_eq!(Some((&0, &1)), iter.next());
+            assert_eq!(Some((&0, &3)), iter.next_back());
+            assert_eq!(1, iter.len());
+            assert_eq!(Some((&0, &2)), iter.next());
+            assert_eq!(None, iter.next_back());
+            assert_eq!(None, iter.next());
+            assert_eq!(0, iter.len());
+
+            let map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            let mut iter = map.clone().into_iter();
+            assert_eq!(Some((3, 4)), iter.next_back());
+            assert_eq!(Some((0, 2)), iter.next_back());
+            assert_eq!(Some((0, 1)), iter.next());
+            assert_eq!(None, iter.next_back());
+            assert_eq!(None, iter.next());
+
+            let mut values = map.into_values();
+            assert_eq!(Some(4), values.next_back());
+            assert_eq!(Some(1), values.next());
+            assert_eq!(1, values.len());
+            assert_eq!(Some(2), values.next_back());
+            assert_eq!(None, values.next());
+        }
+    };
+}
+
+macro_rules! drain_multimap_tests {
+    ($type:tt, $multimap_macro:tt, $drain_all:tt) => {
+        #[test]
+        fn drain_yields_all_pairs_and_empties_map() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            let drain = map.drain $drain_all;
+            assert_eq!(3, drain.len());
+            let mut pairs = drain.collect::<Vec<_>>();
+            pairs.sort_unstable();
+            assert_eq!(vec![(0, 1), (0, 2), (3, 4)], pairs);
+            assert!(map.is_empty());
+            assert_eq!(0, map.keys_len());
+        }
+
+        #[test]
+        fn drain_dropped_early_still_empties_map() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            let mut drain = map.drain $drain_all;
+            assert!(drain.next().is_some());
+            assert_eq!(2, drain.len());
+            drop(drain);
+            assert!(map.is_empty());
+            map.insert(5, 6);
+            assert_eq!(1, map.len());
+        }
+
+        #[test]
+        fn drain_key_removes_single_key() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2 },
+                3 => { 4 }
+            };
+            assert_eq!(0, map.drain_key(&7).len());
+            let mut drain = map.drain_key(&0);
+            assert_eq!(2, drain.len());
+            assert!(drain.next().is_some());
+            assert_eq!(1, drain.len());
+            drop(drain);
+            assert_eq!(1, map.len());
+            assert!(!map.contains_key(&0));
+            let pairs = map.drain_key(&3).collect::<Vec<_>>();
+            assert_eq!(vec![(3, 4)], pairs);
+            assert!(map.is_empty());
+        }
+
+        #[test]
+        fn extract_if_removes_matching_pairs() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2, 3 },
+                4 => { 6 },
+                5 => { 7 }
+            };
+            let mut pairs = map.extract_if(|_key, value| value % 2 == 0).collect::<Vec<_>>();
+            pairs.sort_unstable();
+            assert_eq!(vec![(0, 2), (4, 6)], pairs);
+            assert_eq!(3, map.len());
+            assert_eq!(2, map.keys_len());
+            assert!(!map.contains_key(&4));
+
+            drop(map.extract_if(|key, _value| *key == 5));
+            assert_eq!(2, map.len());
+            assert!(map.contains(&0, &1));
+            assert!(map.contains(&0, &3));
+        }
+
+        #[test]
+        fn extract_if_calls_predicate_once_per_pair() {
+            let mut map = $multimap_macro! {
+                0 => { 1, 2, 3 },
+                4 => { 6 }
+            };
+            let mut calls = 0;
+            let extracted = map
+                .extract_if(|_key, value| {
+                    calls += 1;
+                    *value == 2
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(4, calls);
+            assert_eq!(vec![(0, 2)], extracted);
+            assert_eq!(3, map.len());
+            assert!(map.contains(&0, &1));
+            assert!(map.contains(&0, &3));
+            assert!(map.contains(&4, &6));
+        }
+    };
+}
+
+macro_rules! index_key_multimap_tests {
+    ($type:tt, $multimap_macro:tt, $a:expr, $b:expr, $c:expr) => {
+        #[test]
+        fn new_is_empty() {
+            let map: $type<_, u8> = $type::new();
+            assert!(map.is_empty());
+            assert_eq!(0, map.len());
+            assert_eq!(0, map.keys_len());
+            assert_eq!(None, map.get($a));
+            assert!(!map.contains_key($a));
+            assert_eq!(0, map.iter().count());
+            assert_eq!("{}", format!("{map:?}"));
+        }
+
+        #[test]
+        fn insert_updates_len_and_keys_len() {
+            let mut map = $type::new();
+            let _ = map.insert($c, 1);
+            let _ = map.insert($c, 2);
+            let _ = map.insert($a, 3);
+            assert_eq!(3, map.len());
+            assert_eq!(2, map.keys_len());
+            assert!(map.contains_key($c));
+            assert!(!map.contains_key($b));
+            assert!(map.contains($c, &2));
+            assert!(!map.contains($a, &2));
+            assert_eq!(2, map[$c].len());
+            assert_eq!(
+                Some(($a, 1)),
+                map.get_key_values($a).map(|(k, v)| (k, v.len()))
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "no entry found for key")]
+        fn index_panics_when_key_is_absent() {
+            let map = $multimap_macro! { $a => { 1 } };
+            let _ = &map[$b];
+        }
+
+        #[test]
+        fn iterators_are_in_index_order() {
+            let map = $multimap_macro! {
+                $c => { 3 },
+                $a => { 1 },
+                $b => { 2 }
+            };
+            let iter = map.iter();
+            assert_eq!(3, iter.len());
+            assert_eq!(vec![($a, &1), ($b, &2), ($c, &3)], iter.collect::<Vec<_>>());
+            assert_eq!(vec![&1, &2, &3], map.values().collect::<Vec<_>>());
+            let keys = map.keys();
+            assert_eq!(3, keys.len());
+            assert_eq!(vec![$a, $b, $c], keys.collect::<Vec<_>>());
+            assert_eq!(
+                vec![$a, $b, $c],
+                map.clone().into_keys().collect::<Vec<_>>()
+            );
+            assert_eq!(vec![1, 2, 3], map.clone().into_values().collect::<Vec<_>>());
+            let mut into_iter = map.into_iter();
+            assert_eq!(Some(($a, 1)), into_iter.next());
+            assert_eq!(2, into_iter.len());
+        }
+
+        #[test]
+        fn remove_removes_key_when_needed() {
+            let mut map = $multimap_macro! {
+                $a => { 1, 2 },
+                $b => { 3 }
+            };
+            assert_eq!(Some(1), map.remove($a, &1));
+            assert_eq!(None, map.remove($a, &1));
+            assert_eq!(2, map.len());
+            assert_eq!(Some(3), map.remove($b, &3));
+            assert_eq!(1, map.len());
+            assert_eq!(1, map.keys_len());
+            assert!(!map.contains_key($b));
+        }
+
+        #[test]
+        fn remove_key_entry_returns_entire_value_collection_when_present() {
+            let mut map = $multimap_macro! {
+                $a => { 1, 2 },
+                $b => { 3 }
+            };
+            let (key, values) = map.remove_key_entry($a).unwrap();
+            assert_eq!($a, key);
+            assert_eq!(2, values.len());
+            assert_eq!(1, map.len());
+            assert_eq!(None, map.remove_key($a));
+            assert_eq!(Some(1), map.remove_key($b).map(|values| values.len()));
+            assert!(map.is_empty());
+        }
+
+        #[test]
+        fn retain_updates_len() {
+            let mut map = $multimap_macro! {
+                $a => { 1, 2 },
+                $b => { 3, 4 }
+            };
+            map.retain(|key, value| *key == $a || *value > 3);
+            assert_eq!(3, map.len());
+            assert_eq!(2, map.keys_len());
+            assert!(!map.contains($b, &3));
+        }
+
+        #[test]
+        fn get_mut_updates_len() {
+            let mut map = $multimap_macro! {
+                $a => { 1, 2 }
+            };
+            assert!(map.get_mut($b).is_none());
+            {
+                let mut values = map.get_mut($a).unwrap();
+                values.clear();
+            }
+            assert!(map.is_empty());
+            assert!(!map.contains_key($a));
+        }
+
+        #[test]
+        fn entry_updates_len() {
+            let mut map = $type::new();
+            assert!(matches!(map.entry($b), Entry::Vacant(_)));
+            assert_eq!(1, map.entry($b).insert(1).len());
+            assert!(matches!(map.entry($b), Entry::Occupied(_)));
+            assert_eq!(Some(3), map.entry($b).extend([2, 3]).map(|v| v.len()));
+            assert_eq!(None, map.entry($a).extend([]));
+            assert_eq!(3, map.len());
+            assert_eq!($b, map.entry($b).key());
+            let Entry::Occupied(entry) = map.entry($b) else {
+                panic!("entry should be occupied");
+            };
+            let (key, values) = entry.remove_entry();
+            assert_eq!($b, key);
+            assert_eq!(3, values.len());
+            assert!(map.is_empty());
+        }
+
+        #[test]
+        fn collect_extend_and_eq() {
+            let mut map = [($a, 1), ($c, 2)].into_iter().collect::<$type<_, _>>();
+            map.extend([($a, 3)]);
+            map.extend([(&$b, &4)]);
+            let expected = $multimap_macro! {
+                $a => { 1, 3 },
+                $b => { 4 },
+                $c => { 2 }
+            };
+            assert_eq!(expected, map);
+            map.remove($b, &4);
+            assert_ne!(expected, map);
+        }
+
+        #[test]
+        fn drain_drain_key_and_extract_if_update_len() {
+            let mut map = $multimap_macro! {
+                $a => { 1, 2 },
+                $b => { 3, 4 },
+                $c => { 5 }
+            };
+            let mut extracted = map
+                .extract_if(|key, value| *key == $b || *value == 2)
+                .collect::<Vec<_>>();
+            extracted.sort_unstable_by_key(|(_key, value)| *value);
+            assert_eq!(vec![($a, 2), ($b, 3), ($b, 4)], extracted);
+            assert_eq!(2, map.len());
+            assert!(!map.contains_key($b));
+
+            assert_eq!(0, map.drain_key($b).len());
+            assert_eq!(vec![($c, 5)], map.drain_key($c).collect::<Vec<_>>());
+            assert_eq!(1, map.len());
+
+            let mut drain = map.drain();
+            assert_eq!(Some(($a, 1)), drain.next());
+            assert!(map.is_empty());
+            assert_eq!(0, map.keys_len());
+        }
+    };
+}
+
+macro_rules! enum_multimap_tests {
+    ($type:tt, $multimap_macro:tt) => {
+        use more_collections::EnumKey;
+        use more_collections::IndexKey;
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        enum Color {
+            Red,
+            Green,
+            Blue,
+        }
+
+        impl IndexKey for Color {
+            fn as_index(&self) -> usize {
+                *self as usize
+            }
+
+            fn from_index(index: usize) -> Self {
+                match index {
+                    0 => Color::Red,
+                    1 => Color::Green,
+                    2 => Color::Blue,
+                    _ => panic!("invalid index {index}"),
+                }
+            }
+        }
+
+        impl EnumKey for Color {
+            const VARIANT_COUNT: usize = 3;
+        }
+
+        index_key_multimap_tests! {$type, $multimap_macro, Color::Red, Color::Green, Color::Blue}
+        serde_multimap_tests! {$type, $multimap_macro, Color, Color::Red, Color::Green}
+        invert_multimap_tests! {$type, $multimap_macro, Color::Red, Color::Green, Color::Blue}
+
+        #[test]
+        fn keys_len_tracks_emptied_and_refilled_keys() {
+            let mut map = $multimap_macro! {
+                Color::Red => { 1 },
+                Color::Blue => { 2 }
+            };
+            map.get_mut(Color::Red).unwrap().clear();
+            assert_eq!(1, map.keys_len());
+            assert_eq!(vec![Color::Blue], map.keys().collect::<Vec<_>>());
+            let Entry::Occupied(entry) = map.entry(Color::Blue) else {
+                panic!("entry should be occupied");
+            };
+            assert_eq!(1, entry.remove_entry().1.len());
+            assert_eq!(0, map.keys_len());
+            assert_eq!(0, map.keys().len());
+            map.entry(Color::Blue).insert(3);
+            map.insert(Color::Red, 4);
+            assert_eq!(2, map.keys_len());
+            assert_eq!(
+                vec![Color::Red, Color::Blue],
+                map.keys().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(Color::Red, &4), (Color::Blue, &3)],
+                map.iter().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn deserialize_rejects_unknown_variant() {
+            assert!(serde_json::from_str::<$type<Color, u8>>(r#"{"3": [1]}"#).is_err());
+        }
+    };
+}
+
+macro_rules! multimap_trait_tests {
+    ($type:tt, $insert_trait:tt, $a:expr, $b:expr) => {
+        fn generic_summary<M>(map: &M, key: &M::Key) -> (usize, usize, i32, bool)
+        where
+            M: more_collections::Multimap<Value = i32>,
+        {
+            (
+                map.iter().count(),
+                map.keys().count(),
+                map.values().copied().sum(),
+                map.contains_key(key),
+            )
+        }
+
+        #[test]
+        fn multimap_trait() {
+            use more_collections::$insert_trait;
+            use more_collections::Multimap;
+
+            let mut map = $type::new();
+            $insert_trait::insert(&mut map, $a, 1);
+            $insert_trait::insert(&mut map, $a, 2);
+            $insert_trait::insert(&mut map, $b, 3);
+
+            assert_eq!(3, Multimap::len(&map));
+            assert_eq!(2, Multimap::keys_len(&map));
+            assert!(!Multimap::is_empty(&map));
+            assert_eq!((3, 2, 6, true), generic_summary(&map, &$a));
+            assert!(Multimap::contains(&map, &$a, &2));
+            assert_eq!(2, Multimap::get(&map, &$a).unwrap().len());
+
+            assert_eq!(Some(2), Multimap::remove(&mut map, &$a, &2));
+            assert_eq!(None, Multimap::remove(&mut map, &$a, &2));
+            Multimap::retain(&mut map, |_, value| *value != 3);
+            assert!(!Multimap::contains_key(&map, &$b));
+            assert_eq!(1, Multimap::remove_key(&mut map, &$a).unwrap().len());
+            assert!(Multimap::is_empty(&map));
+        }
+
+        multimap_trait_tests! {@insert $type, $insert_trait, $a}
+    };
+    (@insert $type:tt, SetMultimap, $a:expr) => {
+        #[test]
+        fn set_multimap_trait_insert() {
+            use more_collections::Multimap;
+            use more_collections::SetMultimap;
+
+            let mut map = $type::new();
+            assert!(SetMultimap::insert(&mut map, $a, 1));
+            assert!(!SetMultimap::insert(&mut map, $a, 1));
+            assert_eq!(1, Multimap::len(&map));
+        }
+    };
+    (@insert $type:tt, ListMultimap, $a:expr) => {
+        #[test]
+        fn list_multimap_trait_insert() {
+            use more_collections::ListMultimap;
+            use more_collections::Multimap;
+
+            let mut map = $type::new();
+            ListMultimap::insert(&mut map, $a, 1);
+            ListMultimap::insert(&mut map, $a, 1);
+            assert_eq!(2, Multimap::len(&map));
+        }
+    };
+}
+
+macro_rules! invert_multimap_tests {
+    ($type:tt, $multimap_macro:tt, $a:expr, $b:expr, $c:expr) => {
+        #[test]
+        fn invert_swaps_keys_and_values() {
+            let map = $multimap_macro! {
+                $a => { $b, $c },
+                $b => { $c }
+            };
+            let expected = $multimap_macro! {
+                $b => { $a },
+                $c => { $a, $b }
+            };
+            assert_eq!(expected, map.inverted());
+            assert_eq!(3, map.inverted().len());
+            assert_eq!(2, map.inverted().keys_len());
+            assert_eq!(expected, map.clone().invert());
+            assert_eq!(map, map.inverted().invert());
+        }
+    };
+}
+
+macro_rules! serde_multimap_tests {
+    ($type:tt, $multimap_macro:tt, $key:ty, $a:expr, $b:expr) => {
+        #[test]
+        fn serde_round_trip() {
+            let map = $multimap_macro! {
+                $a => { 1, 2 },
+                $b => { 3 }
+            };
+            let json = serde_json::to_string(&map).unwrap();
+            let deserialized: $type<$key, _> = serde_json::from_str(&json).unwrap();
+            assert_eq!(map, deserialized);
+            assert_eq!(3, deserialized.len());
+            assert_eq!(2, deserialized.keys_len());
+        }
+
+        #[test]
+        fn deserialize_drops_empty_values_and_merges_keys() {
+            let map: $type<$key, u8> =
+                serde_json::from_str(r#"{"0": [1], "1": [], "0": [2]}"#).unwrap();
+            assert_eq!(
+                $multimap_macro! {
+                    $a => { 1, 2 }
+                },
+                map
+            );
+            assert_eq!(2, map.len());
+            assert_eq!(1, map.keys_len());
+        }
+
+        #[test]
+        fn deserialize_rejects_invalid_input() {
+            assert!(serde_json::from_str::<$type<$key, u8>>(r#"{"0": 1}"#).is_err());
+            assert!(serde_json::from_str::<$type<$key, u8>>("[0, 1]").is_err());
+        }
+    };
+}
+
+mod bi_multimap {
+    use indexmap::indexset;
+    use more_collections::bimultimap;
+    use more_collections::indexsetmultimap;
+    use more_collections::BiMultimap;
+    use more_collections::IndexSetMultimap;
+
+    multimap_trait_tests! {BiMultimap, SetMultimap, 0u8, 1}
+
+    fn assert_in_sync(map: &BiMultimap<u8, char>) {
+        assert_eq!(map.as_multimap(), &map.as_inverse().inverted());
+        assert_eq!(map.len(), map.as_inverse().len());
+    }
+
+    #[test]
+    fn insert_updates_both_indices() {
+        let mut map = BiMultimap::new();
+        assert!(map.insert(0, 'a'));
+        assert!(map.insert(0, 'b'));
+        assert!(map.insert(1, 'a'));
+        assert!(!map.insert(1, 'a'));
+
+        assert_eq!(3, map.len());
+        assert_eq!(2, map.keys_len());
+        assert_eq!(2, map.values_len());
+        assert_eq!(Some(&indexset! {'a', 'b'}), map.get_by_key(&0));
+        assert_eq!(Some(&indexset! {0, 1}), map.get_by_value(&'a'));
+        assert_eq!(Some(&indexset! {0}), map.get_by_value(&'b'));
+        assert_eq!(None, map.get_by_value(&'c'));
+        assert!(map.contains_value(&'b'));
+        assert!(!map.contains_value(&'c'));
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn remove_updates_both_indices() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a' }
+        };
+        assert_eq!(Some((0, 'a')), map.remove(&0, &'a'));
+        assert_eq!(None, map.remove(&0, &'a'));
+        assert_eq!(Some(&indexset! {1}), map.get_by_value(&'a'));
+
+        assert_eq!(Some((1, 'a')), map.remove(&1, &'a'));
+        assert!(!map.contains_key(&1));
+        assert!(!map.contains_value(&'a'));
+        assert_eq!(1, map.len());
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn remove_key_updates_both_indices() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a' }
+        };
+        assert_eq!(Some(indexset! {'a', 'b'}), map.remove_key(&0));
+        assert_eq!(None, map.remove_key(&0));
+        assert_eq!(Some(&indexset! {1}), map.get_by_value(&'a'));
+        assert!(!map.contains_value(&'b'));
+        assert_eq!(1, map.len());
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn remove_value_updates_both_indices() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a' }
+        };
+        assert_eq!(Some(indexset! {0, 1}), map.remove_value(&'a'));
+        assert_eq!(None, map.remove_value(&'a'));
+        assert_eq!(Some(&indexset! {'b'}), map.get_by_key(&0));
+        assert!(!map.contains_key(&1));
+        assert_eq!(1, map.len());
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn retain_updates_both_indices() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a', 'c' }
+        };
+        map.retain(|key, value| *key == 0 || *value != 'a');
+        assert_eq!(Some(&indexset! {0}), map.get_by_value(&'a'));
+        assert_eq!(Some(&indexset! {'c'}), map.get_by_key(&1));
+        assert_eq!(3, map.len());
+        assert_in_sync(&map);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(0, map.values_len());
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn retain_calls_predicate_once_per_pair() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a', 'c' }
+        };
+        // keep every other pair, the result depends on the number of calls
+        let calls = std::cell::Cell::new(0);
+        map.retain(|_, _| {
+            calls.set(calls.get() + 1);
+            calls.get() % 2 == 0
+        });
+        assert_eq!(4, calls.get());
+        assert_eq!(2, map.len());
+        assert_eq!(Some(&indexset! {'b'}), map.get_by_key(&0));
+        assert_eq!(Some(&indexset! {'c'}), map.get_by_key(&1));
+        assert_eq!(None, map.get_by_value(&'a'));
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn drain_and_extract_if_update_both_indices() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a', 'c' },
+            2 => { 'd' }
+        };
+        let extracted = map.extract_if(|key, value| *key == 1 && *value == 'a');
+        assert_eq!(vec![(1, 'a')], extracted.collect::<Vec<_>>());
+        assert_eq!(Some(&indexset! {0}), map.get_by_value(&'a'));
+        assert_in_sync(&map);
+
+        assert_eq!(
+            vec![(0, 'a'), (0, 'b')],
+            map.drain_key(&0).collect::<Vec<_>>()
+        );
+        assert!(!map.contains_value(&'a'));
+        assert_eq!(2, map.len());
+        assert_in_sync(&map);
+
+        assert_eq!(2, map.drain().count());
+        assert!(map.is_empty());
+        assert_eq!(0, map.values_len());
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn try_map_values_in_place_rebuilds_reverse_index() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'B' },
+            1 => { 'b' }
+        };
+        let result: Result<(), ()> = map.try_map_values_in_place(|_key, value| {
+            value.make_ascii_lowercase();
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+        assert_eq!(Some(&indexset! {0, 1}), map.get_by_value(&'b'));
+        assert!(!map.contains_value(&'B'));
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn try_map_values_in_place_preserves_order_of_unchanged_values() {
+        let mut map = bimultimap! {
+            0 => { 'c', 'x' },
+            1 => { 'a', 'x' },
+            2 => { 'b' }
+        };
+        let result = map.try_map_values_in_place(|key, value| {
+            if *key == 2 {
+                return Err(*value);
+            }
+            if *value == 'x' {
+                *value = if *key == 0 { 'd' } else { 'a' };
+            }
+            Ok(())
+        });
+        assert_eq!(Err('b'), result);
+        assert_eq!(
+            vec![&'c', &'a', &'b', &'d'],
+            map.distinct_values().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&indexset! {1}), map.get_by_value(&'a'));
+        assert!(!map.contains_value(&'x'));
+        assert_in_sync(&map);
+    }
+
+    #[test]
+    fn removals_preserve_order() {
+        let mut map = bimultimap! {
+            0 => { 'a', 'b', 'c' },
+            1 => { 'c' },
+            2 => { 'b' }
+        };
+        map.remove_key(&1);
+        map.remove(&0, &'b');
+        assert_eq!(vec![&0, &2], map.keys().collect::<Vec<_>>());
+        assert_eq!(
+            vec![&'a', &'b', &'c'],
+            map.distinct_values().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(&0, &'a'), (&0, &'c'), (&2, &'b')],
+            map.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn from_index_set_multimap_builds_reverse_index() {
+        let map = BiMultimap::from(indexsetmultimap! {
+            0 => { 'a', 'b' },
+            1 => { 'a' }
+        });
+        assert_eq!(Some(&indexset! {0, 1}), map.get_by_value(&'a'));
+        assert_eq!(map, [(0, 'a'), (0, 'b'), (1, 'a')].into_iter().collect());
+        assert_in_sync(&map);
+        assert_eq!(
+            indexsetmultimap! {
+                0 => { 'a', 'b' },
+                1 => { 'a' }
+            },
+            map.into_multimap()
+        );
+    }
+}
+
+mod btree_set_multimap {
+    use maplit::btreemap;
+    use maplit::btreeset;
+    use more_collections::btree_set_multimap::Entry;
+    use more_collections::btreesetmultimap;
+    use more_collections::BTreeSetMultimap;
+
+    general_multimap_tests! {BTreeSetMultimap, btreesetmultimap, btreemap, btreeset}
+    btree_multimap_tests! {BTreeSetMultimap, btreesetmultimap, btreeset}
+    set_multimap_tests! {BTreeSetMultimap}
+    double_ended_multimap_tests! {btreesetmultimap}
+    drain_multimap_tests! {BTreeSetMultimap, btreesetmultimap, ()}
+    serde_multimap_tests! {BTreeSetMultimap, btreesetmultimap, u8, 0, 1}
+    invert_multimap_tests! {BTreeSetMultimap, btreesetmultimap, 0, 1, 2}
+    multimap_trait_tests! {BTreeSetMultimap, SetMultimap, 0u8, 1}
+
+    #[test]
+    fn values_have_sorted_order() {
+        let map = btreesetmultimap! {
+            0 => { 3, 1, 2 }
+        };
+        assert_eq!(vec![1, 2, 3], map.values().copied().collect::<Vec<_>>());
+    }
+}
+
+mod btree_vec_multimap {
+    use maplit::btreemap;
+    use more_collections::btree_vec_multimap::Entry;
+    use more_collections::btreevecmultimap;
+    use more_collections::BTreeVecMultimap;
+
+    general_multimap_tests! {BTreeVecMultimap, btreevecmultimap, btreemap, vec}
+    btree_multimap_tests! {BTreeVecMultimap, btreevecmultimap, vec}
+    list_multimap_tests! {btreevecmultimap}
+    double_ended_multimap_tests! {btreevecmultimap}
+    drain_multimap_tests! {BTreeVecMultimap, btreevecmultimap, ()}
+    serde_multimap_tests! {BTreeVecMultimap, btreevecmultimap, u8, 0, 1}
+    invert_multimap_tests! {BTreeVecMultimap, btreevecmultimap, 0, 1, 2}
+    multimap_trait_tests! {BTreeVecMultimap, ListMultimap, 0u8, 1}
+
+    #[test]
+    fn values_have_insertion_order() {
+        let map = btreevecmultimap! {
+            0 => { 3, 1, 3 }
+        };
+        assert_eq!(vec![3, 1, 3], map.values().copied().collect::<Vec<_>>());
+    }
+}
+
+mod enum_hash_set_multimap {
+    use more_collections::enum_hash_set_multimap::Entry;
+    use more_collections::enumhashsetmultimap;
+    use more_collections::EnumHashSetMultimap;
+
+    enum_multimap_tests! {EnumHashSetMultimap, enumhashsetmultimap}
+    multimap_trait_tests! {EnumHashSetMultimap, SetMultimap, Color::Red, Color::Green}
+
+    #[test]
+    fn insert_ignores_duplicates() {
+        let mut map = EnumHashSetMultimap::new();
+        assert!(map.insert(Color::Red, 1));
+        assert!(!map.insert(Color::Red, 1));
+        assert_eq!(1, map.len());
+    }
+}
+
+mod enum_index_set_multimap {
+    use more_collections::enum_index_set_multimap::Entry;
+    use more_collections::enumindexsetmultimap;
+    use more_collections::EnumIndexSetMultimap;
+
+    enum_multimap_tests! {EnumIndexSetMultimap, enumindexsetmultimap}
+    multimap_trait_tests! {EnumIndexSetMultimap, SetMultimap, Color::Red, Color::Green}
+
+    #[test]
+    fn values_have_insertion_order() {
+        let mut map = enumindexsetmultimap! {
+            Color::Red => { 3, 1, 2 }
+        };
+        assert!(!map.insert(Color::Red, 1));
+        assert_eq!(vec![3, 1, 2], map.values().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn try_map_values_in_place_keeps_insertion_order() {
+        let mut map = enumindexsetmultimap! {
+            Color::Red => { 3, 1, 2 },
+            Color::Blue => { 5 }
+        };
+        let result = map.try_map_values_in_place(|key, value| {
+            if *key == Color::Blue {
+                return Err(*value);
+            }
+            *value %= 2;
+            Ok(())
+        });
+        assert_eq!(Err(5), result);
+        assert_eq!(vec![1, 0, 5], map.values().copied().collect::<Vec<_>>());
+        assert_eq!(3, map.len());
+    }
+}
+
+mod enum_vec_multimap {
+    use more_collections::enum_vec_multimap::Entry;
+    use more_collections::enumvecmultimap;
+    use more_collections::EnumVecMultimap;
+
+    enum_multimap_tests! {EnumVecMultimap, enumvecmultimap}
+    multimap_trait_tests! {EnumVecMultimap, ListMultimap, Color::Red, Color::Green}
+
+    #[test]
+    fn insert_allows_duplicates() {
+        let mut map = EnumVecMultimap::new();
+        map.insert(Color::Red, 1);
+        map.insert(Color::Red, 1);
+        assert_eq!(2, map.len());
+        assert_eq!(Some(&vec![1, 1]), map.get(Color::Red));
+    }
+
+    #[test]
+    fn equality_test_counts_duplicate_values() {
+        let a = enumvecmultimap! {Color::Red => { 1, 1, 2 }};
+        let b = enumvecmultimap! {Color::Red => { 1, 2, 2 }};
+        assert_ne!(a, b);
+        assert!(!a.eq_unordered(&b));
+
+        let c = enumvecmultimap! {Color::Red => { 2, 1, 1 }};
+        assert_eq!(a, c);
+        assert!(a.eq_unordered(&c));
+        assert!(!a.eq_ordered(&c));
+        assert!(a.eq_ordered(&a.clone()));
+    }
+
+    #[test]
+    fn iter_mut_follows_key_index_order() {
+        let mut map = enumvecmultimap! {
+            Color::Blue => { 3 },
+            Color::Red => { 1, 2 }
+        };
+        let mut iter = map.iter_mut();
+        assert_eq!(3, iter.len());
+        let (key, value) = iter.next().unwrap();
+        assert_eq!((Color::Red, &mut 1), (key, value));
+        assert_eq!(2, iter.len());
+
+        map.values_mut().for_each(|value| *value *= 10);
+        for (key, value) in &mut map {
+            *value += key.as_index();
+        }
+        assert_eq!(vec![10, 20, 32], map.values().copied().collect::<Vec<_>>());
+    }
+}
+
+mod hash_set_multimap {
+    use maplit::hashmap;
+    use maplit::hashset;
+    use more_collections::hash_set_multimap::Entry;
+    use more_collections::hashsetmultimap;
+    use more_collections::HashSetMultimap;
+
+    general_multimap_tests! {HashSetMultimap, hashsetmultimap, hashmap, hashset}
+    capacity_multimap_tests! {HashSetMultimap}
+    hash_multimap_tests! {HashSetMultimap, hashsetmultimap, hashset}
+    set_multimap_tests! {HashSetMultimap}
+    set_algebra_multimap_tests! {HashSetMultimap, hashsetmultimap}
+    drain_multimap_tests! {HashSetMultimap, hashsetmultimap, ()}
+    serde_multimap_tests! {HashSetMultimap, hashsetmultimap, u8, 0, 1}
+    invert_multimap_tests! {HashSetMultimap, hashsetmultimap, 0, 1, 2}
+    multimap_trait_tests! {HashSetMultimap, SetMultimap, 0u8, 1}
+}
+
+mod hash_small_set_multimap {
+    use std::collections::hash_map::RandomState;
+
+    use maplit::hashmap;
+    use more_collections::hash_small_set_multimap::Entry;
+    use more_collections::hashsmallsetmultimap;
+    use more_collections::smallset;
+
+    type HashSmallSetMultimap<K, V, S = RandomState> =
+        more_collections::HashSmallSetMultimap<K, V, 2, S>;
+
+    general_multimap_tests! {HashSmallSetMultimap, hashsmallsetmultimap, hashmap, smallset}
+    capacity_multimap_tests! {HashSmallSetMultimap}
+    hash_multimap_tests! {HashSmallSetMultimap, hashsmallsetmultimap, smallset}
+    set_multimap_tests! {HashSmallSetMultimap}
+    set_algebra_multimap_tests! {HashSmallSetMultimap, hashsmallsetmultimap}
+    drain_multimap_tests! {HashSmallSetMultimap, hashsmallsetmultimap, ()}
+    serde_multimap_tests! {HashSmallSetMultimap, hashsmallsetmultimap, u8, 0, 1}
+    invert_multimap_tests! {HashSmallSetMultimap, hashsmallsetmultimap, 0, 1, 2}
+    multimap_trait_tests! {HashSmallSetMultimap, SetMultimap, 0u8, 1}
+
+    #[test]
+    fn values_spill_beyond_inline_capacity() {
+        let mut map = HashSmallSetMultimap::new();
+        assert!(map.insert(0, 1));
+        assert!(map.insert(0, 2));
+        assert!(map.insert(0, 3));
+        assert!(!map.insert(0, 2));
+        assert_eq!(3, map.len());
+        assert_eq!(Some(&smallset! { 1, 2, 3 }), map.get(&0));
+
+        assert_eq!(Some(2), map.remove(&0, &2));
+        assert_eq!(Some(1), map.remove(&0, &1));
+        assert_eq!(1, map.len());
+        assert_eq!(Some(&smallset! { 3 }), map.get(&0));
+    }
+}
+mod hash_vec_multimap {
     use maplit::hashmap;
+    use more_collections::hash_vec_multimap::Entry;
     use more_collections::hashvecmultimap;
     use more_collections::HashVecMultimap;
 
     general_multimap_tests! {HashVecMultimap, hashvecmultimap, hashmap, vec}
+    capacity_multimap_tests! {HashVecMultimap}
     hash_multimap_tests! {HashVecMultimap, hashvecmultimap, vec}
+    list_multimap_tests! {hashvecmultimap}
+    drain_multimap_tests! {HashVecMultimap, hashvecmultimap, ()}
+    serde_multimap_tests! {HashVecMultimap, hashvecmultimap, u8, 0, 1}
+    invert_multimap_tests! {HashVecMultimap, hashvecmultimap, 0, 1, 2}
+    multimap_trait_tests! {HashVecMultimap, ListMultimap, 0u8, 1}
+
+    #[test]
+    fn get_mut_does_not_require_clone_keys() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Key(u8);
+
+        let mut map = HashVecMultimap::new();
+        map.insert(Key(0), 1);
+        map.get_mut(Key(0)).unwrap().push(2);
+        assert!(map.get_mut(Key(1)).is_none());
+        assert_eq!(Some(&vec![1, 2]), map.get(&Key(0)));
+        assert_eq!(2, map.len());
+    }
 }
 
 mod index_set_multimap {
     use indexmap::indexmap;
     use indexmap::indexset;
+    use more_collections::index_set_multimap::Entry;
     use more_collections::indexsetmultimap;
     use more_collections::IndexSetMultimap;
 
     general_multimap_tests! {IndexSetMultimap, indexsetmultimap, indexmap, indexset}
+    capacity_multimap_tests! {IndexSetMultimap}
     set_multimap_tests! {IndexSetMultimap}
+    set_algebra_multimap_tests! {IndexSetMultimap, indexsetmultimap}
+    double_ended_multimap_tests! {indexsetmultimap}
+    drain_multimap_tests! {IndexSetMultimap, indexsetmultimap, (..)}
+    serde_multimap_tests! {IndexSetMultimap, indexsetmultimap, u8, 0, 1}
+    invert_multimap_tests! {IndexSetMultimap, indexsetmultimap, 0, 1, 2}
+    multimap_trait_tests! {IndexSetMultimap, SetMultimap, 0u8, 1}
+
+    #[test]
+    fn union_and_intersection_follow_order_of_self() {
+        let left = indexsetmultimap! {
+            1 => { 3 },
+            0 => { 2, 1 }
+        };
+        let right = indexsetmultimap! {
+            0 => { 1, 2, 4 }
+        };
+        assert_eq!(
+            vec![(&1, &3), (&0, &2), (&0, &1), (&0, &4)],
+            left.union(&right).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(&0, &2), (&0, &1)],
+            left.intersection(&right).collect::<Vec<_>>()
+        );
+    }
     index_multimap_tests! {IndexSetMultimap, indexsetmultimap, indexset}
 
+    #[test]
+    fn invert_follows_first_occurrence_of_values() {
+        let map = indexsetmultimap! {
+            0 => { 3, 1 },
+            2 => { 1, 4 }
+        };
+        let inverted = map.invert();
+        assert_eq!(vec![&3, &1, &4], inverted.keys().collect::<Vec<_>>());
+        assert_eq!(
+            vec![(&3, &0), (&1, &0), (&1, &2), (&4, &2)],
+            inverted.iter().collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn insert_full_returns_correct_values() {
         let mut map = indexsetmultimap! {
@@ -943,14 +2722,80 @@ mod index_set_multimap {
     }
 }
 
+mod index_small_vec_multimap {
+    use std::collections::hash_map::RandomState;
+
+    use indexmap::indexmap;
+    use more_collections::index_small_vec_multimap::Entry;
+    use more_collections::indexsmallvecmultimap;
+    use smallvec::SmallVec;
+
+    type IndexSmallVecMultimap<K, V, S = RandomState> =
+        more_collections::IndexSmallVecMultimap<K, V, 2, S>;
+
+    macro_rules! smallvec2 {
+        ($($value:expr),* $(,)?) => {
+            SmallVec::<[_; 2]>::from_iter([$($value),*])
+        };
+    }
+
+    general_multimap_tests! {IndexSmallVecMultimap, indexsmallvecmultimap, indexmap, smallvec2}
+    capacity_multimap_tests! {IndexSmallVecMultimap}
+    index_multimap_tests! {IndexSmallVecMultimap, indexsmallvecmultimap, smallvec2}
+    list_multimap_tests! {indexsmallvecmultimap}
+    double_ended_multimap_tests! {indexsmallvecmultimap}
+    drain_multimap_tests! {IndexSmallVecMultimap, indexsmallvecmultimap, (..)}
+    serde_multimap_tests! {IndexSmallVecMultimap, indexsmallvecmultimap, u8, 0, 1}
+    invert_multimap_tests! {IndexSmallVecMultimap, indexsmallvecmultimap, 0, 1, 2}
+    multimap_trait_tests! {IndexSmallVecMultimap, ListMultimap, 0u8, 1}
+
+    #[test]
+    fn values_spill_beyond_inline_capacity() {
+        let mut map = indexsmallvecmultimap! {
+            0 => { 1, 2 },
+            1 => { 3 }
+        };
+        assert!(!map.get(&0).unwrap().spilled());
+
+        map.insert(0, 2);
+        map.insert(0, 4);
+        assert!(map.get(&0).unwrap().spilled());
+        assert_eq!(Some(&smallvec2! { 1, 2, 2, 4 }), map.get(&0));
+        assert_eq!(5, map.len());
+
+        assert_eq!(Some(2), map.shift_remove(&0, &2));
+        assert_eq!(Some(&smallvec2! { 1, 2, 4 }), map.get(&0));
+        assert_eq!(4, map.len());
+    }
+}
 mod index_vec_multimap {
     use indexmap::indexmap;
+    use more_collections::index_vec_multimap::Entry;
     use more_collections::indexvecmultimap;
     use more_collections::IndexVecMultimap;
 
     general_multimap_tests! {IndexVecMultimap, indexvecmultimap, indexmap, vec}
+    capacity_multimap_tests! {IndexVecMultimap}
     index_multimap_tests! {IndexVecMultimap, indexvecmultimap, vec}
 
+    #[test]
+    fn invert_keeps_duplicates() {
+        let map = indexvecmultimap! {
+            0 => { 3, 1, 3 },
+            2 => { 1 }
+        };
+        let inverted = map.inverted();
+        assert_eq!(vec![&3, &1], inverted.keys().collect::<Vec<_>>());
+        assert_eq!(Some(&vec![0, 0]), inverted.get(&3));
+        assert_eq!(Some(&vec![0, 2]), inverted.get(&1));
+    }
+    list_multimap_tests! {indexvecmultimap}
+    double_ended_multimap_tests! {indexvecmultimap}
+    drain_multimap_tests! {IndexVecMultimap, indexvecmultimap, (..)}
+    serde_multimap_tests! {IndexVecMultimap, indexvecmultimap, u8, 0, 1}
+    invert_multimap_tests! {IndexVecMultimap, indexvecmultimap, 0, 1, 2}
+    multimap_trait_tests! {IndexVecMultimap, ListMultimap, 0u8, 1}
+
     #[test]
     fn insert_full_returns_correct_values() {
         let mut map = indexvecmultimap! {
@@ -971,4 +2816,213 @@ mod index_vec_multimap {
         assert_eq!((0, 3), map.insert_full(0, 2));
         assert_eq!(9, map.len());
     }
+    #[test]
+    fn get_mut_supports_sorting_and_dedup_of_values() {
+        let mut map = indexvecmultimap! {
+            0 => {3, 1, 3, 2},
+            1 => {4}
+        };
+        {
+            let mut values = map.get_mut(0).unwrap();
+            values.sort_unstable();
+            values.dedup();
+        }
+        assert_eq!(Some(&vec![1, 2, 3]), map.get(&0));
+        assert_eq!(4, map.len());
+    }
+}
+
+mod vec_set_multimap {
+    use more_collections::vec_set_multimap::Entry;
+    use more_collections::vecsetmultimap;
+    use more_collections::VecSetMultimap;
+
+    index_key_multimap_tests! {VecSetMultimap, vecsetmultimap, 0usize, 1usize, 2usize}
+    serde_multimap_tests! {VecSetMultimap, vecsetmultimap, usize, 0, 1}
+    invert_multimap_tests! {VecSetMultimap, vecsetmultimap, 0usize, 1, 2}
+    multimap_trait_tests! {VecSetMultimap, SetMultimap, 0usize, 1}
+
+    #[test]
+    fn insert_ignores_duplicates() {
+        let mut map = VecSetMultimap::new();
+        assert!(map.insert(7usize, 1));
+        assert!(!map.insert(7, 1));
+        assert_eq!(1, map.len());
+        assert_eq!(8, map.key_capacity());
+    }
+
+    #[test]
+    fn try_map_values_in_place_merges_equal_values() {
+        let mut map = vecsetmultimap! {
+            0 => { 1, 2, 3 },
+            2 => { 4 }
+        };
+        let result: Result<(), ()> = map.try_map_values_in_place(|key, value| {
+            *value = *value / 2 + key;
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+        assert_eq!(vecsetmultimap! { 0 => { 0, 1 }, 2 => { 4 } }, map);
+        assert_eq!(3, map.len());
+    }
+}
+
+mod vec_vec_multimap {
+    use more_collections::vec_vec_multimap::Entry;
+    use more_collections::vecvecmultimap;
+    use more_collections::VecMap;
+    use more_collections::VecVecMultimap;
+
+    index_key_multimap_tests! {VecVecMultimap, vecvecmultimap, 0usize, 1usize, 2usize}
+    serde_multimap_tests! {VecVecMultimap, vecvecmultimap, usize, 0, 1}
+    invert_multimap_tests! {VecVecMultimap, vecvecmultimap, 0usize, 1, 2}
+    multimap_trait_tests! {VecVecMultimap, ListMultimap, 0usize, 1}
+
+    #[test]
+    fn insert_allows_duplicates() {
+        let mut map = VecVecMultimap::with_key_capacity(1);
+        map.insert(3usize, 1);
+        map.insert(3, 1);
+        assert_eq!(2, map.len());
+        assert_eq!(Some(&vec![1, 1]), map.get(3));
+    }
+
+    #[test]
+    fn equality_test_counts_duplicate_values() {
+        let a = vecvecmultimap! {0 => { 1, 1, 2 }};
+        let b = vecvecmultimap! {0 => { 1, 2, 2 }};
+        assert_ne!(a, b);
+        assert!(!a.eq_unordered(&b));
+
+        let c = vecvecmultimap! {0 => { 2, 1, 1 }};
+        assert_eq!(a, c);
+        assert!(a.eq_unordered(&c));
+        assert!(!a.eq_ordered(&c));
+        assert!(a.eq_ordered(&a.clone()));
+    }
+
+    #[test]
+    fn from_vec_map_drops_empty_values() {
+        let mut inner = VecMap::new();
+        inner.insert(0usize, vec![1, 2]);
+        inner.insert(1, vec![]);
+        let map = VecVecMultimap::from(inner);
+        assert_eq!(2, map.len());
+        assert_eq!(1, map.keys_len());
+        assert!(!map.contains_key(1));
+    }
+
+    #[test]
+    fn iter_mut_follows_key_index_order() {
+        let mut map = vecvecmultimap! {
+            4 => { 3 },
+            1 => { 1, 2 }
+        };
+        let mut iter = map.iter_mut();
+        assert_eq!(3, iter.len());
+        assert_eq!(Some((1, &mut 1)), iter.next());
+        assert_eq!(2, iter.len());
+
+        map.values_mut().for_each(|value| *value *= 10);
+        for (key, value) in &mut map {
+            *value += key;
+        }
+        assert_eq!(vec![11, 21, 34], map.values().copied().collect::<Vec<_>>());
+    }
+}
+mod conversions {
+    use more_collections::hashsetmultimap;
+    use more_collections::hashvecmultimap;
+    use more_collections::indexsetmultimap;
+    use more_collections::indexvecmultimap;
+    use more_collections::HashSetMultimap;
+    use more_collections::HashVecMultimap;
+    use more_collections::IndexSetMultimap;
+    use more_collections::IndexVecMultimap;
+
+    #[test]
+    fn vec_to_set_removes_duplicates() {
+        let vec = indexvecmultimap! {
+            0 => { 1, 2, 1 },
+            3 => { 4 }
+        };
+        let set = IndexSetMultimap::from(vec.clone());
+        assert_eq!(3, set.len());
+        assert_eq!(
+            vec![(&0, &1), (&0, &2), (&3, &4)],
+            set.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec, set);
+        assert_eq!(set, HashSetMultimap::from(vec));
+    }
+
+    #[test]
+    fn set_to_vec_and_back_preserves_pairs() {
+        let set = indexsetmultimap! {
+            2 => { 1, 0 },
+            0 => { 3 }
+        };
+        let vec = IndexVecMultimap::from(set.clone());
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(set, IndexSetMultimap::from(vec.clone()));
+        assert_eq!(set, vec);
+        assert_eq!(vec, set);
+    }
+
+    #[test]
+    fn hash_and_index_multimaps_are_equal_with_same_pairs() {
+        let hash = hashsetmultimap! {
+            0 => { 1, 2 },
+            3 => { 4 }
+        };
+        let index = IndexSetMultimap::from(hash.clone());
+        assert_eq!(hash, index);
+        assert_eq!(index, hash);
+        assert_eq!(hash, HashSetMultimap::from(index.clone()));
+        assert_ne!(hash, indexsetmultimap! { 0 => { 1, 2 }, 3 => { 5 } });
+
+        let hash = hashvecmultimap! {
+            0 => { 1, 1, 2 }
+        };
+        let index = IndexVecMultimap::from(hash.clone());
+        assert_eq!(hash, index);
+        assert_eq!(index, hash);
+        assert_ne!(hash, indexvecmultimap! { 0 => { 1, 2, 2 } });
+        assert_eq!(hash, HashVecMultimap::from(index));
+    }
+
+    #[test]
+    fn list_and_set_multimaps_compare_duplicates() {
+        let set = hashsetmultimap! {
+            0 => { 1, 2 }
+        };
+        assert_eq!(set, hashvecmultimap! { 0 => { 2, 1 } });
+        assert_ne!(set, hashvecmultimap! { 0 => { 1, 1 } });
+        assert_ne!(hashvecmultimap! { 0 => { 1, 1 } }, set);
+        assert_ne!(set, indexvecmultimap! { 0 => { 1 } });
+        assert_ne!(set, indexvecmultimap! { 0 => { 1, 2 }, 3 => { 4 } });
+    }
+
+    #[test]
+    fn list_and_set_multimaps_ignore_duplicated_list_values() {
+        let list = hashvecmultimap! {
+            0 => { 1, 1 }
+        };
+        let set = hashsetmultimap! {
+            0 => { 1 }
+        };
+        assert_eq!(list, set);
+        assert_eq!(set, list);
+        assert_eq!(
+            indexvecmultimap! { 0 => { 1, 2, 1 } },
+            indexsetmultimap! { 0 => { 2, 1 } }
+        );
+        assert_eq!(
+            indexsetmultimap! { 0 => { 2, 1 } },
+            indexvecmultimap! { 0 => { 1, 2, 1 } }
+        );
+    }
 }
@@ -0,0 +1,396 @@
+#![cfg(test)]
+
+macro_rules! general_multiset_tests {
+    ($type:tt, $multiset_macro:tt) => {
+        #[test]
+        fn insert_counts_occurrences() {
+            let mut set = $type::new();
+            assert!(set.is_empty());
+
+            assert_eq!(0, set.insert("a"));
+            assert_eq!(1, set.insert("a"));
+            assert_eq!(0, set.insert("b"));
+
+            assert_eq!(3, set.len());
+            assert_eq!(2, set.distinct_len());
+            assert_eq!(2, set.count(&"a"));
+            assert_eq!(1, set.count(&"b"));
+            assert_eq!(0, set.count(&"c"));
+            assert!(set.contains(&"a"));
+            assert!(!set.contains(&"c"));
+        }
+
+        #[test]
+        fn insert_n() {
+            let mut set = $type::new();
+            assert_eq!(0, set.insert_n(1, 3));
+            assert_eq!(3, set.insert_n(1, 2));
+            assert_eq!(5, set.len());
+            assert_eq!(5, set.count(&1));
+
+            assert_eq!(0, set.insert_n(2, 0));
+            assert!(!set.contains(&2));
+            assert_eq!(1, set.distinct_len());
+        }
+
+        #[test]
+        fn remove() {
+            let mut set = $multiset_macro! {1, 1, 2};
+            assert!(set.remove(&1));
+            assert_eq!(2, set.len());
+            assert_eq!(1, set.count(&1));
+
+            assert!(set.remove(&1));
+            assert!(!set.contains(&1));
+            assert_eq!(1, set.distinct_len());
+
+            assert!(!set.remove(&1));
+            assert!(!set.remove(&3));
+            assert_eq!(1, set.len());
+        }
+
+        #[test]
+        fn remove_n() {
+            let mut set = $multiset_macro! {1, 1, 1, 2};
+            assert_eq!(2, set.remove_n(&1, 2));
+            assert_eq!(1, set.count(&1));
+            assert_eq!(2, set.len());
+
+            assert_eq!(1, set.remove_n(&1, 5));
+            assert!(!set.contains(&1));
+            assert_eq!(1, set.len());
+
+            assert_eq!(0, set.remove_n(&3, 1));
+            assert_eq!(0, set.remove_n(&2, 0));
+            assert_eq!(1, set.len());
+        }
+
+        #[test]
+        fn remove_all() {
+            let mut set = $multiset_macro! {1, 1, 1, 2};
+            assert_eq!(3, set.remove_all(&1));
+            assert_eq!(0, set.remove_all(&1));
+            assert_eq!(1, set.len());
+            assert_eq!($multiset_macro! {2}, set);
+        }
+
+        #[test]
+        fn retain() {
+            let mut set = $multiset_macro! {1, 1, 2, 3, 3, 3};
+            set.retain(|value, count| *value == 2 || count > 2);
+            assert_eq!($multiset_macro! {2, 3, 3, 3}, set);
+            assert_eq!(4, set.len());
+        }
+
+        #[test]
+        fn clear() {
+            let mut set = $multiset_macro! {1, 1, 2};
+            set.clear();
+            assert!(set.is_empty());
+            assert_eq!(0, set.distinct_len());
+        }
+
+        #[test]
+        fn iter_repeats_values() {
+            let set = $multiset_macro! {1, 2, 1, 3, 1};
+            let mut values = set.iter().copied().collect::<Vec<_>>();
+            values.sort_unstable();
+            assert_eq!(vec![1, 1, 1, 2, 3], values);
+
+            let mut iter = set.iter();
+            assert_eq!(5, iter.len());
+            iter.next();
+            assert_eq!(4, iter.len());
+            assert_eq!(4, iter.clone().count());
+            assert_eq!(5, (&set).into_iter().count());
+        }
+
+        #[test]
+        fn iter_counts_and_into_counts() {
+            let set = $multiset_macro! {1, 2, 1};
+            let counts = set
+                .iter_counts()
+                .map(|(value, count)| (*value, count))
+                .collect::<std::collections::HashMap<_, _>>();
+            assert_eq!(maplit::hashmap! {1 => 2, 2 => 1}, counts);
+            assert_eq!(2, set.iter_counts().len());
+
+            let counts = set
+                .into_counts()
+                .collect::<std::collections::HashMap<_, _>>();
+            assert_eq!(maplit::hashmap! {1 => 2, 2 => 1}, counts);
+        }
+
+        #[test]
+        fn most_common() {
+            let set = $multiset_macro! {"a", "b", "b", "c", "c", "c"};
+            assert_eq!(vec![(&"c", 3), (&"b", 2)], set.most_common(2));
+            assert_eq!(3, set.most_common(10).len());
+            assert!(set.most_common(0).is_empty());
+        }
+
+        #[test]
+        fn algebra() {
+            let left = $multiset_macro! {1, 1, 1, 2, 3};
+            let right = $multiset_macro! {1, 2, 2, 4};
+
+            assert_eq!(
+                $multiset_macro! {1, 1, 1, 1, 2, 2, 2, 3, 4},
+                left.sum(&right)
+            );
+            assert_eq!($multiset_macro! {1, 1, 1, 2, 2, 3, 4}, left.union(&right));
+            assert_eq!($multiset_macro! {1, 2}, left.intersection(&right));
+            assert_eq!($multiset_macro! {1, 1, 3}, left.difference(&right));
+            assert_eq!($multiset_macro! {2, 4}, right.difference(&left));
+
+            assert_eq!(left.sum(&right), &left + &right);
+            assert_eq!(left.union(&right), &left | &right);
+            assert_eq!(left.intersection(&right), &left & &right);
+            assert_eq!(left.difference(&right), &left - &right);
+            assert_eq!(9, (&left + &right).len());
+            assert_eq!(2, (&left & &right).len());
+        }
+
+        #[test]
+        fn from_iterator_and_extend() {
+            let mut set = [1, 2, 1].into_iter().collect::<$type<_>>();
+            assert_eq!($multiset_macro! {1, 1, 2}, set);
+
+            set.extend(vec![2, 3]);
+            set.extend(&[3, 3]);
+            assert_eq!($multiset_macro! {1, 1, 2, 2, 3, 3, 3}, set);
+            assert_eq!(7, set.len());
+        }
+
+        #[test]
+        fn eq_ignores_insertion_order() {
+            assert_eq!($multiset_macro! {1, 2, 1}, $multiset_macro! {2, 1, 1});
+            assert_ne!($multiset_macro! {1, 2, 1}, $multiset_macro! {1, 2});
+            assert_eq!($type::<i32>::default(), $multiset_macro! {});
+        }
+    };
+}
+
+macro_rules! capacity_multiset_tests {
+    ($type:tt) => {
+        #[test]
+        fn capacity() {
+            let mut set = $type::<i32>::with_capacity(10);
+            assert!(set.capacity() >= 10);
+            set.insert(1);
+            set.shrink_to_fit();
+            set.reserve(5);
+            assert!(set.capacity() >= 6);
+        }
+    };
+}
+
+mod btree_multiset {
+    use more_collections::btreemultiset;
+    use more_collections::BTreeMultiset;
+
+    general_multiset_tests! {BTreeMultiset, btreemultiset}
+
+    #[test]
+    fn iter_is_sorted() {
+        let set = btreemultiset! {3, 1, 3, 2};
+        assert_eq!(vec![1, 2, 3, 3], set.iter().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut set = BTreeMultiset::new();
+        assert_eq!(None, set.first());
+        assert_eq!(None, set.last());
+
+        set.extend([5, 2, 9, 2]);
+        assert_eq!(Some(&2), set.first());
+        assert_eq!(Some(&9), set.last());
+    }
+
+    #[test]
+    fn range() {
+        let set = btreemultiset! {1, 2, 2, 3, 4, 4, 4, 5};
+        assert_eq!(
+            vec![(&2, 2), (&3, 1), (&4, 3)],
+            set.range(2..5).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(&5, 1), (&4, 3)],
+            set.range(4..).rev().collect::<Vec<_>>()
+        );
+        assert_eq!(6, set.count_range(2..5));
+        assert_eq!(8, set.count_range(..));
+        assert_eq!(0, set.count_range(6..));
+    }
+
+    #[test]
+    fn nth_smallest() {
+        let set = btreemultiset! {4, 1, 4, 2, 4};
+        let expected = [1, 2, 4, 4, 4];
+        for (index, value) in expected.iter().enumerate() {
+            assert_eq!(Some(value), set.nth_smallest(index));
+        }
+        assert_eq!(None, set.nth_smallest(5));
+        assert_eq!(None, BTreeMultiset::<i32>::new().nth_smallest(0));
+    }
+
+    #[test]
+    fn count_less_than() {
+        let set = btreemultiset! {10, 20, 20, 30, 40};
+        assert_eq!(0, set.count_less_than(&10));
+        assert_eq!(1, set.count_less_than(&20));
+        assert_eq!(3, set.count_less_than(&25));
+        assert_eq!(5, set.count_less_than(&100));
+    }
+}
+
+mod hash_multiset {
+    use more_collections::hashmultiset;
+    use more_collections::HashMultiset;
+
+    general_multiset_tests! {HashMultiset, hashmultiset}
+    capacity_multiset_tests! {HashMultiset}
+}
+
+mod index_multiset {
+    use more_collections::indexmultiset;
+    use more_collections::IndexMultiset;
+
+    general_multiset_tests! {IndexMultiset, indexmultiset}
+    capacity_multiset_tests! {IndexMultiset}
+
+    #[test]
+    fn preserves_insertion_order() {
+        let mut set = indexmultiset! {3, 1, 3, 2};
+        assert_eq!(vec![3, 3, 1, 2], set.iter().copied().collect::<Vec<_>>());
+
+        set.remove_all(&1);
+        assert_eq!(vec![(3, 2), (2, 1)], set.into_counts().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn most_common_ties_keep_insertion_order() {
+        let set = indexmultiset! {"b", "a", "a", "b", "c"};
+        assert_eq!(vec![(&"b", 2), (&"a", 2), (&"c", 1)], set.most_common(3));
+    }
+}
+
+mod vec_multiset {
+    use more_collections::vecmultiset;
+    use more_collections::VecMultiset;
+
+    #[test]
+    fn insert_and_count() {
+        let mut set = VecMultiset::<usize>::new();
+        assert!(set.is_empty());
+
+        assert_eq!(0, set.insert(3));
+        assert_eq!(1, set.insert(3));
+        assert_eq!(0, set.insert_n(1, 4));
+        assert_eq!(0, set.insert_n(5, 0));
+
+        assert_eq!(6, set.len());
+        assert_eq!(2, set.distinct_len());
+        assert_eq!(2, set.count(3));
+        assert_eq!(4, set.count(1));
+        assert_eq!(0, set.count(5));
+        assert!(!set.contains(5));
+    }
+
+    #[test]
+    fn increment_and_decrement() {
+        let mut set = VecMultiset::<usize>::new();
+        assert_eq!(1, set.increment(2));
+        assert_eq!(2, set.increment(2));
+        assert_eq!(2, set.len());
+
+        assert_eq!(Some(1), set.decrement(2));
+        assert_eq!(Some(0), set.decrement(2));
+        assert!(!set.contains(2));
+        assert!(set.is_empty());
+        assert_eq!(0, set.distinct_len());
+
+        assert_eq!(None, set.decrement(2));
+        assert_eq!(None, set.decrement(100));
+    }
+
+    #[test]
+    fn remove() {
+        let mut set = vecmultiset! {1, 1, 1, 2};
+        assert!(set.remove(1));
+        assert_eq!(2, set.remove_n(1, 5));
+        assert!(!set.contains(1));
+        assert!(!set.remove(1));
+        assert_eq!(1, set.remove_all(2));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn retain() {
+        let mut set = vecmultiset! {0, 1, 1, 2, 2, 2};
+        set.retain(|key, count| key == 0 || count > 1);
+        assert_eq!(vecmultiset! {0, 1, 1, 2, 2, 2}, set);
+
+        set.retain(|_, count| count > 2);
+        assert_eq!(vecmultiset! {2, 2, 2}, set);
+        assert_eq!(3, set.len());
+    }
+
+    #[test]
+    fn iterates_in_key_order() {
+        let set = vecmultiset! {4, 1, 4, 0};
+        assert_eq!(vec![0, 1, 4, 4], set.iter().collect::<Vec<_>>());
+        assert_eq!(4, set.iter().len());
+        assert_eq!(
+            vec![(0, 1), (1, 1), (4, 2)],
+            set.iter_counts().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(4, 2), (1, 1), (0, 1)],
+            set.clone().into_counts().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(4, (&set).into_iter().count());
+        assert_eq!("{0: 1, 1: 1, 4: 2}", format!("{set:?}"));
+    }
+
+    #[test]
+    fn most_common() {
+        let set = vecmultiset! {3, 1, 1, 2, 2};
+        assert_eq!(vec![(1, 2), (2, 2)], set.most_common(2));
+        assert_eq!(vec![(1, 2), (2, 2), (3, 1)], set.most_common(5));
+    }
+
+    #[test]
+    fn element_wise_operations() {
+        let left = vecmultiset! {0, 0, 0, 1, 2};
+        let right = vecmultiset! {0, 1, 1, 3};
+
+        assert_eq!(vecmultiset! {0, 0, 0, 0, 1, 1, 1, 2, 3}, &left + &right);
+        assert_eq!(vecmultiset! {0, 0, 0, 1, 1, 2, 3}, &left | &right);
+        assert_eq!(vecmultiset! {0, 1}, &left & &right);
+        assert_eq!(vecmultiset! {0, 0, 2}, &left - &right);
+        assert_eq!(vecmultiset! {1, 3}, right.difference(&left));
+
+        assert_eq!(9, left.sum(&right).len());
+        assert_eq!(7, left.union(&right).len());
+        assert_eq!(2, left.intersection(&right).len());
+        assert_eq!(3, left.difference(&right).len());
+    }
+
+    #[test]
+    fn eq_ignores_capacity() {
+        let mut set = VecMultiset::with_capacity(100);
+        assert!(set.capacity() >= 100);
+        set.insert(1);
+        assert_eq!(vecmultiset! {1}, set);
+        assert_ne!(vecmultiset! {1, 1}, set);
+    }
+
+    #[test]
+    fn from_iterator_and_extend() {
+        let mut set = [2, 0, 2].into_iter().collect::<VecMultiset<usize>>();
+        set.extend(&[0, 1]);
+        assert_eq!(vecmultiset! {0, 0, 1, 2, 2}, set);
+    }
+}